@@ -0,0 +1,71 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+use poker_api::equity::{self, EquityOptions};
+use poker_api::evaluator;
+use poker_api::models::{Card, Game};
+use poker_api::notation::parse_cards;
+
+/// Mãos de `size` cartas sorteadas com seed fixa, para comparar execuções.
+fn random_hands(count: usize, size: usize) -> Vec<Vec<Card>> {
+    let mut rng = ChaCha20Rng::seed_from_u64(2024);
+    (0..count)
+        .map(|_| {
+            let mut deck = Game::create_deck();
+            deck.shuffle(&mut rng);
+            deck.truncate(size);
+            deck
+        })
+        .collect()
+}
+
+fn bench_evaluator(c: &mut Criterion) {
+    for (name, size) in [("five_cards", 5), ("six_cards", 6), ("seven_cards", 7)] {
+        let hands = random_hands(10_000, size);
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Elements(hands.len() as u64));
+
+        group.bench_function("hand_strength", |b| {
+            b.iter(|| {
+                for hand in &hands {
+                    black_box(evaluator::hand_strength(black_box(hand)));
+                }
+            })
+        });
+        group.bench_function("evaluate", |b| {
+            b.iter(|| {
+                for hand in &hands {
+                    black_box(evaluator::evaluate(black_box(hand)));
+                }
+            })
+        });
+        group.finish();
+    }
+}
+
+/// As simulações de equity são o principal consumidor do avaliador.
+fn bench_equity(c: &mut Criterion) {
+    let hands = [parse_cards("AhAd").unwrap(), parse_cards("KsKc").unwrap()];
+    let flop = parse_cards("Qh7s2d").unwrap();
+    let options = EquityOptions {
+        samples: Some(10_000),
+        seed: Some(2024),
+    };
+
+    let mut group = c.benchmark_group("equity");
+    group.sample_size(10);
+    group.bench_function("preflop_monte_carlo_10k", |b| {
+        b.iter(|| equity::calculate(black_box(&hands), &[], &[], &options).unwrap())
+    });
+    group.bench_function("flop_exhaustive", |b| {
+        b.iter(|| {
+            equity::calculate(black_box(&hands), &flop, &[], &EquityOptions::default()).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_evaluator, bench_equity);
+criterion_main!(benches);
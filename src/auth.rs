@@ -0,0 +1,112 @@
+use axum::http::{header, HeaderMap};
+use sha2::{Digest, Sha256};
+
+use crate::{error::GameError, models::Room};
+
+/// Hash guardado no lugar do token do jogador. Só o jogador conhece o token;
+/// a sala, o banco e o journal guardam apenas o SHA-256 dele.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Hash de um token lido do armazenamento. Bancos e journals antigos guardavam
+/// o token em texto puro (um UUID), que é convertido na carga.
+pub fn stored_token_hash(stored: String) -> String {
+    let is_hash = stored.len() == 64 && stored.bytes().all(|b| b.is_ascii_hexdigit());
+    if is_hash {
+        stored
+    } else {
+        hash_token(&stored)
+    }
+}
+
+/// Compara dois valores sem parar no primeiro byte diferente, para que o tempo
+/// da comparação não revele o prefixo correto.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Token do header `Authorization: Bearer <token>`.
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+/// Confere o token de um jogador da sala.
+pub fn verify_player(room: &Room, player_id: &str, token: Option<&str>) -> Result<(), GameError> {
+    let (Some(expected), Some(token)) = (room.player_tokens.get(player_id), token) else {
+        return Err(GameError::Unauthorized);
+    };
+    if !constant_time_eq(hash_token(token).as_bytes(), expected.as_bytes()) {
+        return Err(GameError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Jogador da sala dono do token, se houver.
+pub fn player_with_token(room: &Room, token: &str) -> Option<String> {
+    let hash = hash_token(token);
+    room.player_tokens
+        .iter()
+        .find(|(_, expected)| constant_time_eq(hash.as_bytes(), expected.as_bytes()))
+        .map(|(player_id, _)| player_id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn room_with_token(player_id: &str, token: &str) -> Room {
+        Room {
+            id: "sala".to_string(),
+            creator_id: player_id.to_string(),
+            players: HashMap::new(),
+            game: None,
+            max_players: 6,
+            websocket_senders: HashMap::new(),
+            player_tokens: HashMap::from([(player_id.to_string(), hash_token(token))]),
+            deck_source: Default::default(),
+            fairness: Default::default(),
+            rules: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_verify_player_token() {
+        let room = room_with_token("alice", "segredo");
+        assert_eq!(verify_player(&room, "alice", Some("segredo")), Ok(()));
+        assert_eq!(
+            player_with_token(&room, "segredo").as_deref(),
+            Some("alice")
+        );
+        assert_eq!(player_with_token(&room, "outro"), None);
+        for (player_id, token) in [
+            ("alice", None),
+            ("alice", Some("outro")),
+            ("alice", Some(hash_token("segredo").as_str())),
+            ("bob", Some("segredo")),
+        ] {
+            assert_eq!(
+                verify_player(&room, player_id, token),
+                Err(GameError::Unauthorized)
+            );
+        }
+    }
+
+    #[test]
+    fn test_stored_tokens_are_hashed() {
+        let token = "0b0f4f1e-8d55-4d8e-9a8e-2f5b7f1c3a11";
+        let hash = hash_token(token);
+        assert_eq!(hash.len(), 64);
+        assert!(!hash.contains(token));
+        // Tokens em texto puro de bancos antigos viram hash; hashes ficam iguais
+        assert_eq!(stored_token_hash(token.to_string()), hash);
+        assert_eq!(stored_token_hash(hash.clone()), hash);
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+    }
+}
@@ -0,0 +1,566 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::GameError;
+use crate::evaluator::{hand_strength, HandStrength};
+use crate::models::*;
+use crate::notation::parse_cards;
+use crate::range::{Range, WeightedCombo};
+
+/// Acima deste número de avaliações (combinações × boards × jogadores) a
+/// enumeração completa fica cara e o cálculo passa a ser por Monte Carlo.
+pub const EXHAUSTIVE_LIMIT: u64 = 1_000_000;
+pub const DEFAULT_SAMPLES: u32 = 100_000;
+pub const MAX_SAMPLES: u32 = 1_000_000;
+/// Tentativas de sortear combinações sem cartas repetidas entre os ranges.
+const MAX_DEAL_ATTEMPTS: u32 = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EquityMethod {
+    Exhaustive,
+    MonteCarlo,
+}
+
+/// Frações dos boards em que o jogador ganha sozinho (`win`) ou divide o pote
+/// (`tie`). `equity` soma as vitórias e a parte de cada empate. Com ranges,
+/// cada combinação pesa conforme o seu peso no range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerEquity {
+    /// Hole cards, quando o jogador tem uma mão exata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hand: Option<Vec<Card>>,
+    /// Combinações do range que não colidem com o board e as cartas mortas
+    pub combos: usize,
+    pub win: f64,
+    pub tie: f64,
+    pub equity: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityResult {
+    pub method: EquityMethod,
+    /// Boards avaliados: todos os possíveis ou as amostras sorteadas.
+    pub samples: u64,
+    pub board: Vec<Card>,
+    pub players: Vec<PlayerEquity>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EquityOptions {
+    /// Amostras do Monte Carlo (`DEFAULT_SAMPLES` se não informado).
+    pub samples: Option<u32>,
+    /// Seed do Monte Carlo, para resultados reproduzíveis.
+    pub seed: Option<u64>,
+}
+
+/// Atende ao `POST /tools/equity`: lê as mãos ou ranges e as cartas em
+/// notação padrão e calcula a equity de cada jogador.
+pub fn from_request(request: &EquityRequest) -> Result<EquityResult, GameError> {
+    let parse = |notation: &str| parse_cards(notation).map_err(|_| GameError::InvalidEquityRequest);
+    let ranges = request
+        .hands
+        .iter()
+        .map(|range| range.parse::<Range>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| GameError::InvalidEquityRequest)?;
+    let board = parse(&request.board)?;
+    let dead = parse(&request.dead)?;
+    let options = EquityOptions {
+        samples: request.samples,
+        seed: request.seed,
+    };
+
+    calculate_ranges(&ranges, &board, &dead, &options)
+}
+
+/// Equity de 2 a 10 mãos exatas. Ver `calculate_ranges`.
+pub fn calculate(
+    hands: &[Vec<Card>],
+    board: &[Card],
+    dead: &[Card],
+    options: &EquityOptions,
+) -> Result<EquityResult, GameError> {
+    let ranges = hands
+        .iter()
+        .map(|hand| {
+            let cards: [Card; 2] = hand
+                .clone()
+                .try_into()
+                .map_err(|_| GameError::InvalidEquityRequest)?;
+            Ok(Range::from(cards))
+        })
+        .collect::<Result<Vec<_>, GameError>>()?;
+
+    calculate_ranges(&ranges, board, dead, options)
+}
+
+/// Equity de 2 a 10 ranges com o board parcial dado, descontando as cartas
+/// mortas. As combinações que colidem com o board ou entre si são
+/// descartadas. Enumera todas as combinações e boards quando possível e
+/// sorteia caso contrário.
+pub fn calculate_ranges(
+    ranges: &[Range],
+    board: &[Card],
+    dead: &[Card],
+    options: &EquityOptions,
+) -> Result<EquityResult, GameError> {
+    if !(2..=10).contains(&ranges.len())
+        || board.len() > 5
+        || options
+            .samples
+            .is_some_and(|samples| samples == 0 || samples > MAX_SAMPLES)
+    {
+        return Err(GameError::InvalidEquityRequest);
+    }
+
+    let known: Vec<Card> = board.iter().chain(dead).cloned().collect();
+    let deck: Vec<Card> = Game::create_deck()
+        .into_iter()
+        .filter(|card| !known.contains(card))
+        .collect();
+    // Cartas repetidas entre o board e as cartas mortas
+    if deck.len() + known.len() != 52 {
+        return Err(GameError::InvalidEquityRequest);
+    }
+
+    let ranges: Vec<Range> = ranges.iter().map(|range| range.without(&known)).collect();
+    let missing = 5 - board.len();
+    if ranges.iter().any(Range::is_empty) || deck.len() < missing + 2 * ranges.len() {
+        return Err(GameError::InvalidEquityRequest);
+    }
+
+    let mut tally = Tally::new(ranges.len());
+    let assignments = ranges
+        .iter()
+        .fold(1u64, |acc, range| acc.saturating_mul(range.len() as u64));
+    let boards = combinations((deck.len() - 2 * ranges.len()) as u64, missing as u64);
+    let evaluations = assignments
+        .saturating_mul(boards)
+        .saturating_mul(ranges.len() as u64);
+
+    let method = if options.samples.is_none() && evaluations <= EXHAUSTIVE_LIMIT {
+        let mut runout = Vec::with_capacity(missing);
+        for_each_assignment(&ranges, &mut Vec::new(), &mut |combos| {
+            let weight: f64 = combos.iter().map(|combo| combo.weight).product();
+            let rest: Vec<&Card> = deck
+                .iter()
+                .filter(|card| !combos.iter().any(|combo| combo.cards.contains(card)))
+                .collect();
+            for_each_combination(rest.len(), missing, |indices| {
+                runout.clear();
+                runout.extend(indices.iter().map(|&i| rest[i].clone()));
+                tally.add(combos, board, &runout, weight);
+            });
+        });
+        EquityMethod::Exhaustive
+    } else {
+        let mut rng = match options.seed {
+            Some(seed) => ChaCha20Rng::seed_from_u64(seed),
+            None => ChaCha20Rng::from_entropy(),
+        };
+        let pickers: Vec<WeightedPicker> = ranges.iter().map(WeightedPicker::new).collect();
+        let mut deck = deck;
+        let mut runout = Vec::with_capacity(missing);
+
+        for _ in 0..options.samples.unwrap_or(DEFAULT_SAMPLES) {
+            let Some(combos) = deal_combos(&ranges, &pickers, &mut rng) else {
+                break;
+            };
+            // Fisher-Yates parcial, pulando as cartas das combinações sorteadas
+            runout.clear();
+            let mut i = 0;
+            while runout.len() < missing {
+                let j = rng.gen_range(i..deck.len());
+                deck.swap(i, j);
+                if !combos.iter().any(|combo| combo.cards.contains(&deck[i])) {
+                    runout.push(deck[i].clone());
+                }
+                i += 1;
+            }
+            tally.add(&combos, board, &runout, 1.0);
+        }
+        EquityMethod::MonteCarlo
+    };
+
+    // Nenhuma combinação dos ranges cabe junto sem repetir cartas
+    if tally.samples == 0 {
+        return Err(GameError::InvalidEquityRequest);
+    }
+    Ok(tally.finish(&ranges, board, method))
+}
+
+/// Soma acumulada dos pesos de um range, para sortear combinações.
+struct WeightedPicker {
+    cumulative: Vec<f64>,
+}
+
+impl WeightedPicker {
+    fn new(range: &Range) -> Self {
+        let cumulative = range
+            .combos
+            .iter()
+            .scan(0.0, |total, combo| {
+                *total += combo.weight;
+                Some(*total)
+            })
+            .collect();
+        Self { cumulative }
+    }
+
+    fn pick<R: Rng>(&self, rng: &mut R) -> usize {
+        let total = self.cumulative.last().copied().unwrap_or_default();
+        let target = rng.gen::<f64>() * total;
+        self.cumulative
+            .partition_point(|&sum| sum <= target)
+            .min(self.cumulative.len() - 1)
+    }
+}
+
+/// Sorteia uma combinação de cada range sem cartas repetidas entre elas. Um
+/// sorteio com colisão é descartado por inteiro, o que mantém a distribuição
+/// conjunta proporcional ao produto dos pesos.
+fn deal_combos<'a, R: Rng>(
+    ranges: &'a [Range],
+    pickers: &[WeightedPicker],
+    rng: &mut R,
+) -> Option<Vec<&'a WeightedCombo>> {
+    'attempt: for _ in 0..MAX_DEAL_ATTEMPTS {
+        let mut combos: Vec<&WeightedCombo> = Vec::with_capacity(ranges.len());
+        for (range, picker) in ranges.iter().zip(pickers) {
+            let combo = &range.combos[picker.pick(rng)];
+            if combos
+                .iter()
+                .any(|other| combo.conflicts_with(&other.cards))
+            {
+                continue 'attempt;
+            }
+            combos.push(combo);
+        }
+        return Some(combos);
+    }
+    None
+}
+
+/// Chama `f` com cada escolha de uma combinação por range sem cartas
+/// repetidas.
+fn for_each_assignment<'a>(
+    ranges: &'a [Range],
+    chosen: &mut Vec<&'a WeightedCombo>,
+    f: &mut impl FnMut(&[&'a WeightedCombo]),
+) {
+    let Some(range) = ranges.get(chosen.len()) else {
+        f(chosen);
+        return;
+    };
+    for combo in &range.combos {
+        if chosen
+            .iter()
+            .any(|other| combo.conflicts_with(&other.cards))
+        {
+            continue;
+        }
+        chosen.push(combo);
+        for_each_assignment(ranges, chosen, f);
+        chosen.pop();
+    }
+}
+
+/// Soma ponderada de vitórias, empates e partes do pote ao longo dos boards.
+struct Tally {
+    cards: Vec<Card>,
+    strengths: Vec<HandStrength>,
+    wins: Vec<f64>,
+    ties: Vec<f64>,
+    shares: Vec<f64>,
+    total: f64,
+    samples: u64,
+}
+
+impl Tally {
+    fn new(players: usize) -> Self {
+        Self {
+            cards: Vec::with_capacity(7),
+            strengths: vec![HandStrength::default(); players],
+            wins: vec![0.0; players],
+            ties: vec![0.0; players],
+            shares: vec![0.0; players],
+            total: 0.0,
+            samples: 0,
+        }
+    }
+
+    fn add(&mut self, combos: &[&WeightedCombo], board: &[Card], runout: &[Card], weight: f64) {
+        for (combo, strength) in combos.iter().zip(&mut self.strengths) {
+            self.cards.clear();
+            self.cards
+                .extend(combo.cards.iter().chain(board).chain(runout).cloned());
+            *strength = hand_strength(&self.cards);
+        }
+
+        let best = self.strengths.iter().max().copied().unwrap_or_default();
+        let winners = self.strengths.iter().filter(|&&s| s == best).count();
+        for (i, strength) in self.strengths.iter().enumerate() {
+            if *strength != best {
+                continue;
+            }
+            if winners == 1 {
+                self.wins[i] += weight;
+            } else {
+                self.ties[i] += weight;
+            }
+            self.shares[i] += weight / winners as f64;
+        }
+        self.total += weight;
+        self.samples += 1;
+    }
+
+    fn finish(self, ranges: &[Range], board: &[Card], method: EquityMethod) -> EquityResult {
+        let players = ranges
+            .iter()
+            .enumerate()
+            .map(|(i, range)| PlayerEquity {
+                hand: match range.combos.as_slice() {
+                    [combo] => Some(combo.cards.to_vec()),
+                    _ => None,
+                },
+                combos: range.len(),
+                win: self.wins[i] / self.total,
+                tie: self.ties[i] / self.total,
+                equity: self.shares[i] / self.total,
+            })
+            .collect();
+
+        EquityResult {
+            method,
+            samples: self.samples,
+            board: board.to_vec(),
+            players,
+        }
+    }
+}
+
+fn combinations(n: u64, k: u64) -> u64 {
+    (0..k).fold(1u64, |acc, i| acc.saturating_mul(n - i) / (i + 1))
+}
+
+/// Chama `f` com cada combinação de `k` índices em `0..n`, em ordem
+/// lexicográfica.
+fn for_each_combination(n: usize, k: usize, mut f: impl FnMut(&[usize])) {
+    let mut indices: Vec<usize> = (0..k).collect();
+    loop {
+        f(&indices);
+
+        // Avança o último índice que ainda tem espaço e reinicia os seguintes
+        let Some(i) = (0..k).rev().find(|&i| indices[i] < n - k + i) else {
+            return;
+        };
+        indices[i] += 1;
+        for j in i + 1..k {
+            indices[j] = indices[j - 1] + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hands(notation: &[&str]) -> Vec<Vec<Card>> {
+        notation
+            .iter()
+            .map(|hand| parse_cards(hand).unwrap())
+            .collect()
+    }
+
+    fn board(notation: &str) -> Vec<Card> {
+        parse_cards(notation).unwrap()
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(combinations(48, 5), 1_712_304);
+        assert_eq!(combinations(45, 2), 990);
+        assert_eq!(combinations(44, 0), 1);
+
+        let mut count = 0;
+        for_each_combination(6, 3, |indices| {
+            assert!(indices.windows(2).all(|w| w[0] < w[1]));
+            count += 1;
+        });
+        assert_eq!(count, 20);
+    }
+
+    #[test]
+    fn test_exhaustive_on_the_turn() {
+        // KK só vence com um dos dois reis restantes entre 44 cartas
+        let result = calculate(
+            &hands(&["AhAd", "KcKs"]),
+            &board("2c 7d 9h Js"),
+            &[],
+            &EquityOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.method, EquityMethod::Exhaustive);
+        assert_eq!(result.samples, 44);
+        assert_eq!(result.players[0].win, 42.0 / 44.0);
+        assert_eq!(result.players[1].win, 2.0 / 44.0);
+        assert_eq!(result.players[0].tie, 0.0);
+    }
+
+    #[test]
+    fn test_split_pots_share_equity() {
+        // Broadway no board: todo river é empate
+        let result = calculate(
+            &hands(&["2c2d", "3c3d", "4c4d"]),
+            &board("Ah Kd Qs Jc"),
+            &[],
+            &EquityOptions::default(),
+        )
+        .unwrap();
+        assert!(result.players[0].tie > 0.0);
+        for player in &result.players {
+            let total = player.win + player.tie;
+            assert!(total > 0.0 && total <= 1.0);
+        }
+        let equity: f64 = result.players.iter().map(|p| p.equity).sum();
+        assert!((equity - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dead_cards_are_removed() {
+        let result = calculate(
+            &hands(&["AhAd", "KcKs"]),
+            &board("2c 7d 9h Js"),
+            &board("Kd Kh"),
+            &EquityOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.samples, 42);
+        assert_eq!(result.players[0].equity, 1.0);
+    }
+
+    #[test]
+    fn test_monte_carlo_preflop() {
+        let options = EquityOptions {
+            samples: Some(20_000),
+            seed: Some(7),
+        };
+        let result = calculate(&hands(&["AhAd", "KcKs"]), &[], &[], &options).unwrap();
+
+        assert_eq!(result.method, EquityMethod::MonteCarlo);
+        assert_eq!(result.samples, 20_000);
+        // AA x KK: ~82% para os ases
+        assert!((result.players[0].equity - 0.82).abs() < 0.02);
+        assert_eq!(
+            calculate(&hands(&["AhAd", "KcKs"]), &[], &[], &options).unwrap(),
+            result
+        );
+    }
+
+    fn ranges(notation: &[&str]) -> Vec<Range> {
+        notation
+            .iter()
+            .map(|range| range.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_range_against_hand() {
+        // Todo par de ases tem a mesma equity contra KK neste board
+        let result = calculate_ranges(
+            &ranges(&["AA", "KcKs"]),
+            &board("2c 7d 9h Js"),
+            &[],
+            &EquityOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.method, EquityMethod::Exhaustive);
+        assert_eq!(result.samples, 6 * 44);
+        assert_eq!(result.players[0].combos, 6);
+        assert_eq!(result.players[0].hand, None);
+        assert_eq!(result.players[1].hand, Some(board("Kc Ks")));
+        assert_eq!(result.players[0].equity, 42.0 / 44.0);
+    }
+
+    #[test]
+    fn test_range_against_range() {
+        let result = calculate_ranges(
+            &ranges(&["QQ+", "AKs"]),
+            &board("Ac 7d 2h"),
+            &[],
+            &EquityOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.method, EquityMethod::Exhaustive);
+        // O ás do board tira combinações dos dois ranges
+        assert_eq!(result.players[0].combos, 15);
+        assert_eq!(result.players[1].combos, 3);
+        let equity: f64 = result.players.iter().map(|p| p.equity).sum();
+        assert!((equity - 1.0).abs() < 1e-9);
+        // Só AA (3 das 30 combinações compatíveis) está na frente do par de ases
+        assert!(result.players[0].equity > 0.1 && result.players[0].equity < 0.25);
+
+        let options = EquityOptions {
+            samples: Some(20_000),
+            seed: Some(3),
+        };
+        let sampled =
+            calculate_ranges(&ranges(&["QQ+", "AKs"]), &board("Ac 7d 2h"), &[], &options).unwrap();
+        assert_eq!(sampled.method, EquityMethod::MonteCarlo);
+        assert!((sampled.players[0].equity - result.players[0].equity).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_weights_change_range_equity() {
+        let full = calculate_ranges(
+            &ranges(&["AA, 33", "KcKs"]),
+            &board("2c 7d 9h Js"),
+            &[],
+            &EquityOptions::default(),
+        )
+        .unwrap();
+        let light = calculate_ranges(
+            &ranges(&["AA, 33:0.1", "KcKs"]),
+            &board("2c 7d 9h Js"),
+            &[],
+            &EquityOptions::default(),
+        )
+        .unwrap();
+        // Menos peso para os 33 (que perdem para KK) aumenta a equity
+        assert!(light.players[0].equity > full.players[0].equity);
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let none = EquityOptions::default();
+        let invalid = [
+            calculate(&hands(&["AhAd"]), &[], &[], &none),
+            calculate(&hands(&["AhAd", "KcKsQs"]), &[], &[], &none),
+            calculate(&hands(&["AhAd", "AhKs"]), &[], &[], &none),
+            calculate(&hands(&["AhAd", "KcKs"]), &board("Ad 2c 3c"), &[], &none),
+            calculate(
+                &hands(&["AhAd", "KcKs"]),
+                &board("2c 3c 4c 5c 6c 7c"),
+                &[],
+                &none,
+            ),
+            calculate(
+                &hands(&["AhAd", "KcKs"]),
+                &[],
+                &[],
+                &EquityOptions {
+                    samples: Some(0),
+                    seed: None,
+                },
+            ),
+        ];
+        for result in invalid {
+            assert_eq!(result.unwrap_err(), GameError::InvalidEquityRequest);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+#[cfg(feature = "server")]
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+
+use crate::i18n::{translate, Locale};
+#[cfg(feature = "server")]
+use crate::storage::StorageError;
+
+/// Erros da API e do motor do jogo. O `code` é estável e deve ser usado pelos
+/// clientes; a mensagem é apenas para exibição.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    // Sala
+    RoomNotFound,
+    RoomFull,
+    PlayerNotInRoom,
+    Unauthorized,
+    TestModeDisabled,
+    InvalidDeck,
+    // Ciclo do jogo
+    GameNotStarted,
+    GameAlreadyStarted,
+    NotEnoughPlayers,
+    HandNotFinished,
+    HandAlreadyFinished,
+    HandNotFound,
+    HandNotReplayable,
+    HandNotVerifiable,
+    RabbitHuntNotAvailable,
+    // Ações
+    NotYourTurn,
+    AlreadyFolded,
+    CannotCheck,
+    InsufficientChips,
+    RaiseTooSmall,
+    StaleAction,
+    DuplicateAction,
+    MissingHandNumber,
+    RunItPending,
+    RunItNotAvailable,
+    InvalidRunCount,
+    // Importação
+    InvalidHandHistory,
+    ImportLimitReached,
+    // Ferramentas
+    InvalidEquityRequest,
+    // Infraestrutura
+    DeckUnavailable,
+    Internal,
+}
+
+impl GameError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            GameError::RoomNotFound => "room_not_found",
+            GameError::RoomFull => "room_full",
+            GameError::PlayerNotInRoom => "player_not_in_room",
+            GameError::Unauthorized => "unauthorized",
+            GameError::TestModeDisabled => "test_mode_disabled",
+            GameError::InvalidDeck => "invalid_deck",
+            GameError::GameNotStarted => "game_not_started",
+            GameError::GameAlreadyStarted => "game_already_started",
+            GameError::NotEnoughPlayers => "not_enough_players",
+            GameError::HandNotFinished => "hand_not_finished",
+            GameError::HandAlreadyFinished => "hand_already_finished",
+            GameError::HandNotFound => "hand_not_found",
+            GameError::HandNotReplayable => "hand_not_replayable",
+            GameError::HandNotVerifiable => "hand_not_verifiable",
+            GameError::RabbitHuntNotAvailable => "rabbit_hunt_not_available",
+            GameError::NotYourTurn => "not_your_turn",
+            GameError::AlreadyFolded => "already_folded",
+            GameError::CannotCheck => "cannot_check",
+            GameError::InsufficientChips => "insufficient_chips",
+            GameError::RaiseTooSmall => "raise_too_small",
+            GameError::StaleAction => "stale_action",
+            GameError::DuplicateAction => "duplicate_action",
+            GameError::MissingHandNumber => "missing_hand_number",
+            GameError::RunItPending => "run_it_pending",
+            GameError::RunItNotAvailable => "run_it_not_available",
+            GameError::InvalidRunCount => "invalid_run_count",
+            GameError::InvalidHandHistory => "invalid_hand_history",
+            GameError::ImportLimitReached => "import_limit_reached",
+            GameError::InvalidEquityRequest => "invalid_equity_request",
+            GameError::DeckUnavailable => "deck_unavailable",
+            GameError::Internal => "internal_error",
+        }
+    }
+
+    #[cfg(feature = "server")]
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            GameError::RoomNotFound | GameError::HandNotFound => StatusCode::NOT_FOUND,
+            GameError::Unauthorized => StatusCode::UNAUTHORIZED,
+            GameError::PlayerNotInRoom | GameError::TestModeDisabled => StatusCode::FORBIDDEN,
+            // Requisição válida, mas incompatível com o estado atual da sala/mão
+            GameError::RoomFull
+            | GameError::GameNotStarted
+            | GameError::GameAlreadyStarted
+            | GameError::NotEnoughPlayers
+            | GameError::HandNotFinished
+            | GameError::HandAlreadyFinished
+            | GameError::HandNotReplayable
+            | GameError::HandNotVerifiable
+            | GameError::RabbitHuntNotAvailable
+            | GameError::NotYourTurn
+            | GameError::StaleAction
+            | GameError::DuplicateAction
+            | GameError::RunItPending
+            | GameError::RunItNotAvailable
+            | GameError::ImportLimitReached => StatusCode::CONFLICT,
+            // Ação que viola as regras do poker
+            GameError::AlreadyFolded
+            | GameError::CannotCheck
+            | GameError::InsufficientChips
+            | GameError::RaiseTooSmall
+            | GameError::InvalidRunCount
+            | GameError::MissingHandNumber
+            | GameError::InvalidDeck
+            | GameError::InvalidHandHistory
+            | GameError::InvalidEquityRequest => StatusCode::UNPROCESSABLE_ENTITY,
+            GameError::DeckUnavailable | GameError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn message(&self, locale: Locale) -> &'static str {
+        translate(locale, self.code())
+    }
+
+    /// Corpo do erro, usado tanto nas respostas HTTP quanto nos eventos
+    /// `error` do WebSocket.
+    pub fn to_json(&self, locale: Locale) -> serde_json::Value {
+        serde_json::json!({
+            "code": self.code(),
+            "message": self.message(locale)
+        })
+    }
+
+    /// Resposta HTTP do erro no idioma pedido. O próprio erro vai nas extensões
+    /// da resposta para que o middleware de idioma possa re-renderizá-lo.
+    #[cfg(feature = "server")]
+    pub fn localized_response(&self, locale: Locale) -> Response {
+        let mut body = self.to_json(locale);
+        body["success"] = serde_json::Value::Bool(false);
+        let mut response = (self.status_code(), Json(body)).into_response();
+        response.extensions_mut().insert(self.clone());
+        response
+    }
+}
+
+impl std::fmt::Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message(Locale::default()))
+    }
+}
+
+impl std::error::Error for GameError {}
+
+#[cfg(feature = "server")]
+impl From<StorageError> for GameError {
+    fn from(error: StorageError) -> Self {
+        eprintln!("Erro de armazenamento: {}", error);
+        GameError::Internal
+    }
+}
+
+/// Responde no idioma padrão; o middleware `i18n::localize_errors` troca o
+/// corpo pelo idioma da requisição.
+#[cfg(feature = "server")]
+impl IntoResponse for GameError {
+    fn into_response(self) -> Response {
+        self.localized_response(Locale::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_body_has_code_and_message() {
+        let body = GameError::NotYourTurn.to_json(Locale::PtBr);
+        assert_eq!(body["code"], "not_your_turn");
+        assert_eq!(body["message"], "Não é sua vez de jogar");
+
+        let body = GameError::NotYourTurn.to_json(Locale::En);
+        assert_eq!(body["code"], "not_your_turn");
+        assert_eq!(body["message"], "It is not your turn");
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn test_status_codes() {
+        assert_eq!(GameError::RoomNotFound.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            GameError::Unauthorized.status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(GameError::RoomFull.status_code(), StatusCode::CONFLICT);
+        assert_eq!(
+            GameError::RaiseTooSmall.status_code(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+}
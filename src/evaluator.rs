@@ -0,0 +1,687 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::{i18n::Message, models::*};
+
+/// Força de uma mão como um inteiro comparável: quanto maior, melhor.
+///
+/// Os bits 20 e acima guardam a categoria (`HandRank`) e os 20 bits de baixo
+/// guardam até cinco ranks de desempate, 4 bits cada, do mais para o menos
+/// significativo. Duas mãos empatam exatamente quando a força é igual.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct HandStrength(u32);
+
+const CATEGORY_SHIFT: u32 = 20;
+const KICKER_SLOTS: u32 = 5;
+
+impl HandStrength {
+    fn new(rank: HandRank, kickers: impl IntoIterator<Item = u8>) -> Self {
+        let mut value = (rank as u32) << CATEGORY_SHIFT;
+        for (slot, kicker) in kickers.into_iter().take(KICKER_SLOTS as usize).enumerate() {
+            value |= (kicker as u32) << (4 * (KICKER_SLOTS - 1 - slot as u32));
+        }
+        Self(value)
+    }
+
+    pub fn rank(self) -> HandRank {
+        match self.0 >> CATEGORY_SHIFT {
+            10 => HandRank::RoyalFlush,
+            9 => HandRank::StraightFlush,
+            8 => HandRank::FourOfAKind,
+            7 => HandRank::FullHouse,
+            6 => HandRank::Flush,
+            5 => HandRank::Straight,
+            4 => HandRank::ThreeOfAKind,
+            3 => HandRank::TwoPair,
+            2 => HandRank::OnePair,
+            _ => HandRank::HighCard,
+        }
+    }
+
+    /// Ranks de desempate, no mesmo formato de `HandEvaluation::kickers`.
+    pub fn kickers(self) -> Vec<u8> {
+        (0..KICKER_SLOTS)
+            .map(|slot| ((self.0 >> (4 * (KICKER_SLOTS - 1 - slot))) & 0xF) as u8)
+            .take_while(|&kicker| kicker != 0)
+            .collect()
+    }
+}
+
+/// Tabelas indexadas pela máscara de 13 bits dos ranks presentes (bit 0 = 2,
+/// bit 12 = Ás), montadas uma única vez.
+struct Tables {
+    /// Melhor mão com cinco cartas do mesmo naipe: straight flush ou flush
+    flush: Vec<HandStrength>,
+    /// Melhor mão sem pares: straight ou carta alta
+    unique: Vec<HandStrength>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut flush = Vec::with_capacity(1 << 13);
+        let mut unique = Vec::with_capacity(1 << 13);
+        for mask in 0..1u16 << 13 {
+            let straight = straight_high(mask);
+            flush.push(match straight {
+                Some(14) => HandStrength::new(HandRank::RoyalFlush, [14]),
+                Some(high) => HandStrength::new(HandRank::StraightFlush, [high]),
+                None => HandStrength::new(HandRank::Flush, top_ranks(mask)),
+            });
+            unique.push(match straight {
+                Some(high) => HandStrength::new(HandRank::Straight, [high]),
+                None => HandStrength::new(HandRank::HighCard, top_ranks(mask)),
+            });
+        }
+        Tables { flush, unique }
+    })
+}
+
+/// Carta mais alta da maior sequência na máscara (5 para A-2-3-4-5).
+fn straight_high(mask: u16) -> Option<u8> {
+    const FIVE: u16 = 0b11111;
+    const WHEEL: u16 = 0b1_0000_0000_1111;
+    (0..=8)
+        .rev()
+        .find(|&low| mask & (FIVE << low) == FIVE << low)
+        .map(|low| low as u8 + 6)
+        .or_else(|| (mask & WHEEL == WHEEL).then_some(5))
+}
+
+/// Ranks da máscara em ordem decrescente.
+fn top_ranks(mut mask: u16) -> impl Iterator<Item = u8> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+        let bit = highest_bit(mask);
+        mask &= !(1 << bit);
+        Some(bit as u8 + 2)
+    })
+}
+
+fn highest_bit(mask: u16) -> u16 {
+    15 - mask.leading_zeros() as u16
+}
+
+fn suit_index(suit: Suit) -> usize {
+    match suit {
+        Suit::Hearts => 0,
+        Suit::Diamonds => 1,
+        Suit::Clubs => 2,
+        Suit::Spades => 3,
+    }
+}
+
+/// Força da melhor mão de 5 cartas entre as cartas dadas, sem alocar. Com
+/// menos de 5 cartas, avalia as que houver (um par, carta alta).
+pub fn hand_strength(cards: &[Card]) -> HandStrength {
+    let mut suits = [0u16; 4];
+    let mut counts = [0u8; 13];
+    for card in cards {
+        let rank = card.rank as usize - 2;
+        suits[suit_index(card.suit)] |= 1 << rank;
+        counts[rank] += 1;
+    }
+
+    let tables = tables();
+    let flush = suits
+        .iter()
+        .filter(|mask| mask.count_ones() >= 5)
+        .map(|&mask| tables.flush[mask as usize])
+        .max();
+    match flush {
+        // Com até 7 cartas, um flush exclui quadra e full house
+        Some(flush) if cards.len() <= 7 => flush,
+        flush => {
+            let ranks = suits[0] | suits[1] | suits[2] | suits[3];
+            let unsuited = unsuited_strength(ranks, &counts);
+            flush.map_or(unsuited, |flush| flush.max(unsuited))
+        }
+    }
+}
+
+/// Melhor mão sem contar flushes, a partir da máscara de ranks e da
+/// quantidade de cartas de cada rank.
+fn unsuited_strength(ranks: u16, counts: &[u8; 13]) -> HandStrength {
+    let (mut quads, mut trips, mut pairs) = (0u16, 0u16, 0u16);
+    for (rank, &count) in counts.iter().enumerate() {
+        match count {
+            4 => quads |= 1 << rank,
+            3 => trips |= 1 << rank,
+            2 => pairs |= 1 << rank,
+            _ => {}
+        }
+    }
+
+    let rank_of = |bit: u16| bit as u8 + 2;
+    if quads != 0 {
+        let quad = highest_bit(quads);
+        let kicker = top_ranks(ranks & !(1 << quad)).take(1);
+        return HandStrength::new(
+            HandRank::FourOfAKind,
+            std::iter::once(rank_of(quad)).chain(kicker),
+        );
+    }
+    if trips != 0 {
+        let trip = highest_bit(trips);
+        let paired = (trips & !(1 << trip)) | pairs;
+        if paired != 0 {
+            return HandStrength::new(
+                HandRank::FullHouse,
+                [rank_of(trip), rank_of(highest_bit(paired))],
+            );
+        }
+    }
+
+    let unique = tables().unique[ranks as usize];
+    if unique.rank() == HandRank::Straight {
+        return unique;
+    }
+    if trips != 0 {
+        let trip = highest_bit(trips);
+        let kickers = top_ranks(ranks & !(1 << trip)).take(2);
+        return HandStrength::new(
+            HandRank::ThreeOfAKind,
+            std::iter::once(rank_of(trip)).chain(kickers),
+        );
+    }
+    match pairs.count_ones() {
+        0 => unique,
+        1 => {
+            let pair = highest_bit(pairs);
+            let kickers = top_ranks(ranks & !(1 << pair)).take(3);
+            HandStrength::new(
+                HandRank::OnePair,
+                std::iter::once(rank_of(pair)).chain(kickers),
+            )
+        }
+        _ => {
+            let high = highest_bit(pairs);
+            let low = highest_bit(pairs & !(1 << high));
+            let kicker = top_ranks(ranks & !(1 << high) & !(1 << low)).take(1);
+            HandStrength::new(
+                HandRank::TwoPair,
+                [rank_of(high), rank_of(low)].into_iter().chain(kicker),
+            )
+        }
+    }
+}
+
+/// Avaliação completa (categoria, desempates e as 5 cartas usadas), derivada
+/// de [`hand_strength`].
+pub fn evaluate(cards: &[Card]) -> HandEvaluation {
+    let strength = hand_strength(cards);
+    HandEvaluation {
+        rank: strength.rank(),
+        kickers: strength.kickers(),
+        cards: best_five(cards, strength),
+    }
+}
+
+/// Compara duas mãos pela melhor combinação de 5 cartas de cada uma.
+pub fn compare(a: &[Card], b: &[Card]) -> std::cmp::Ordering {
+    hand_strength(a).cmp(&hand_strength(b))
+}
+
+/// Descreve a mão por extenso, como nos sites de poker ("Full House, Kings
+/// full of Sevens"), numa [`Message`] do catálogo `hand.*`.
+pub fn describe(evaluation: &HandEvaluation) -> Message {
+    let rank = |slot: usize| rank_from_value(evaluation.kickers.get(slot).copied().unwrap_or(0));
+    match evaluation.rank {
+        HandRank::RoyalFlush => Message::new("hand.royal_flush"),
+        HandRank::StraightFlush => Message::new("hand.straight_flush").with("high", rank(0)),
+        HandRank::FourOfAKind => Message::new("hand.four_of_a_kind").with("rank", rank(0)),
+        HandRank::FullHouse => Message::new("hand.full_house")
+            .with("rank", rank(0))
+            .with("over", rank(1)),
+        HandRank::Flush => Message::new("hand.flush").with("high", rank(0)),
+        HandRank::Straight => Message::new("hand.straight").with("high", rank(0)),
+        HandRank::ThreeOfAKind => Message::new("hand.three_of_a_kind").with("rank", rank(0)),
+        HandRank::TwoPair => Message::new("hand.two_pair")
+            .with("high", rank(0))
+            .with("low", rank(1)),
+        HandRank::OnePair => Message::new("hand.one_pair").with("rank", rank(0)),
+        HandRank::HighCard => Message::new("hand.high_card").with("high", rank(0)),
+    }
+}
+
+/// Partes de uma mão no showdown e por que ela ganhou, empatou ou perdeu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandExplanation {
+    pub category: HandRank,
+    pub description: Message,
+    /// Ranks que formam a categoria: o par, a trinca e o par do full house, a
+    /// carta alta da sequência, as cinco cartas do flush
+    pub ranks: Vec<Rank>,
+    /// Cartas que completam as cinco, na ordem em que desempatam
+    pub kickers: Vec<Rank>,
+    pub verdict: Verdict,
+    /// A mesma explicação por extenso, no catálogo `showdown.*`
+    pub summary: Message,
+}
+
+/// Resultado de uma mão contra a melhor mão do showdown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum Verdict {
+    Won,
+    Tied,
+    /// Categoria menor que a da vencedora
+    LostOnCategory {
+        winner: HandRank,
+    },
+    /// Mesma categoria, mas um rank que forma a mão é menor (par de reis
+    /// contra par de ases)
+    LostOnRank {
+        rank: Rank,
+        winner: Rank,
+    },
+    /// Mesma categoria e mesmos ranks; perdeu no kicker de número `kicker`
+    /// (1 = o primeiro kicker)
+    LostOnKicker {
+        kicker: usize,
+        rank: Rank,
+        winner: Rank,
+    },
+}
+
+/// Quantos dos ranks de desempate formam a categoria; o resto são kickers.
+fn category_ranks(rank: HandRank) -> usize {
+    match rank {
+        HandRank::FullHouse | HandRank::TwoPair => 2,
+        HandRank::Flush => 5,
+        _ => 1,
+    }
+}
+
+/// Explica a mão contra a melhor mão do showdown (`best`), que foi dividida
+/// entre `winners` jogadores.
+pub fn explain(hand: &HandEvaluation, best: &HandEvaluation, winners: usize) -> HandExplanation {
+    let split = category_ranks(hand.rank).min(hand.kickers.len());
+    let description = describe(hand);
+
+    let verdict = if hand == best {
+        if winners > 1 {
+            Verdict::Tied
+        } else {
+            Verdict::Won
+        }
+    } else if hand.rank != best.rank {
+        Verdict::LostOnCategory { winner: best.rank }
+    } else {
+        let (slot, rank, winner) = hand
+            .kickers
+            .iter()
+            .zip(&best.kickers)
+            .enumerate()
+            .find(|(_, (mine, theirs))| mine != theirs)
+            .map_or((0, 0, 0), |(slot, (&mine, &theirs))| (slot, mine, theirs));
+        if slot < split {
+            Verdict::LostOnRank {
+                rank: rank_from_value(rank),
+                winner: rank_from_value(winner),
+            }
+        } else {
+            Verdict::LostOnKicker {
+                kicker: slot - split + 1,
+                rank: rank_from_value(rank),
+                winner: rank_from_value(winner),
+            }
+        }
+    };
+
+    let lost = |key: &str| {
+        Message::new(key)
+            .with("hand", &description)
+            .with("winner_hand", describe(best))
+    };
+    let summary = match &verdict {
+        Verdict::Won => Message::new("showdown.won").with("hand", &description),
+        Verdict::Tied => Message::new("showdown.tied").with("hand", &description),
+        Verdict::LostOnCategory { .. } => lost("showdown.lost_on_category"),
+        Verdict::LostOnRank { rank, winner } => lost("showdown.lost_on_rank")
+            .with("rank", rank)
+            .with("winner", winner),
+        Verdict::LostOnKicker {
+            kicker,
+            rank,
+            winner,
+        } => lost("showdown.lost_on_kicker")
+            .with("kicker", kicker)
+            .with("rank", rank)
+            .with("winner", winner),
+    };
+
+    HandExplanation {
+        category: hand.rank,
+        description,
+        ranks: hand.kickers[..split]
+            .iter()
+            .map(|&rank| rank_from_value(rank))
+            .collect(),
+        kickers: hand.kickers[split..]
+            .iter()
+            .map(|&rank| rank_from_value(rank))
+            .collect(),
+        verdict,
+        summary,
+    }
+}
+
+fn rank_from_value(value: u8) -> Rank {
+    const RANKS: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+    RANKS[value.clamp(2, 14) as usize - 2]
+}
+
+/// Escolhe as cartas que formam a mão descrita pela força, da mais para a
+/// menos significativa.
+fn best_five(cards: &[Card], strength: HandStrength) -> Vec<Card> {
+    let kickers = strength.kickers();
+    let sequence = |high: u8| -> Vec<u8> {
+        if high == 5 {
+            vec![5, 4, 3, 2, 14]
+        } else {
+            (high - 4..=high).rev().collect()
+        }
+    };
+    let repeat = std::iter::repeat_n::<u8>;
+    let wanted: Vec<u8> = match strength.rank() {
+        HandRank::RoyalFlush | HandRank::StraightFlush | HandRank::Straight => sequence(kickers[0]),
+        HandRank::FourOfAKind => repeat(kickers[0], 4).chain(kickers[1..].to_vec()).collect(),
+        HandRank::FullHouse => repeat(kickers[0], 3).chain(repeat(kickers[1], 2)).collect(),
+        HandRank::ThreeOfAKind => repeat(kickers[0], 3).chain(kickers[1..].to_vec()).collect(),
+        HandRank::TwoPair => repeat(kickers[0], 2)
+            .chain(repeat(kickers[1], 2))
+            .chain(kickers[2..].to_vec())
+            .collect(),
+        HandRank::OnePair => repeat(kickers[0], 2).chain(kickers[1..].to_vec()).collect(),
+        HandRank::Flush | HandRank::HighCard => kickers,
+    };
+
+    let flush_suit = matches!(
+        strength.rank(),
+        HandRank::RoyalFlush | HandRank::StraightFlush | HandRank::Flush
+    )
+    .then(|| {
+        // O naipe cujas cartas formam exatamente essa mão
+        [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades]
+            .into_iter()
+            .find(|&suit| {
+                let suited: Vec<Card> = cards.iter().filter(|c| c.suit == suit).cloned().collect();
+                suited.len() >= 5 && hand_strength(&suited) == strength
+            })
+    })
+    .flatten();
+
+    let mut used = vec![false; cards.len()];
+    wanted
+        .into_iter()
+        .filter_map(|rank| {
+            let index = cards.iter().enumerate().position(|(i, card)| {
+                !used[i]
+                    && card.rank as u8 == rank
+                    && flush_suit.is_none_or(|suit| card.suit == suit)
+            })?;
+            used[index] = true;
+            Some(cards[index].clone())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{i18n::Locale, notation::parse_cards};
+
+    fn cards(notation: &str) -> Vec<Card> {
+        parse_cards(notation).unwrap()
+    }
+
+    fn strength(notation: &str) -> HandStrength {
+        hand_strength(&cards(notation))
+    }
+
+    #[test]
+    fn test_categories_and_kickers() {
+        let cases = [
+            ("Ah Kh Qh Jh Th 2c 3d", HandRank::RoyalFlush, vec![14]),
+            ("9s 8s 7s 6s 5s Ah Ad", HandRank::StraightFlush, vec![9]),
+            ("7c 7d 7h 7s Kd 2c 3c", HandRank::FourOfAKind, vec![7, 13]),
+            ("Kc Kd Kh 7s 7d 7c 2h", HandRank::FullHouse, vec![13, 7]),
+            (
+                "Ad 9d 7d 4d 2d Ks Kc",
+                HandRank::Flush,
+                vec![14, 9, 7, 4, 2],
+            ),
+            ("9c 8d 7h 6s 5c 5d 5h", HandRank::Straight, vec![9]),
+            (
+                "Qc Qd Qh 9s 4c 3d 2h",
+                HandRank::ThreeOfAKind,
+                vec![12, 9, 4],
+            ),
+            ("Jc Jd 4h 4s 9c 9d Ah", HandRank::TwoPair, vec![11, 9, 14]),
+            (
+                "Tc Td Ah 8s 6c 4d 2h",
+                HandRank::OnePair,
+                vec![10, 14, 8, 6],
+            ),
+            (
+                "Ac Jd 9h 7s 5c 3d 2h",
+                HandRank::HighCard,
+                vec![14, 11, 9, 7, 5],
+            ),
+        ];
+        for (hand, rank, kickers) in cases {
+            let evaluation = evaluate(&cards(hand));
+            assert_eq!(evaluation.rank, rank, "{}", hand);
+            assert_eq!(evaluation.kickers, kickers, "{}", hand);
+            assert_eq!(evaluation.cards.len(), 5, "{}", hand);
+        }
+    }
+
+    #[test]
+    fn test_wheel_is_the_lowest_straight() {
+        let wheel = strength("Ah 2d 3c 4s 5h Kd Kc");
+        assert_eq!(wheel.rank(), HandRank::Straight);
+        assert_eq!(wheel.kickers(), vec![5]);
+        assert!(wheel < strength("2d 3c 4s 5h 6h Kd Kc"));
+        assert_eq!(
+            evaluate(&cards("Ah 2d 3c 4s 5h Kd Kc")).cards,
+            cards("5h 4s 3c 2d Ah")
+        );
+
+        let steel_wheel = strength("Ah 2h 3h 4h 5h Kd Kc");
+        assert_eq!(steel_wheel.rank(), HandRank::StraightFlush);
+        assert_eq!(steel_wheel.kickers(), vec![5]);
+    }
+
+    #[test]
+    fn test_strength_orders_hands() {
+        assert!(strength("As Ad Kc Qh 2s 3c 4d") > strength("Ks Kd Ac Qh 2s 3c 4d"));
+        // Mesmo par, decide o kicker
+        assert!(strength("As Ad Kc Qh 9s 3c 4d") > strength("As Ad Kc Qh 8s 3c 4d"));
+        // Só as 5 melhores cartas contam
+        assert_eq!(
+            strength("As Ad Kc Qh Js 3c 2d"),
+            strength("Ah Ac Kd Qs Jc 4h 3s")
+        );
+        // Duas trincas viram full house com a maior por cima
+        assert_eq!(strength("9c 9d 9h 5s 5c 5d Ah").kickers(), vec![9, 5]);
+        // Terceiro par pode ser o kicker do two pair
+        assert_eq!(strength("9c 9d 5h 5s 7c 7d 2h").kickers(), vec![9, 7, 5]);
+    }
+
+    #[test]
+    fn test_flush_uses_cards_of_the_suit() {
+        let evaluation = evaluate(&cards("Ah Kh 9h 4h 2h Ac As"));
+        assert_eq!(evaluation.rank, HandRank::Flush);
+        assert!(evaluation
+            .cards
+            .iter()
+            .all(|card| card.suit == Suit::Hearts));
+    }
+
+    #[test]
+    fn test_describe_hands() {
+        let cases = [
+            ("Ah Kh Qh Jh Th 2c 3d", "Royal Flush"),
+            ("9s 8s 7s 6s 5s Ah Ad", "Straight Flush, Nine high"),
+            ("7c 7d 7h 7s Kd 2c 3c", "Four of a Kind, Sevens"),
+            ("Kc Kd Kh 7s 7d 2c 3h", "Full House, Kings full of Sevens"),
+            ("Ad 9d 7d 4d 2d Ks Kc", "Flush, Ace high"),
+            ("Ah 2d 3c 4s 5h Kd Kc", "Straight, Five high"),
+            ("Qc Qd Qh 9s 4c 3d 2h", "Three of a Kind, Queens"),
+            ("Jc Jd 4h 4s 9c 8d Ah", "Two Pair, Jacks and Fours"),
+            ("6c 6d Ah 8s Tc 4d 2h", "Pair of Sixes"),
+            ("Ac Jd 9h 7s 5c 3d 2h", "High Card, Ace"),
+        ];
+        for (hand, description) in cases {
+            assert_eq!(
+                describe(&evaluate(&cards(hand))).render(Locale::En),
+                description
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_hands() {
+        use std::cmp::Ordering;
+
+        let board = "Ks 9d 4c 2h 2s";
+        let hand = |hole: &str| cards(&format!("{} {}", hole, board));
+        assert_eq!(compare(&hand("Ah Kd"), &hand("Qh Qd")), Ordering::Greater);
+        assert_eq!(compare(&hand("Ah 3d"), &hand("Ac 3s")), Ordering::Equal);
+        assert_eq!(compare(&hand("9h 9c"), &hand("Kh Kc")), Ordering::Less);
+    }
+
+    #[test]
+    fn test_explain_showdown_hands() {
+        let board = "Ks 9d 4c 2h 2s";
+        let hand = |hole: &str| evaluate(&cards(&format!("{} {}", hole, board)));
+        let best = hand("Ah Kd");
+
+        let won = explain(&best, &best, 1);
+        assert_eq!(won.verdict, Verdict::Won);
+        assert_eq!(won.ranks, [Rank::King, Rank::Two]);
+        assert_eq!(won.kickers, [Rank::Ace]);
+        assert_eq!(explain(&best, &best, 2).verdict, Verdict::Tied);
+
+        let kicker = explain(&hand("Kh Qd"), &best, 1);
+        assert_eq!(
+            kicker.verdict,
+            Verdict::LostOnKicker {
+                kicker: 1,
+                rank: Rank::Queen,
+                winner: Rank::Ace
+            }
+        );
+        assert_eq!(
+            kicker.summary.render(Locale::En),
+            "Two Pair, Kings and Twos loses to Two Pair, Kings and Twos on the kicker: Ace beats Queen"
+        );
+        assert_eq!(
+            kicker.summary.render(Locale::PtBr),
+            "Dois pares, Reis e Dois perde para Dois pares, Reis e Dois no kicker: Ás ganha de Dama"
+        );
+
+        let rank = explain(&hand("Qh Qc"), &best, 1);
+        assert_eq!(
+            rank.verdict,
+            Verdict::LostOnRank {
+                rank: Rank::Queen,
+                winner: Rank::King
+            }
+        );
+
+        let full_house = hand("Kh Kc");
+        let category = explain(&best, &full_house, 1);
+        assert_eq!(
+            category.verdict,
+            Verdict::LostOnCategory {
+                winner: HandRank::FullHouse
+            }
+        );
+        assert_eq!(category.summary.key, "showdown.lost_on_category");
+        assert_eq!(
+            category.summary.render(Locale::En),
+            "Two Pair, Kings and Twos loses to Full House, Kings full of Twos"
+        );
+        assert_eq!(
+            serde_json::to_value(&category.verdict).unwrap()["outcome"],
+            "lost_on_category"
+        );
+    }
+
+    #[test]
+    fn test_any_number_of_cards() {
+        assert_eq!(strength("Ah Ad").rank(), HandRank::OnePair);
+        assert_eq!(strength("Ah Kd").kickers(), vec![14, 13]);
+        assert_eq!(evaluate(&cards("7h 7d 7c")).cards.len(), 3);
+
+        // Com mais de 7 cartas o flush não exclui a quadra
+        let nine = "Ah Kh 9h 4h 2h 9c 9d 9s 3c";
+        assert_eq!(strength(nine).rank(), HandRank::FourOfAKind);
+        let two_flushes = "Ah Kh 9h 4h 2h Ks Qs Js 8s 3s";
+        let evaluation = evaluate(&cards(two_flushes));
+        assert_eq!(evaluation.kickers, vec![14, 13, 9, 4, 2]);
+        assert!(evaluation
+            .cards
+            .iter()
+            .all(|card| card.suit == Suit::Hearts));
+    }
+
+    /// Enumera todas as mãos de 5 cartas e confere as contagens conhecidas de
+    /// cada categoria e de classes de equivalência (7462).
+    #[test]
+    fn test_all_five_card_hands() {
+        let deck = Game::create_deck();
+        let mut counts = std::collections::BTreeMap::new();
+        let mut classes = std::collections::HashSet::new();
+        let mut hand = Vec::with_capacity(5);
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            hand.clear();
+                            hand.extend([a, b, c, d, e].map(|i| deck[i].clone()));
+                            let strength = hand_strength(&hand);
+                            *counts.entry(strength.rank()).or_insert(0) += 1;
+                            classes.insert(strength);
+                        }
+                    }
+                }
+            }
+        }
+
+        assert_eq!(counts[&HandRank::HighCard], 1_302_540);
+        assert_eq!(counts[&HandRank::OnePair], 1_098_240);
+        assert_eq!(counts[&HandRank::TwoPair], 123_552);
+        assert_eq!(counts[&HandRank::ThreeOfAKind], 54_912);
+        assert_eq!(counts[&HandRank::Straight], 10_200);
+        assert_eq!(counts[&HandRank::Flush], 5_108);
+        assert_eq!(counts[&HandRank::FullHouse], 3_744);
+        assert_eq!(counts[&HandRank::FourOfAKind], 624);
+        assert_eq!(counts[&HandRank::StraightFlush], 36);
+        assert_eq!(counts[&HandRank::RoyalFlush], 4);
+        assert_eq!(classes.len(), 7462);
+    }
+}
@@ -0,0 +1,403 @@
+use rand::seq::SliceRandom;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::models::*;
+use crate::sealed::Unsealed;
+use crate::shuffle::shuffled_deck;
+
+/// Embaralhamento comprovadamente justo (commit-reveal), sem revelar as cartas
+/// que não foram abertas na mesa.
+///
+/// Da `server_seed` sai um deck base: `create_deck` embaralhado por um ChaCha20
+/// semeado com `sha256(server_seed)`. Cada posição `i` do deck base tem um sal
+/// `sha256("{server_seed}:{i}")` e um compromisso
+/// `sha256("{sal}:{carta}")`, com a carta em notação curta (`Ah`). Antes da mão
+/// o servidor publica `commitment`, o sha256 dos 52 compromissos concatenados.
+///
+/// Os jogadores podem registrar client seeds depois disso. A ordem final do
+/// deck é uma permutação do deck base sorteada por um ChaCha20 semeado com
+/// `sha256("{client_seeds}:{hand_number}")`, em que as client seeds aparecem
+/// como `player_id=seed` ordenadas por jogador e separadas por vírgula: a
+/// posição `j` do deck da mão recebe a carta da posição `ordem[j]` do deck
+/// base. Como o deck base foi comprometido antes das client seeds, o servidor
+/// não escolhe onde cada carta cai.
+///
+/// Ao fim da mão a `server_seed` continua secreta. A prova ([`FairnessProof`])
+/// traz os 52 compromissos e abre só as posições das cartas que apareceram na
+/// mesa (board e mãos mostradas), com o sal de cada uma; as cartas do muck e as
+/// não distribuídas continuam escondidas. Qualquer um confere a prova com
+/// [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FairShuffle {
+    pub hand_number: u64,
+    /// Vazia nas cópias do histórico entregues fora da visão do admin
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub server_seed: String,
+    pub commitment: String,
+    #[serde(default)]
+    pub client_seeds: BTreeMap<String, String>,
+}
+
+/// Prova pública de uma mão: os compromissos de todas as posições do deck base
+/// e as cartas abertas na mesa, cada uma com a posição no deck da mão e o sal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FairnessProof {
+    pub hand_number: u64,
+    pub commitment: String,
+    pub client_seeds: BTreeMap<String, String>,
+    pub positions: Vec<String>,
+    pub revealed: Vec<RevealedCard>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevealedCard {
+    /// Posição no deck da mão (as cartas saem do fim)
+    pub position: usize,
+    pub card: Card,
+    pub salt: String,
+}
+
+/// Estado do commit-reveal de uma sala: a seed já comprometida para a próxima
+/// mão, as client seeds registradas e a prova da mão atual.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredFairness")]
+pub struct FairnessState {
+    // As seeds ficam seladas nos snapshots: com elas dá para recalcular o deck
+    #[serde(serialize_with = "crate::sealed::secret::serialize")]
+    pub next_server_seed: String,
+    pub client_seeds: BTreeMap<String, String>,
+    #[serde(serialize_with = "crate::sealed::secret::serialize")]
+    pub current: Option<FairShuffle>,
+    /// A prova da mão atual estava selada com outra chave mestra e se perdeu
+    /// na restauração; a mão em andamento não pode mais ser verificada.
+    #[serde(skip)]
+    pub proof_unavailable: bool,
+}
+
+impl Default for FairnessState {
+    fn default() -> Self {
+        Self {
+            next_server_seed: new_server_seed(),
+            client_seeds: BTreeMap::new(),
+            current: None,
+            proof_unavailable: false,
+        }
+    }
+}
+
+/// Formato gravado. Uma seed que não abre não derruba a restauração: a seed
+/// da próxima mão é trocada por uma nova e a prova perdida fica marcada em
+/// `proof_unavailable`, para a mão em andamento ser anulada.
+#[derive(Deserialize)]
+struct StoredFairness {
+    next_server_seed: Unsealed<String>,
+    #[serde(default)]
+    client_seeds: BTreeMap<String, String>,
+    #[serde(default)]
+    current: Unsealed<Option<FairShuffle>>,
+}
+
+impl From<StoredFairness> for FairnessState {
+    fn from(stored: StoredFairness) -> Self {
+        let next_server_seed = match stored.next_server_seed {
+            Unsealed::Value(seed) => seed,
+            Unsealed::Unavailable => new_server_seed(),
+        };
+        let (current, proof_unavailable) = match stored.current {
+            Unsealed::Value(current) => (current, false),
+            Unsealed::Unavailable => (None, true),
+        };
+        Self {
+            next_server_seed,
+            client_seeds: stored.client_seeds,
+            current,
+            proof_unavailable,
+        }
+    }
+}
+
+impl FairnessState {
+    /// Compromisso publicado para a próxima mão.
+    pub fn next_commitment(&self) -> String {
+        commitment(&self.next_server_seed)
+    }
+
+    /// Usa a seed comprometida para gerar o deck da mão e já compromete uma
+    /// nova seed para a mão seguinte.
+    pub fn deal(&mut self, hand_number: u64) -> Vec<Card> {
+        let server_seed = std::mem::replace(&mut self.next_server_seed, new_server_seed());
+        let proof = FairShuffle {
+            hand_number,
+            commitment: commitment(&server_seed),
+            server_seed,
+            client_seeds: self.client_seeds.clone(),
+        };
+        let deck = proof.deck();
+        self.current = Some(proof);
+        self.proof_unavailable = false;
+        deck
+    }
+
+    /// Dados públicos do commit-reveal. A seed nunca aparece: a prova de cada
+    /// mão sai em `GET /hands/:hand_id/verify`.
+    pub fn public_view(&self) -> serde_json::Value {
+        serde_json::json!({
+            "commitment": self.current.as_ref().map(|proof| &proof.commitment),
+            "client_seeds": self.current.as_ref().map(|proof| &proof.client_seeds),
+            "next_commitment": self.next_commitment(),
+            "next_client_seeds": self.client_seeds,
+        })
+    }
+}
+
+impl FairShuffle {
+    /// Deck da mão, na ordem usada pelo motor (as cartas saem do fim).
+    pub fn deck(&self) -> Vec<Card> {
+        let base = base_deck(&self.server_seed);
+        deck_order(&self.client_seeds, self.hand_number)
+            .into_iter()
+            .map(|index| base[index].clone())
+            .collect()
+    }
+
+    /// Prova pública que abre só as cartas `visible` (as que apareceram na
+    /// mesa para quem consulta).
+    pub fn proof(&self, visible: &[Card]) -> FairnessProof {
+        let deck = self.deck();
+        let order = deck_order(&self.client_seeds, self.hand_number);
+        let revealed = deck
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| visible.contains(card))
+            .map(|(position, card)| RevealedCard {
+                position,
+                card: card.clone(),
+                salt: salt(&self.server_seed, order[position]),
+            })
+            .collect();
+        FairnessProof {
+            hand_number: self.hand_number,
+            commitment: self.commitment.clone(),
+            client_seeds: self.client_seeds.clone(),
+            positions: position_commitments(&self.server_seed),
+            revealed,
+        }
+    }
+}
+
+/// Resultado da verificação de uma prova.
+#[derive(Debug, Clone, Serialize)]
+pub struct Verification {
+    /// Os compromissos das posições formam o `commitment` publicado
+    pub commitment_matches: bool,
+    /// Cada carta aberta bate com o compromisso da sua posição
+    pub cards_match: bool,
+    pub valid: bool,
+}
+
+/// Verificador independente: confere que os compromissos das posições formam
+/// o compromisso publicado antes da mão e que cada carta aberta, levada de
+/// volta ao deck base pela ordem das client seeds, bate com o compromisso da
+/// posição.
+pub fn verify(proof: &FairnessProof) -> Verification {
+    let commitment_matches =
+        proof.positions.len() == 52 && hash(&proof.positions.concat()) == proof.commitment;
+    let order = deck_order(&proof.client_seeds, proof.hand_number);
+    let cards_match = proof.revealed.iter().all(|revealed| {
+        order
+            .get(revealed.position)
+            .and_then(|&index| proof.positions.get(index))
+            .is_some_and(|expected| *expected == card_commitment(&revealed.salt, &revealed.card))
+    });
+    Verification {
+        commitment_matches,
+        cards_match,
+        valid: commitment_matches && cards_match,
+    }
+}
+
+/// Compromisso publicado para uma `server_seed`.
+pub fn commitment(server_seed: &str) -> String {
+    hash(&position_commitments(server_seed).concat())
+}
+
+/// Compromisso de cada posição do deck base.
+pub fn position_commitments(server_seed: &str) -> Vec<String> {
+    base_deck(server_seed)
+        .iter()
+        .enumerate()
+        .map(|(index, card)| card_commitment(&salt(server_seed, index), card))
+        .collect()
+}
+
+/// Ordem do deck da mão: a posição `j` recebe a carta da posição `ordem[j]`
+/// do deck base.
+pub fn deck_order(client_seeds: &BTreeMap<String, String>, hand_number: u64) -> Vec<usize> {
+    let client_seeds = client_seeds
+        .iter()
+        .map(|(player_id, seed)| format!("{}={}", player_id, seed))
+        .collect::<Vec<_>>()
+        .join(",");
+    let material = format!("{}:{}", client_seeds, hand_number);
+    let seed: [u8; 32] = Sha256::digest(material.as_bytes()).into();
+    let mut order: Vec<usize> = (0..52).collect();
+    order.shuffle(&mut ChaCha20Rng::from_seed(seed));
+    order
+}
+
+fn base_deck(server_seed: &str) -> Vec<Card> {
+    let seed: [u8; 32] = Sha256::digest(server_seed.as_bytes()).into();
+    shuffled_deck(&mut ChaCha20Rng::from_seed(seed))
+}
+
+fn salt(server_seed: &str, index: usize) -> String {
+    hash(&format!("{}:{}", server_seed, index))
+}
+
+fn card_commitment(salt: &str, card: &Card) -> String {
+    hash(&format!("{}:{}", salt, card.notation()))
+}
+
+fn hash(material: &str) -> String {
+    hex::encode(Sha256::digest(material.as_bytes()))
+}
+
+fn new_server_seed() -> String {
+    let mut seed = [0u8; 32];
+    ChaCha20Rng::from_entropy().fill_bytes(&mut seed);
+    hex::encode(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_covers_every_position() {
+        let positions = position_commitments("abc");
+        assert_eq!(positions.len(), 52);
+        assert_eq!(commitment("abc"), hash(&positions.concat()));
+        assert_ne!(commitment("abc"), commitment("abd"));
+    }
+
+    #[test]
+    fn test_deal_uses_committed_seed_and_rotates() {
+        let mut state = FairnessState::default();
+        let committed = state.next_commitment();
+
+        let deck = state.deal(1);
+        let shuffle = state.current.clone().unwrap();
+        assert_eq!(shuffle.commitment, committed);
+        assert_ne!(state.next_commitment(), committed);
+
+        let proof = shuffle.proof(&deck);
+        assert_eq!(proof.revealed.len(), 52);
+        assert!(verify(&proof).valid);
+    }
+
+    #[test]
+    fn test_client_seeds_change_the_deck() {
+        let mut shuffle = FairShuffle {
+            hand_number: 1,
+            server_seed: "seed".to_string(),
+            commitment: commitment("seed"),
+            client_seeds: BTreeMap::new(),
+        };
+        let without = shuffle.deck();
+        shuffle
+            .client_seeds
+            .insert("player1".to_string(), "sorte".to_string());
+        let with = shuffle.deck();
+
+        assert_ne!(without, with);
+        assert_eq!(with, shuffle.deck());
+        assert!(Game::create_deck().iter().all(|card| with.contains(card)));
+        shuffle.hand_number = 2;
+        assert_ne!(with, shuffle.deck());
+    }
+
+    #[test]
+    fn test_proof_opens_only_the_visible_cards() {
+        let mut state = FairnessState::default();
+        let deck = state.deal(1);
+        let shuffle = state.current.clone().unwrap();
+
+        // O board e uma mão mostrada; o resto do deck fica escondido
+        let visible: Vec<Card> = deck.iter().rev().take(9).cloned().collect();
+        let proof = shuffle.proof(&visible);
+        assert!(verify(&proof).valid);
+        let revealed: Vec<&Card> = proof.revealed.iter().map(|r| &r.card).collect();
+        assert_eq!(revealed.len(), 9);
+        assert!(visible.iter().all(|card| revealed.contains(&card)));
+        for card in &proof.revealed {
+            assert_eq!(deck[card.position], card.card);
+        }
+
+        let text = serde_json::to_string(&proof).unwrap();
+        assert!(!text.contains(&shuffle.server_seed));
+        assert!(!text.contains("server_seed"));
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let mut state = FairnessState::default();
+        let deck = state.deal(3);
+        let proof = state.current.clone().unwrap().proof(&deck[40..]);
+        assert!(verify(&proof).valid);
+
+        // Uma carta aberta trocada por outra
+        let mut swapped = proof.clone();
+        swapped.revealed[0].card = deck[0].clone();
+        let verification = verify(&swapped);
+        assert!(verification.commitment_matches);
+        assert!(!verification.cards_match);
+        assert!(!verification.valid);
+
+        // Compromissos que não formam o publicado
+        let mut forged = proof.clone();
+        forged.positions.swap(0, 1);
+        assert!(!verify(&forged).commitment_matches);
+
+        // Client seeds diferentes das usadas levam as cartas a outras posições
+        let mut reseeded = proof.clone();
+        reseeded
+            .client_seeds
+            .insert("intruso".to_string(), "x".to_string());
+        assert!(!verify(&reseeded).cards_match);
+    }
+
+    #[test]
+    fn test_seed_is_never_public() {
+        let mut state = FairnessState::default();
+        state.deal(1);
+
+        let view = state.public_view();
+        assert!(view.get("server_seed").is_none());
+        assert_eq!(
+            view["commitment"],
+            state.current.as_ref().unwrap().commitment
+        );
+        let text = view.to_string();
+        assert!(!text.contains(&state.current.as_ref().unwrap().server_seed));
+        assert!(!text.contains(&state.next_server_seed));
+    }
+
+    #[test]
+    fn test_unreadable_seeds_are_replaced() {
+        let mut state = FairnessState::default();
+        state.deal(1);
+        let mut json = serde_json::to_value(&state).unwrap();
+        json["next_server_seed"] = crate::sealed::sealed_with_other_key();
+        json["current"] = crate::sealed::sealed_with_other_key();
+
+        let restored: FairnessState = serde_json::from_value(json).unwrap();
+        assert_ne!(restored.next_commitment(), state.next_commitment());
+        assert_eq!(restored.next_server_seed.len(), 64);
+        assert!(restored.current.is_none());
+        assert!(restored.proof_unavailable);
+    }
+}
@@ -1,32 +1,45 @@
+use crate::error::GameError;
+use crate::evaluator;
+use crate::fairness::FairnessState;
+use crate::history::*;
 use crate::models::*;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::collections::HashMap;
+use crate::sealed::SealedDeck;
+use crate::shuffle::DeckSource;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 impl Game {
     pub fn new(players: Vec<Player>) -> Self {
-        let mut deck = Self::create_deck();
-        let mut rng = thread_rng();
-        deck.shuffle(&mut rng);
-
         Self {
             id: Uuid::new_v4().to_string(),
             players,
-            deck,
+            deck: SealedDeck::default(),
             community_cards: Vec::new(),
             pot: 0,
             current_bet: 0,
+            last_raise: 0,
             current_player_index: 0,
             dealer_index: 0,
             small_blind: 5,
             big_blind: 10,
             state: GameState::PreFlop,
             round_bets: HashMap::new(),
+            hand_number: 0,
+            action_sequence: 0,
+            applied_action_ids: HashSet::new(),
+            hand_history: None,
+            deck_source: DeckSource::default(),
+            fairness: FairnessState::default(),
+            rules: RoomRules::default(),
+            run_it: None,
+            reveal: HandReveal::default(),
+            hand_result: None,
+            shows_losing_hands: HashSet::new(),
         }
     }
 
-    fn create_deck() -> Vec<Card> {
+    /// Deck novo, na ordem de naipes e ranks (antes do embaralhamento).
+    pub fn create_deck() -> Vec<Card> {
         let mut deck = Vec::new();
         let suits = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
         let ranks = [
@@ -56,7 +69,27 @@ impl Game {
 
         deck
     }
-    pub fn start_round(&mut self) {
+    pub fn start_round(&mut self) -> Result<(), GameError> {
+        // Recriar e embaralhar o deck
+        let hand_number = self.hand_number + 1;
+        let deck = match &self.deck_source {
+            DeckSource::Random => self.fairness.deal(hand_number),
+            // Seeds fixas e decks empilhados não têm prova de commit-reveal
+            source => {
+                self.fairness.current = None;
+                source.deck_for_hand(hand_number, self.players.len())
+            }
+        };
+
+        self.start_round_with_deck(deck)
+    }
+
+    /// Inicia uma mão usando o deck dado, na ordem em que está (as cartas saem
+    /// do fim). Usado pelo replay para reproduzir uma mão gravada; um deck com
+    /// cartas repetidas ou desconhecidas é rejeitado antes de mexer na mesa.
+    pub fn start_round_with_deck(&mut self, deck: Vec<Card>) -> Result<(), GameError> {
+        let deck = SealedDeck::seal(&deck)?;
+
         // Reset player states
         for player in &mut self.players {
             player.hand.clear();
@@ -65,25 +98,31 @@ impl Game {
             player.is_all_in = false;
         }
 
-        // Recriar e embaralhar o deck
-        self.deck = Self::create_deck();
-        let mut rng = thread_rng();
-        self.deck.shuffle(&mut rng);
-
+        self.deck = deck;
         self.community_cards.clear();
         self.pot = 0;
         self.current_bet = 0;
+        self.last_raise = 0;
         self.round_bets.clear();
         self.state = GameState::PreFlop;
+        self.hand_number += 1;
+        self.action_sequence = 0;
+        self.applied_action_ids.clear();
+        self.run_it = None;
+        self.reveal = HandReveal::default();
+        self.hand_result = None;
+        self.begin_hand_history();
 
         // Deal cards
-        self.deal_hole_cards();
+        self.deal_hole_cards()?;
+        self.record_hole_cards();
 
         // Post blinds
         self.post_blinds();
 
         // Set current player (left of big blind - que é dealer + 3 posições)
         self.current_player_index = self.get_first_active_player_after_big_blind();
+        Ok(())
     }
 
     fn get_first_active_player_after_big_blind(&self) -> usize {
@@ -104,14 +143,15 @@ impl Game {
         start_index
     }
 
-    fn deal_hole_cards(&mut self) {
+    fn deal_hole_cards(&mut self) -> Result<(), GameError> {
         for _ in 0..2 {
             for player in &mut self.players {
-                if let Some(card) = self.deck.pop() {
+                if let Some(card) = self.deck.pop()? {
                     player.hand.push(card);
                 }
             }
         }
+        Ok(())
     }
 
     fn post_blinds(&mut self) {
@@ -128,6 +168,11 @@ impl Game {
             self.players[small_blind_index].id.clone(),
             small_blind_amount,
         );
+        self.record_action(
+            small_blind_index,
+            ActionKind::SmallBlind,
+            small_blind_amount,
+        );
 
         // Big blind
         let big_blind_amount = std::cmp::min(self.big_blind, self.players[big_blind_index].chips);
@@ -137,31 +182,53 @@ impl Game {
         self.current_bet = big_blind_amount;
         self.round_bets
             .insert(self.players[big_blind_index].id.clone(), big_blind_amount);
+        self.record_action(big_blind_index, ActionKind::BigBlind, big_blind_amount);
+    }
+
+    /// Menor aumento aceito agora: o tamanho do último bet ou raise da rodada
+    /// de apostas, ou o big blind se ninguém apostou mais que isso.
+    pub fn min_raise(&self) -> u32 {
+        self.last_raise.max(self.big_blind)
     }
+
     pub fn process_action(
         &mut self,
         player_id: &str,
         action: PlayerAction,
-    ) -> Result<Option<serde_json::Value>, String> {
+    ) -> Result<Option<serde_json::Value>, GameError> {
+        if matches!(self.state, GameState::Showdown | GameState::Finished) {
+            return Err(GameError::HandAlreadyFinished);
+        }
+        if self.run_it.is_some() {
+            return Err(GameError::RunItPending);
+        }
+
         let current_player = &self.players[self.current_player_index];
 
         if current_player.id != player_id {
-            return Err("Não é sua vez de jogar".to_string());
+            return Err(GameError::NotYourTurn);
         }
 
         if current_player.is_folded {
-            return Err("Jogador já foldou".to_string());
+            return Err(GameError::AlreadyFolded);
         }
 
+        let chips_before = current_player.chips;
+        let kind = match action {
+            PlayerAction::Fold => ActionKind::Fold,
+            PlayerAction::Check => ActionKind::Check,
+            PlayerAction::Call => ActionKind::Call,
+            PlayerAction::Raise(_) => ActionKind::Raise,
+            PlayerAction::AllIn => ActionKind::AllIn,
+        };
+
         match action {
             PlayerAction::Fold => {
                 self.players[self.current_player_index].is_folded = true;
             }
             PlayerAction::Check => {
                 if self.current_bet > self.players[self.current_player_index].current_bet {
-                    return Err(
-                        "Não é possível dar check, há uma aposta a ser igualada".to_string()
-                    );
+                    return Err(GameError::CannotCheck);
                 }
                 // Registrar que este jogador fez uma ação (check) nesta rodada
                 let current_total_bet = *self
@@ -204,9 +271,16 @@ impl Game {
                 let available_chips = self.players[self.current_player_index].chips;
 
                 if total_bet > available_chips {
-                    return Err("Fichas insuficientes para essa aposta".to_string());
+                    return Err(GameError::InsufficientChips);
                 }
 
+                // O aumento mínimo é o último bet ou raise da rodada (nunca menos
+                // que o big blind), exceto quando o jogador aposta tudo
+                if amount < self.min_raise() && total_bet < available_chips {
+                    return Err(GameError::RaiseTooSmall);
+                }
+                self.last_raise = self.last_raise.max(amount);
+
                 self.players[self.current_player_index].chips -= total_bet;
                 self.players[self.current_player_index].current_bet += total_bet;
                 self.current_bet = self.players[self.current_player_index].current_bet;
@@ -221,6 +295,11 @@ impl Game {
                     self.players[self.current_player_index].id.clone(),
                     current_total_bet,
                 );
+
+                // Um raise com todas as fichas é um all-in
+                if self.players[self.current_player_index].chips == 0 {
+                    self.players[self.current_player_index].is_all_in = true;
+                }
             }
             PlayerAction::AllIn => {
                 let all_in_amount = self.players[self.current_player_index].chips;
@@ -239,14 +318,68 @@ impl Game {
                     current_total_bet,
                 );
 
-                if self.players[self.current_player_index].current_bet > self.current_bet {
+                let raised = self.players[self.current_player_index]
+                    .current_bet
+                    .saturating_sub(self.current_bet);
+                if raised > 0 {
                     self.current_bet = self.players[self.current_player_index].current_bet;
+                    self.last_raise = self.last_raise.max(raised);
                 }
             }
         }
 
+        let amount = chips_before - self.players[self.current_player_index].chips;
+        self.record_action(self.current_player_index, kind, amount);
+
+        self.action_sequence += 1;
         self.next_player();
-        let round_result = self.check_round_completion();
+        let round_result = self.check_round_completion()?;
+
+        Ok(round_result)
+    }
+
+    /// Versão idempotente de `process_action` usada pelos clientes. Reenvios com
+    /// um `action_id` já aplicado retornam `DuplicateAction` sem alterar o jogo, e
+    /// ações que esperam outra mão ou sequência são rejeitadas como `StaleAction`.
+    ///
+    /// Os `action_id` aplicados valem só para a mão atual, então toda ação com
+    /// `action_id` precisa dizer a mão (`hand_number`): um reenvio que chega
+    /// depois da mão virar é rejeitado como `StaleAction` em vez de ser
+    /// aplicado de novo na mão seguinte.
+    pub fn process_client_action(
+        &mut self,
+        player_id: &str,
+        message: &GameActionMessage,
+    ) -> Result<Option<serde_json::Value>, GameError> {
+        if message.action_id.is_some() && message.hand_number.is_none() {
+            return Err(GameError::MissingHandNumber);
+        }
+        let action_key = message
+            .action_id
+            .as_ref()
+            .map(|action_id| (player_id.to_string(), action_id.clone()));
+
+        if let Some(key) = &action_key {
+            if self.applied_action_ids.contains(key) {
+                return Err(GameError::DuplicateAction);
+            }
+        }
+
+        let stale_hand = message
+            .hand_number
+            .is_some_and(|hand| hand != self.hand_number);
+        let stale_sequence = message
+            .sequence
+            .is_some_and(|sequence| sequence != self.action_sequence);
+        if stale_hand || stale_sequence {
+            return Err(GameError::StaleAction);
+        }
+
+        let round_result = self.process_action(player_id, message.action.clone())?;
+
+        if let Some(key) = action_key {
+            self.applied_action_ids.insert(key);
+        }
 
         Ok(round_result)
     }
@@ -269,7 +402,7 @@ impl Game {
             }
         }
     }
-    fn check_round_completion(&mut self) -> Option<serde_json::Value> {
+    fn check_round_completion(&mut self) -> Result<Option<serde_json::Value>, GameError> {
         let active_players: Vec<_> = self
             .players
             .iter()
@@ -277,6 +410,25 @@ impl Game {
             .filter(|(_, p)| !p.is_folded && !p.is_all_in)
             .collect();
 
+        // Todos os outros foldaram: a mão termina sem distribuir o resto do
+        // board, que fica disponível para o rabbit hunt
+        let contenders = self.players.iter().filter(|p| !p.is_folded).count();
+        if contenders == 1 {
+            return self.determine_winner();
+        }
+
+        // All-in: no máximo um jogador ainda pode apostar. Depois que ele
+        // responde à aposta, o resto do board é distribuído de uma vez
+        if contenders >= 2 && active_players.len() <= 1 {
+            if active_players
+                .iter()
+                .any(|(_, p)| p.current_bet < self.current_bet)
+            {
+                return Ok(None);
+            }
+            return self.run_out_all_in();
+        }
+
         // Se há apenas um jogador ativo ou menos, a rodada termina
         if active_players.len() <= 1 {
             return self.advance_game_state();
@@ -296,7 +448,7 @@ impl Game {
             return self.advance_game_state();
         }
 
-        None
+        Ok(None)
     }
     fn has_betting_round_completed(&self, active_players: &[(usize, &Player)]) -> bool {
         // Se não há aposta atual, verificar se todos os jogadores ativos tiveram sua vez
@@ -314,26 +466,27 @@ impl Game {
             player.current_bet == self.current_bet || self.round_bets.contains_key(&player.id)
         })
     }
-    fn advance_game_state(&mut self) -> Option<serde_json::Value> {
+    fn advance_game_state(&mut self) -> Result<Option<serde_json::Value>, GameError> {
         // Reset current bets for next round
         for player in &mut self.players {
             player.current_bet = 0;
         }
         self.current_bet = 0;
+        self.last_raise = 0;
         self.round_bets.clear();
 
         match self.state {
             GameState::PreFlop => {
                 self.state = GameState::Flop;
-                self.deal_flop();
+                self.deal_flop()?;
             }
             GameState::Flop => {
                 self.state = GameState::Turn;
-                self.deal_turn();
+                self.deal_turn()?;
             }
             GameState::Turn => {
                 self.state = GameState::River;
-                self.deal_river();
+                self.deal_river()?;
             }
             GameState::River => {
                 self.state = GameState::Showdown;
@@ -341,10 +494,274 @@ impl Game {
             }
             _ => {}
         }
+        self.record_board();
 
         // Reset current player para o primeiro jogador ativo à esquerda do dealer
         self.current_player_index = self.get_first_active_player_after_dealer();
-        None
+        Ok(None)
+    }
+
+    /// Registra a equity de cada jogador no momento do all-in e distribui as
+    /// ruas que faltam até o showdown. Se a sala permite distribuir o board mais
+    /// de uma vez, abre a votação entre os jogadores na disputa.
+    fn run_out_all_in(&mut self) -> Result<Option<serde_json::Value>, GameError> {
+        if self.state != GameState::River {
+            self.record_all_in();
+
+            let max_runs = self.rules.max_runs.min(self.runs_left_in_deck());
+            if max_runs > 1 {
+                self.run_it = Some(RunItVote {
+                    players: self
+                        .players
+                        .iter()
+                        .filter(|p| !p.is_folded)
+                        .map(|p| p.id.clone())
+                        .collect(),
+                    max_runs,
+                    votes: HashMap::new(),
+                });
+                return Ok(None);
+            }
+        }
+
+        self.run_board(1)
+    }
+
+    /// Quantas vezes o deck ainda comporta o resto do board, com os burns.
+    fn runs_left_in_deck(&self) -> u8 {
+        let per_run = match self.state {
+            GameState::PreFlop => 8,
+            GameState::Flop => 4,
+            GameState::Turn => 2,
+            _ => return 1,
+        };
+        (self.deck.len() / per_run).min(u8::MAX as usize) as u8
+    }
+
+    /// Registra o voto de um jogador all-in em quantas vezes distribuir o board.
+    /// Quando todos votaram, vale o menor número pedido.
+    pub fn choose_runs(
+        &mut self,
+        player_id: &str,
+        runs: u8,
+    ) -> Result<Option<serde_json::Value>, GameError> {
+        let vote = self
+            .run_it
+            .as_mut()
+            .filter(|vote| vote.players.iter().any(|id| id == player_id))
+            .ok_or(GameError::RunItNotAvailable)?;
+        if runs == 0 || runs > vote.max_runs {
+            return Err(GameError::InvalidRunCount);
+        }
+
+        vote.votes.insert(player_id.to_string(), runs);
+        if vote.votes.len() < vote.players.len() {
+            return Ok(None);
+        }
+        self.settle_run_it_vote()
+    }
+
+    /// Conta a saída de um jogador que ainda não votou como voto em rodar uma
+    /// vez, para que um jogador desconectado não trave a mesa.
+    pub fn forfeit_run_it(
+        &mut self,
+        player_id: &str,
+    ) -> Result<Option<serde_json::Value>, GameError> {
+        let pending = self.run_it.as_ref().is_some_and(|vote| {
+            vote.players.iter().any(|id| id == player_id) && !vote.votes.contains_key(player_id)
+        });
+        if !pending {
+            return Err(GameError::RunItNotAvailable);
+        }
+        self.choose_runs(player_id, 1)
+    }
+
+    /// Encerra a votação no fim do prazo: quem não votou conta como 1, e o
+    /// board sai uma vez só.
+    pub fn expire_run_it_vote(&mut self) -> Result<Option<serde_json::Value>, GameError> {
+        let vote = self.run_it.as_mut().ok_or(GameError::RunItNotAvailable)?;
+        for player_id in &vote.players {
+            vote.votes.entry(player_id.clone()).or_insert(1);
+        }
+        self.settle_run_it_vote()
+    }
+
+    /// Distribui o board com o menor número votado e fecha a votação.
+    fn settle_run_it_vote(&mut self) -> Result<Option<serde_json::Value>, GameError> {
+        let runs = self
+            .run_it
+            .take()
+            .and_then(|vote| vote.votes.values().copied().min())
+            .unwrap_or(1);
+        self.run_board(runs)
+    }
+
+    /// Distribui o resto do board `runs` vezes a partir do board do all-in.
+    /// Cada pot é dividido igualmente entre as vezes e cada uma tem o seu
+    /// showdown.
+    fn run_board(&mut self, runs: u8) -> Result<Option<serde_json::Value>, GameError> {
+        if runs <= 1 {
+            while matches!(
+                self.state,
+                GameState::PreFlop | GameState::Flop | GameState::Turn | GameState::River
+            ) {
+                if let Some(result) = self.advance_game_state()? {
+                    return Ok(Some(result));
+                }
+            }
+            return Ok(None);
+        }
+
+        for player in &mut self.players {
+            player.current_bet = 0;
+        }
+        self.current_bet = 0;
+        self.last_raise = 0;
+        self.round_bets.clear();
+
+        let all_in_board = self.community_cards.clone();
+        let all_in_street = self.state.clone();
+        let pot = self.pot;
+        let hand_pots = self.pots();
+        let runs = runs as u32;
+
+        let mut results = Vec::new();
+        let mut records: Vec<RunRecord> = Vec::new();
+        let mut showdown = Vec::new();
+        let mut winnings: Vec<PotAward> = Vec::new();
+        let mut pots = Vec::new();
+        for run in 0..runs {
+            self.community_cards = all_in_board.clone();
+            self.state = all_in_street.clone();
+            self.deal_rest_of_board()?;
+
+            // Cada pot é dividido entre as vezes; as fichas que não dividem
+            // por igual ficam com a primeira
+            let shares: Vec<Pot> = hand_pots
+                .iter()
+                .map(|pot| Pot {
+                    amount: pot.amount / runs + if run == 0 { pot.amount % runs } else { 0 },
+                    eligible: pot.eligible.clone(),
+                })
+                .collect();
+            let (mut result, run_showdown, awards) = self.settle_showdown(&shares);
+            result["board"] = serde_json::json!(self.community_cards);
+            for pot in result["pots"].as_array().into_iter().flatten() {
+                let mut pot = pot.clone();
+                pot["run"] = serde_json::json!(run + 1);
+                pots.push(pot);
+            }
+
+            for award in &awards {
+                match winnings.iter_mut().find(|w| w.player_id == award.player_id) {
+                    Some(total) => total.amount += award.amount,
+                    None => winnings.push(award.clone()),
+                }
+            }
+            if run == 0 {
+                showdown = run_showdown;
+            }
+            records.push(RunRecord {
+                board: self.community_cards.clone(),
+                winnings: awards,
+            });
+            results.push(result);
+        }
+
+        let boards: Vec<Vec<Card>> = records.iter().map(|run| run.board.clone()).collect();
+        self.reveal_showdown(&boards);
+        self.mark_mucked(&mut showdown);
+
+        // O estado e o board do histórico ficam com a primeira vez
+        self.community_cards = records[0].board.clone();
+        self.record_board();
+        self.finish_hand_history(showdown, winnings)?;
+        if let Some(history) = self.hand_history.as_mut() {
+            history.runs = records;
+        }
+        self.pot = 0;
+        self.state = GameState::Finished;
+
+        self.hand_result = Some(serde_json::json!({
+            "type": "run_it",
+            "pot_won": pot,
+            "pots": pots,
+            "runs": results
+        }));
+        Ok(self.get_hand_result())
+    }
+
+    fn deal_rest_of_board(&mut self) -> Result<(), GameError> {
+        loop {
+            match self.state {
+                GameState::PreFlop => {
+                    self.state = GameState::Flop;
+                    self.deal_flop()?;
+                }
+                GameState::Flop => {
+                    self.state = GameState::Turn;
+                    self.deal_turn()?;
+                }
+                GameState::Turn => {
+                    self.state = GameState::River;
+                    self.deal_river()?;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Registra o board e o pot no momento do all-in. A equity fica pendente
+    /// até `calculate_all_in_equity` ou `set_all_in_equity`.
+    fn record_all_in(&mut self) {
+        let record = AllInRecord {
+            street: self.state.clone(),
+            board: self.community_cards.clone(),
+            pot: self.pot,
+            players: Vec::new(),
+        };
+        if let Some(history) = self.hand_history.as_mut() {
+            history.all_in = Some(record);
+        }
+    }
+
+    /// Cálculo da equity do all-in da mão atual, se ainda estiver pendente.
+    /// Os jogadores na disputa e as cartas não mudam depois do all-in, então
+    /// o pedido pode ser feito a qualquer momento até o fim da mão.
+    pub fn all_in_equity_request(&self) -> Option<AllInEquityRequest> {
+        let all_in = self.hand_history.as_ref()?.all_in.as_ref()?;
+        if !all_in.players.is_empty() {
+            return None;
+        }
+        let contenders: Vec<&Player> = self.players.iter().filter(|p| !p.is_folded).collect();
+        Some(AllInEquityRequest::new(
+            self.hand_number,
+            &contenders,
+            &all_in.board,
+            self.pots(),
+        ))
+    }
+
+    /// Grava a equity calculada fora do motor. É ignorada se a mão já mudou.
+    pub fn set_all_in_equity(&mut self, request: &AllInEquityRequest, players: Vec<AllInEquity>) {
+        if request.hand_number != self.hand_number {
+            return;
+        }
+        if let Some(all_in) = self
+            .hand_history
+            .as_mut()
+            .and_then(|history| history.all_in.as_mut())
+        {
+            all_in.players = players;
+        }
+    }
+
+    /// Calcula na hora a equity pendente do all-in (bloqueia a thread).
+    pub fn calculate_all_in_equity(&mut self) {
+        if let Some(request) = self.all_in_equity_request() {
+            let players = request.calculate();
+            self.set_all_in_equity(&request, players);
+        }
     }
 
     fn get_first_active_player_after_dealer(&self) -> usize {
@@ -359,38 +776,41 @@ impl Game {
         self.dealer_index
     }
 
-    fn deal_flop(&mut self) {
+    fn deal_flop(&mut self) -> Result<(), GameError> {
         // Burn one card
-        self.deck.pop();
+        self.deck.pop()?;
 
         // Deal 3 community cards
         for _ in 0..3 {
-            if let Some(card) = self.deck.pop() {
+            if let Some(card) = self.deck.pop()? {
                 self.community_cards.push(card);
             }
         }
+        Ok(())
     }
 
-    fn deal_turn(&mut self) {
+    fn deal_turn(&mut self) -> Result<(), GameError> {
         // Burn one card
-        self.deck.pop();
+        self.deck.pop()?;
 
         // Deal 1 community card
-        if let Some(card) = self.deck.pop() {
+        if let Some(card) = self.deck.pop()? {
             self.community_cards.push(card);
         }
+        Ok(())
     }
 
-    fn deal_river(&mut self) {
+    fn deal_river(&mut self) -> Result<(), GameError> {
         // Burn one card
-        self.deck.pop();
+        self.deck.pop()?;
 
         // Deal 1 community card
-        if let Some(card) = self.deck.pop() {
+        if let Some(card) = self.deck.pop()? {
             self.community_cards.push(card);
         }
+        Ok(())
     }
-    fn determine_winner(&mut self) -> Option<serde_json::Value> {
+    fn determine_winner(&mut self) -> Result<Option<serde_json::Value>, GameError> {
         let active_players: Vec<_> = self
             .players
             .iter()
@@ -403,268 +823,543 @@ impl Game {
             let (winner_index, _) = active_players[0];
             let winner_id = self.players[winner_index].id.clone();
             let winner_name = self.players[winner_index].name.clone();
-            let winner_hand = self.players[winner_index].hand.clone();
 
             self.players[winner_index].chips += self.pot;
-            let result = serde_json::json!({
+            self.finish_hand_history(
+                Vec::new(),
+                vec![PotAward {
+                    player_id: winner_id.clone(),
+                    amount: pot_amount,
+                }],
+            )?;
+            // Sem showdown o vencedor escolhe se mostra as cartas (`show_hand`)
+            self.hand_result = Some(serde_json::json!({
                 "type": "single_winner",
                 "winner": {
                     "id": winner_id,
                     "name": winner_name,
-                    "hand": winner_hand
+                    "hand": self.players[winner_index].hand
                 },
-                "pot_won": pot_amount
-            });
+                "pot_won": pot_amount,
+                "pots": [{ "amount": pot_amount, "eligible": [winner_id], "winners": [winner_id] }]
+            }));
             self.pot = 0;
             self.state = GameState::Finished;
-            return Some(result);
-        }
-
-        // Avaliar todas as mãos dos jogadores ativos
-        let mut evaluations: Vec<(usize, HandEvaluation)> = Vec::new();
-
-        for (index, player) in &active_players {
-            let mut all_cards = player.hand.clone();
-            all_cards.extend(self.community_cards.clone());
-            let evaluation = self.evaluate_hand(all_cards);
-            evaluations.push((*index, evaluation));
+            return Ok(self.get_hand_result());
         }
 
-        // Ordenar por força da mão (melhor mão primeiro)
-        evaluations.sort_by(|a, b| b.1.cmp(&a.1));
-
-        // Determinar vencedores (pode haver empate)
-        let best_hand = &evaluations[0].1;
-        let mut winners = Vec::new();
-
-        for (index, eval) in &evaluations {
-            if eval == best_hand {
-                winners.push(*index);
-            } else {
-                break;
-            }
-        }
+        let pots = self.pots();
+        let (result, mut showdown, awards) = self.settle_showdown(&pots);
+        let board = self.community_cards.clone();
+        self.reveal_showdown(&[board]);
+        self.mark_mucked(&mut showdown);
+        self.finish_hand_history(showdown, awards)?;
 
-        // Distribuir o pot entre os vencedores
-        let pot_per_winner = self.pot / winners.len() as u32;
-        let remainder = self.pot % winners.len() as u32;
+        self.pot = 0;
+        self.state = GameState::Finished;
+        self.hand_result = Some(result);
+        Ok(self.get_hand_result())
+    }
 
-        for (i, &winner_index) in winners.iter().enumerate() {
-            let mut winnings = pot_per_winner;
-            if i < remainder as usize {
-                winnings += 1; // Distribuir o resto
+    /// Ordem em que as mãos são abertas no showdown: começa pelo último
+    /// jogador que apostou ou aumentou na última rodada de apostas e, sem
+    /// apostas nela, pelo primeiro jogador depois do dealer.
+    fn show_order(&self) -> Vec<usize> {
+        let actions = self
+            .hand_history
+            .as_ref()
+            .map_or(&[][..], |history| history.actions.as_slice());
+        let last_street = actions.last().map(|action| action.street.clone());
+
+        let mut street_bet = 0;
+        let mut aggressor = None;
+        for action in actions
+            .iter()
+            .filter(|action| Some(&action.street) == last_street.as_ref())
+        {
+            let blind = matches!(action.kind, ActionKind::SmallBlind | ActionKind::BigBlind);
+            if action.total_bet > street_bet && !blind {
+                aggressor = self.players.iter().position(|p| p.id == action.player_id);
             }
-            self.players[winner_index].chips += winnings;
+            street_bet = street_bet.max(action.total_bet);
         }
 
-        // Criar resultado detalhado
-        let result = serde_json::json!({
-            "type": "showdown",
-            "pot_won": pot_amount,
-            "winners": winners.iter().map(|&index| {
-                let player = &self.players[index];
-                let eval = evaluations.iter().find(|(i, _)| *i == index).unwrap().1.clone();
-                serde_json::json!({
-                    "id": player.id,
-                    "name": player.name,
-                    "hand": player.hand,
-                    "best_hand": eval.cards,
-                    "hand_rank": eval.rank
-                })
-            }).collect::<Vec<_>>(),
-            "all_hands": evaluations.iter().map(|(index, eval)| {
-                let player = &self.players[*index];
-                serde_json::json!({
-                    "id": player.id,
-                    "name": player.name,
-                    "hand": player.hand,
-                    "best_hand": eval.cards,
-                    "hand_rank": eval.rank
-                })
-            }).collect::<Vec<_>>()
-        });
-
-        self.pot = 0;
-        self.state = GameState::Finished;
-        Some(result)
+        let start = aggressor.unwrap_or((self.dealer_index + 1) % self.players.len());
+        (0..self.players.len())
+            .map(|offset| (start + offset) % self.players.len())
+            .filter(|&index| !self.players[index].is_folded)
+            .collect()
     }
 
-    fn evaluate_hand(&self, cards: Vec<Card>) -> HandEvaluation {
-        // Obter todas as combinações de 5 cartas das 7 disponíveis
-        let combinations = self.get_five_card_combinations(cards);
-
-        // Avaliar cada combinação e retornar a melhor
-        let mut best_evaluation = self.evaluate_five_cards(&combinations[0]);
+    /// Abre as mãos na ordem do showdown. A primeira sempre é mostrada; as
+    /// seguintes só quando empatam ou ganham de uma mão já aberta em algum dos
+    /// boards. Na sua vez, quem perderia vai para o muck, a não ser que tenha
+    /// desligado o auto-muck (`set_auto_muck`). Depois de um all-in todas as
+    /// mãos ficam abertas.
+    fn reveal_showdown(&mut self, boards: &[Vec<Card>]) {
+        let all_in = self
+            .hand_history
+            .as_ref()
+            .is_some_and(|history| history.all_in.is_some());
+        let order = self.show_order();
+
+        let mut best: Vec<Option<HandEvaluation>> = vec![None; boards.len()];
+        let mut shown = Vec::new();
+        for &index in &order {
+            let player = &self.players[index];
+            let evaluations: Vec<HandEvaluation> = boards
+                .iter()
+                .map(|board| {
+                    let mut cards = player.hand.clone();
+                    cards.extend(board.iter().cloned());
+                    evaluator::evaluate(&cards)
+                })
+                .collect();
 
-        for combination in combinations.iter().skip(1) {
-            let evaluation = self.evaluate_five_cards(combination);
-            if evaluation > best_evaluation {
-                best_evaluation = evaluation;
+            let contests = evaluations
+                .iter()
+                .zip(&best)
+                .any(|(evaluation, best)| best.as_ref().is_none_or(|best| evaluation >= best));
+            if !all_in && !contests && !self.shows_losing_hands.contains(&player.id) {
+                continue;
             }
+            for (evaluation, best) in evaluations.into_iter().zip(best.iter_mut()) {
+                if best.as_ref().is_none_or(|current| evaluation > *current) {
+                    *best = Some(evaluation);
+                }
+            }
+            shown.push(player.id.clone());
         }
 
-        best_evaluation
+        self.reveal.show_order = order
+            .iter()
+            .map(|&index| self.players[index].id.clone())
+            .collect();
+        self.reveal.shown = shown;
     }
 
-    fn get_five_card_combinations(&self, cards: Vec<Card>) -> Vec<Vec<Card>> {
-        let mut combinations = Vec::new();
-        let n = cards.len();
-
-        // Gerar todas as combinações de 5 cartas
-        for i in 0..n {
-            for j in (i + 1)..n {
-                for k in (j + 1)..n {
-                    for l in (k + 1)..n {
-                        for m in (l + 1)..n {
-                            combinations.push(vec![
-                                cards[i].clone(),
-                                cards[j].clone(),
-                                cards[k].clone(),
-                                cards[l].clone(),
-                                cards[m].clone(),
-                            ]);
+    /// Tira do resultado as cartas de quem deu muck e as do vencedor sem
+    /// showdown que não quis mostrar.
+    fn hide_mucked(&self, result: &mut serde_json::Value) {
+        let is_shown = |hand: &serde_json::Value| {
+            hand["id"]
+                .as_str()
+                .is_some_and(|id| self.reveal.shown.iter().any(|s| s == id))
+        };
+        if let Some(runs) = result.get_mut("runs").and_then(|v| v.as_array_mut()) {
+            for run in runs {
+                self.hide_mucked(run);
+            }
+        }
+        if let Some(winner) = result.get_mut("winner") {
+            let shown = is_shown(winner);
+            if let Some(fields) = winner.as_object_mut() {
+                if !shown {
+                    fields.remove("hand");
+                }
+                fields.insert("shown".to_string(), shown.into());
+            }
+        }
+        for key in ["winners", "all_hands"] {
+            let Some(hands) = result.get_mut(key).and_then(|v| v.as_array_mut()) else {
+                continue;
+            };
+            for hand in hands {
+                let shown = is_shown(hand);
+                if let Some(fields) = hand.as_object_mut() {
+                    if !shown {
+                        for field in [
+                            "hand",
+                            "best_hand",
+                            "hand_rank",
+                            "description",
+                            "explanation",
+                        ] {
+                            fields.remove(field);
+                        }
+                        let pots = fields.get_mut("pots").and_then(|v| v.as_array_mut());
+                        for pot in pots.into_iter().flatten() {
+                            if let Some(pot) = pot.as_object_mut() {
+                                pot.remove("explanation");
+                            }
                         }
                     }
+                    fields.insert("shown".to_string(), shown.into());
                 }
             }
         }
-
-        combinations
     }
 
-    fn evaluate_five_cards(&self, cards: &[Card]) -> HandEvaluation {
-        let mut sorted_cards = cards.to_vec();
-        sorted_cards.sort_by(|a, b| (b.rank as u8).cmp(&(a.rank as u8)));
+    /// Marca as mãos que foram para o muck e põe o showdown do histórico na
+    /// ordem em que as mãos foram abertas.
+    fn mark_mucked(&self, showdown: &mut [ShowdownRecord]) {
+        for record in showdown.iter_mut() {
+            record.mucked = !self.reveal.shown.contains(&record.player_id);
+        }
+        showdown.sort_by_key(|record| {
+            self.reveal
+                .show_order
+                .iter()
+                .position(|id| *id == record.player_id)
+        });
+    }
 
-        let ranks: Vec<u8> = sorted_cards.iter().map(|c| c.rank as u8).collect();
-        let suits: Vec<Suit> = sorted_cards.iter().map(|c| c.suit).collect();
+    /// Liga ou desliga o auto-muck do jogador. Com ele desligado, as mãos que
+    /// perdem no showdown também são abertas na ordem do showdown. A escolha
+    /// vale para as mãos seguintes, até ser trocada.
+    pub fn set_auto_muck(&mut self, player_id: &str, enabled: bool) -> Result<(), GameError> {
+        if !self.players.iter().any(|p| p.id == player_id) {
+            return Err(GameError::PlayerNotInRoom);
+        }
+        if enabled {
+            self.shows_losing_hands.remove(player_id);
+        } else {
+            self.shows_losing_hands.insert(player_id.to_string());
+        }
+        Ok(())
+    }
 
-        let is_flush = suits.iter().all(|&s| s == suits[0]);
-        let is_straight = self.is_straight(&ranks);
+    /// Abre as cartas de um jogador depois do fim da mão: o vencedor sem
+    /// showdown, quem foi para o muck ou quem foldou.
+    pub fn show_hand(&mut self, player_id: &str) -> Result<serde_json::Value, GameError> {
+        if self.state != GameState::Finished {
+            return Err(GameError::HandNotFinished);
+        }
+        let player = self
+            .players
+            .iter()
+            .find(|p| p.id == player_id && !p.hand.is_empty())
+            .ok_or(GameError::PlayerNotInRoom)?;
 
-        // Royal Flush
-        if is_flush && is_straight && ranks[0] == 14 {
-            return HandEvaluation {
-                rank: HandRank::RoyalFlush,
-                kickers: vec![14],
-                cards: sorted_cards,
-            };
+        if !self.reveal.shown.contains(&player.id) {
+            self.reveal.shown.push(player.id.clone());
         }
+        // O histórico guarda a escolha, para que as cartas apareçam nele
+        if let Some(history) = self.hand_history.as_mut() {
+            if !history.was_shown(&player.id) {
+                history.shown.push(player.id.clone());
+            }
+        }
+        Ok(serde_json::json!({
+            "player_id": player.id,
+            "name": player.name,
+            "hand": player.hand
+        }))
+    }
 
-        // Straight Flush
-        if is_flush && is_straight {
-            return HandEvaluation {
-                rank: HandRank::StraightFlush,
-                kickers: vec![ranks[0]],
-                cards: sorted_cards,
-            };
+    /// Board que teria saído se a mão não tivesse acabado antes do river,
+    /// tirado das cartas que sobraram no deck com as mesmas queimadas.
+    pub fn rabbit_hunt(&mut self, player_id: &str) -> Result<Vec<Card>, GameError> {
+        if self.state != GameState::Finished {
+            return Err(GameError::HandNotFinished);
+        }
+        if !self.players.iter().any(|p| p.id == player_id) {
+            return Err(GameError::PlayerNotInRoom);
+        }
+        if let Some(board) = &self.reveal.rabbit_hunt {
+            return Ok(board.clone());
+        }
+        if self.community_cards.len() >= 5 {
+            return Err(GameError::RabbitHuntNotAvailable);
         }
 
-        // Contar frequências dos ranks
-        let mut rank_counts: std::collections::HashMap<u8, u8> = std::collections::HashMap::new();
-        for &rank in &ranks {
-            *rank_counts.entry(rank).or_insert(0) += 1;
+        let mut remaining = self.deck.reveal()?;
+        remaining.truncate(self.deck.len());
+        let mut board = self.community_cards.clone();
+        while board.len() < 5 {
+            remaining.pop(); // Burn
+            let count = if board.is_empty() { 3 } else { 1 };
+            for _ in 0..count {
+                board.extend(remaining.pop());
+            }
         }
 
-        let mut counts: Vec<(u8, u8)> = rank_counts.into_iter().collect();
-        counts.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+        self.reveal.rabbit_hunt = Some(board.clone());
+        Ok(board)
+    }
 
-        // Four of a Kind
-        if counts[0].1 == 4 {
-            return HandEvaluation {
-                rank: HandRank::FourOfAKind,
-                kickers: vec![counts[0].0, counts[1].0],
-                cards: sorted_cards,
-            };
+    /// Pot principal e side pots da mão atual, pelo quanto cada jogador
+    /// apostou. Sem o histórico da mão (snapshots antigos), o pot inteiro fica
+    /// entre todos que estão na disputa.
+    fn pots(&self) -> Vec<Pot> {
+        let contenders: Vec<String> = self
+            .players
+            .iter()
+            .filter(|p| !p.is_folded)
+            .map(|p| p.id.clone())
+            .collect();
+        let pots = self
+            .hand_history
+            .as_ref()
+            .map(|history| history.pots(&contenders))
+            .unwrap_or_default();
+        if pots.is_empty() {
+            vec![Pot {
+                amount: self.pot,
+                eligible: contenders,
+            }]
+        } else {
+            pots
         }
+    }
 
-        // Full House
-        if counts[0].1 == 3 && counts[1].1 == 2 {
-            return HandEvaluation {
-                rank: HandRank::FullHouse,
-                kickers: vec![counts[0].0, counts[1].0],
-                cards: sorted_cards,
-            };
-        }
+    /// Avalia as mãos no board atual e divide cada pot entre as melhores mãos
+    /// de quem disputa ele.
+    fn settle_showdown(
+        &mut self,
+        pots: &[Pot],
+    ) -> (serde_json::Value, Vec<ShowdownRecord>, Vec<PotAward>) {
+        // Avaliar todas as mãos dos jogadores ativos
+        let mut evaluations: Vec<(usize, HandEvaluation)> = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !p.is_folded)
+            .map(|(index, player)| {
+                let mut all_cards = player.hand.clone();
+                all_cards.extend(self.community_cards.clone());
+                (index, evaluator::evaluate(&all_cards))
+            })
+            .collect();
 
-        // Flush
-        if is_flush {
-            return HandEvaluation {
-                rank: HandRank::Flush,
-                kickers: ranks,
-                cards: sorted_cards,
-            };
-        }
+        // Ordenar por força da mão (melhor mão primeiro)
+        evaluations.sort_by(|a, b| b.1.cmp(&a.1));
 
-        // Straight
-        if is_straight {
-            return HandEvaluation {
-                rank: HandRank::Straight,
-                kickers: vec![ranks[0]],
-                cards: sorted_cards,
+        // Cada pot vai para a melhor mão entre quem o disputa
+        let mut awards: Vec<PotAward> = Vec::new();
+        let mut pot_winners: Vec<Vec<PotAward>> = Vec::new();
+        let mut pot_best: Vec<Option<(&HandEvaluation, usize)>> = Vec::new();
+        for pot in pots {
+            let contest: Vec<&(usize, HandEvaluation)> = evaluations
+                .iter()
+                .filter(|(index, _)| pot.eligible.contains(&self.players[*index].id))
+                .collect();
+            let Some((_, best)) = contest.first() else {
+                pot_winners.push(Vec::new());
+                pot_best.push(None);
+                continue;
             };
+            let winners: Vec<usize> = contest
+                .iter()
+                .take_while(|(_, eval)| eval == best)
+                .map(|(index, _)| *index)
+                .collect();
+            pot_best.push(Some((best, winners.len())));
+
+            let pot_per_winner = pot.amount / winners.len() as u32;
+            let remainder = pot.amount % winners.len() as u32;
+            let mut pot_awards = Vec::new();
+            for (i, &winner_index) in winners.iter().enumerate() {
+                let mut winnings = pot_per_winner;
+                if i < remainder as usize {
+                    winnings += 1; // Distribuir o resto
+                }
+                self.players[winner_index].chips += winnings;
+                let player_id = self.players[winner_index].id.clone();
+                match awards.iter_mut().find(|award| award.player_id == player_id) {
+                    Some(total) => total.amount += winnings,
+                    None => awards.push(PotAward {
+                        player_id: player_id.clone(),
+                        amount: winnings,
+                    }),
+                }
+                pot_awards.push(PotAward {
+                    player_id,
+                    amount: winnings,
+                });
+            }
+            pot_winners.push(pot_awards);
         }
 
-        // Three of a Kind
-        if counts[0].1 == 3 {
-            return HandEvaluation {
-                rank: HandRank::ThreeOfAKind,
-                kickers: vec![counts[0].0, counts[1].0, counts[2].0],
-                cards: sorted_cards,
-            };
-        }
+        let showdown = evaluations
+            .iter()
+            .map(|(index, eval)| ShowdownRecord {
+                player_id: self.players[*index].id.clone(),
+                hole_cards: self.players[*index].hand.clone(),
+                hand_rank: Some(eval.rank),
+                best_hand: eval.cards.clone(),
+                mucked: false,
+            })
+            .collect();
 
-        // Two Pair
-        if counts[0].1 == 2 && counts[1].1 == 2 {
-            return HandEvaluation {
-                rank: HandRank::TwoPair,
-                kickers: vec![counts[0].0, counts[1].0, counts[2].0],
-                cards: sorted_cards,
-            };
-        }
+        // Criar resultado detalhado, explicando cada mão contra a melhor mão
+        // de cada pot que ela disputou e dizendo quanto levou de cada um
+        let hand_result = |index: usize, eval: &HandEvaluation| {
+            let player = &self.players[index];
+            let mut explanation = None;
+            let player_pots: Vec<serde_json::Value> = pots
+                .iter()
+                .zip(&pot_winners)
+                .zip(&pot_best)
+                .enumerate()
+                .filter(|(_, ((pot, _), _))| pot.eligible.contains(&player.id))
+                .filter_map(|(number, ((pot, winners), best))| {
+                    let (best, tied) = (*best)?;
+                    let won = winners
+                        .iter()
+                        .find(|award| award.player_id == player.id)
+                        .map_or(0, |award| award.amount);
+                    let pot_explanation = evaluator::explain(eval, best, tied);
+                    // Os pots são aninhados: o último que a mão disputa tem a
+                    // disputa mais fraca e resume o resultado dela
+                    explanation = Some(pot_explanation.clone());
+                    Some(serde_json::json!({
+                        "pot": number,
+                        "amount": pot.amount,
+                        "won": won,
+                        "explanation": pot_explanation
+                    }))
+                })
+                .collect();
+            serde_json::json!({
+                "id": player.id,
+                "name": player.name,
+                "hand": player.hand,
+                "best_hand": eval.cards,
+                "hand_rank": eval.rank,
+                "description": evaluator::describe(eval),
+                "explanation": explanation,
+                "pots": player_pots
+            })
+        };
+        let won_anything = |index: &usize| {
+            awards
+                .iter()
+                .any(|a| a.player_id == self.players[*index].id)
+        };
+        let result = serde_json::json!({
+            "type": "showdown",
+            "pot_won": pots.iter().map(|pot| pot.amount).sum::<u32>(),
+            "pots": pots
+                .iter()
+                .zip(&pot_winners)
+                .map(|(pot, winners)| serde_json::json!({
+                    "amount": pot.amount,
+                    "eligible": pot.eligible,
+                    "winners": winners.iter().map(|award| &award.player_id).collect::<Vec<_>>()
+                }))
+                .collect::<Vec<_>>(),
+            "winners": evaluations
+                .iter()
+                .filter(|(index, _)| won_anything(index))
+                .map(|(index, eval)| hand_result(*index, eval))
+                .collect::<Vec<_>>(),
+            "all_hands": evaluations
+                .iter()
+                .map(|(index, eval)| hand_result(*index, eval))
+                .collect::<Vec<_>>()
+        });
 
-        // One Pair
-        if counts[0].1 == 2 {
-            return HandEvaluation {
-                rank: HandRank::OnePair,
-                kickers: vec![counts[0].0, counts[1].0, counts[2].0, counts[3].0],
-                cards: sorted_cards,
-            };
-        }
+        (result, showdown, awards)
+    }
 
-        // High Card
-        HandEvaluation {
-            rank: HandRank::HighCard,
-            kickers: ranks,
-            cards: sorted_cards,
-        }
+    fn begin_hand_history(&mut self) {
+        self.hand_history = Some(HandHistory {
+            hand_id: Uuid::new_v4().to_string(),
+            game_id: self.id.clone(),
+            hand_number: self.hand_number,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+            dealer_index: self.dealer_index,
+            small_blind: self.small_blind,
+            big_blind: self.big_blind,
+            seats: self
+                .players
+                .iter()
+                .enumerate()
+                .map(|(seat, player)| SeatRecord {
+                    seat,
+                    player_id: player.id.clone(),
+                    name: player.name.clone(),
+                    starting_stack: player.chips,
+                    hole_cards: Vec::new(),
+                })
+                .collect(),
+            // O deck só entra no histórico no fim da mão
+            deck: Vec::new(),
+            actions: Vec::new(),
+            board: BoardRecord::default(),
+            showdown: Vec::new(),
+            winnings: Vec::new(),
+            fairness: None,
+            all_in: None,
+            runs: Vec::new(),
+            shown: Vec::new(),
+        });
     }
 
-    fn is_straight(&self, ranks: &[u8]) -> bool {
-        // Verificar sequência normal
-        let mut consecutive = true;
-        for i in 1..ranks.len() {
-            if ranks[i - 1] - ranks[i] != 1 {
-                consecutive = false;
-                break;
+    fn record_hole_cards(&mut self) {
+        if let Some(history) = self.hand_history.as_mut() {
+            for (seat, player) in history.seats.iter_mut().zip(&self.players) {
+                seat.hole_cards = player.hand.clone();
             }
         }
+    }
 
-        if consecutive {
-            return true;
+    fn record_action(&mut self, player_index: usize, kind: ActionKind, amount: u32) {
+        let player = &self.players[player_index];
+        if let Some(history) = self.hand_history.as_mut() {
+            history.actions.push(ActionRecord {
+                street: self.state.clone(),
+                player_id: player.id.clone(),
+                kind,
+                amount,
+                total_bet: player.current_bet,
+                pot_after: self.pot,
+            });
         }
+    }
 
-        // Verificar A-2-3-4-5 (wheel)
-        if ranks == [14, 5, 4, 3, 2] {
-            return true;
+    fn record_board(&mut self) {
+        if let Some(history) = self.hand_history.as_mut() {
+            history.board = BoardRecord {
+                flop: self.community_cards.iter().take(3).cloned().collect(),
+                turn: self.community_cards.get(3).cloned(),
+                river: self.community_cards.get(4).cloned(),
+            };
         }
+    }
 
-        false
+    fn finish_hand_history(
+        &mut self,
+        showdown: Vec<ShowdownRecord>,
+        winnings: Vec<PotAward>,
+    ) -> Result<(), GameError> {
+        if let Some(history) = self.hand_history.as_mut() {
+            history.showdown = showdown;
+            history.winnings = winnings;
+            // A server seed só entra no histórico depois do fim da mão
+            history.fairness = self.fairness.current.clone();
+            history.deck = self.deck.reveal()?;
+            history.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+        Ok(())
     }
+
+    /// Estado do jogo visto de fora da mesa: nenhuma hole card, exceto as
+    /// mostradas depois do fim da mão.
     pub fn get_game_state(&self) -> serde_json::Value {
+        self.game_state_for(None)
+    }
+
+    /// Estado do jogo enviado a um jogador: as cartas dele e as mostradas
+    /// depois do fim da mão. Sem jogador, é a visão pública.
+    pub fn game_state_for(&self, viewer: Option<&str>) -> serde_json::Value {
+        self.game_state(|player_id| viewer == Some(player_id))
+    }
+
+    /// Estado com as cartas de todos. Só para o replay, que esconde depois as
+    /// que o consultante não pode ver.
+    pub(crate) fn full_game_state(&self) -> serde_json::Value {
+        self.game_state(|_| true)
+    }
+
+    fn game_state(&self, sees: impl Fn(&str) -> bool) -> serde_json::Value {
         serde_json::json!({
             "game_id": self.id,
             "state": self.state,
+            "hand_number": self.hand_number,
+            "action_sequence": self.action_sequence,
             "pot": self.pot,
             "current_bet": self.current_bet,
             "current_player": if !self.players.is_empty() {
@@ -673,6 +1368,9 @@ impl Game {
                 None
             },
             "community_cards": self.community_cards,
+            "fairness": self.fairness.public_view(),
+            "run_it": self.run_it,
+            "reveal": self.reveal,
             "players": self.players.iter().map(|p| serde_json::json!({
                 "id": p.id,
                 "name": p.name,
@@ -680,98 +1378,103 @@ impl Game {
                 "current_bet": p.current_bet,
                 "is_folded": p.is_folded,
                 "is_all_in": p.is_all_in,
-                "hand": p.hand // Sempre mostrar as cartas dos jogadores
+                "auto_muck": !self.shows_losing_hands.contains(&p.id),
+                "hand": if sees(&p.id)
+                    || (self.state == GameState::Finished && self.reveal.shown.contains(&p.id))
+                {
+                    p.hand.clone()
+                } else {
+                    Vec::new()
+                }
             })).collect::<Vec<_>>()
         })
     }
 
-    pub fn next_hand(&mut self) {
+    pub fn next_hand(&mut self) -> Result<(), GameError> {
         // Avançar o dealer para o próximo jogador
         self.dealer_index = (self.dealer_index + 1) % self.players.len();
 
         // Começar nova rodada
-        self.start_round();
+        self.start_round()
+    }
+
+    /// Anula a mão em andamento quando o deck selado ou a prova do
+    /// commit-reveal não podem ser abertos (um snapshot restaurado com outra
+    /// chave mestra, por exemplo): cada jogador volta ao stack do início da mão
+    /// e a mão termina sem vencedor nem histórico. Retorna o erro do deck se a
+    /// mão foi anulada.
+    pub fn void_hand_if_deck_unavailable(&mut self) -> Option<GameError> {
+        if self.state == GameState::Finished {
+            return None;
+        }
+        let error = if self.fairness.proof_unavailable {
+            GameError::DeckUnavailable
+        } else {
+            self.deck.open().err()?
+        };
+
+        if let Some(history) = self.hand_history.take() {
+            for seat in history.seats {
+                if let Some(player) = self.players.iter_mut().find(|p| p.id == seat.player_id) {
+                    player.chips = seat.starting_stack;
+                }
+            }
+        }
+        for player in &mut self.players {
+            player.hand.clear();
+            player.current_bet = 0;
+            player.is_folded = false;
+            player.is_all_in = false;
+        }
+        self.community_cards.clear();
+        self.pot = 0;
+        self.current_bet = 0;
+        self.last_raise = 0;
+        self.round_bets.clear();
+        self.run_it = None;
+        self.reveal = HandReveal::default();
+        self.fairness.proof_unavailable = false;
+        self.state = GameState::Finished;
+        self.hand_result = Some(serde_json::json!({
+            "type": "voided",
+            "reason": error.code()
+        }));
+        Some(error)
     }
 
+    /// Resultado da última mão finalizada, sem as cartas de quem deu muck e
+    /// com as escolhas feitas depois do fim da mão (`show_hand`, `rabbit_hunt`).
     pub fn get_hand_result(&self) -> Option<serde_json::Value> {
         if !matches!(self.state, GameState::Finished) {
             return None;
         }
 
-        let active_players: Vec<_> = self
-            .players
-            .iter()
-            .enumerate()
-            .filter(|(_, p)| !p.is_folded)
-            .collect();
-
-        if active_players.len() <= 1 {
-            let winner = &active_players[0].1;
-            return Some(serde_json::json!({
-                "type": "single_winner",
-                "winner": {
-                    "id": winner.id,
-                    "name": winner.name,
-                    "hand": winner.hand
-                },
-                "pot_won": 0 // Pot já foi distribuído
-            }));
-        }
-
-        // Avaliar todas as mãos para mostrar o resultado
-        let mut evaluations: Vec<(usize, HandEvaluation)> = Vec::new();
-
-        for (index, player) in &active_players {
-            let mut all_cards = player.hand.clone();
-            all_cards.extend(self.community_cards.clone());
-            let evaluation = self.evaluate_hand(all_cards);
-            evaluations.push((*index, evaluation));
+        let mut result = self.hand_result.clone()?;
+        self.hide_mucked(&mut result);
+        if let Some(board) = &self.reveal.rabbit_hunt {
+            result["rabbit_hunt"] = serde_json::json!(board);
         }
+        Some(result)
+    }
+}
 
-        // Ordenar por força da mão
-        evaluations.sort_by(|a, b| b.1.cmp(&a.1));
-
-        // Determinar vencedores
-        let best_hand = &evaluations[0].1;
-        let mut winners = Vec::new();
+impl RoomRules {
+    /// Limite de vezes que uma sala pode permitir distribuir o board.
+    pub const MAX_RUNS: u8 = 3;
 
-        for (index, eval) in &evaluations {
-            if eval == best_hand {
-                winners.push((*index, eval.clone()));
-            } else {
-                break;
-            }
+    pub fn new(max_runs: Option<u8>) -> Result<Self, GameError> {
+        match max_runs.unwrap_or(1) {
+            max_runs @ 1..=Self::MAX_RUNS => Ok(Self { max_runs }),
+            _ => Err(GameError::InvalidRunCount),
         }
-
-        Some(serde_json::json!({
-            "type": "showdown",
-            "winners": winners.iter().map(|(index, eval)| {
-                let player = &self.players[*index];
-                serde_json::json!({
-                    "id": player.id,
-                    "name": player.name,
-                    "hand": player.hand,
-                    "best_hand": eval.cards,
-                    "hand_rank": eval.rank
-                })
-            }).collect::<Vec<_>>(),
-            "all_hands": evaluations.iter().map(|(index, eval)| {
-                let player = &self.players[*index];
-                serde_json::json!({
-                    "id": player.id,
-                    "name": player.name,
-                    "hand": player.hand,
-                    "best_hand": eval.cards,
-                    "hand_rank": eval.rank
-                })
-            }).collect::<Vec<_>>()
-        }))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::i18n::{Locale, Message};
+    use crate::notation::parse_cards;
 
     fn create_test_players() -> Vec<Player> {
         vec![
@@ -823,7 +1526,7 @@ mod tests {
         let players = create_test_players();
         let mut game = Game::new(players);
 
-        game.start_round();
+        game.start_round().unwrap();
 
         // Verificar que os blinds foram postados
         assert!(game.pot > 0);
@@ -842,7 +1545,7 @@ mod tests {
     fn test_turn_validation_rejects_wrong_player() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         println!("Current player index: {}", game.current_player_index);
         println!(
@@ -861,14 +1564,14 @@ mod tests {
 
         // Deve retornar erro
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Não é sua vez de jogar");
+        assert_eq!(result.unwrap_err(), GameError::NotYourTurn);
     }
 
     #[test]
     fn test_turn_validation_accepts_correct_player() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         let current_player_id = game.players[game.current_player_index].id.clone();
 
@@ -890,7 +1593,7 @@ mod tests {
     fn test_game_state_does_not_advance_on_invalid_action() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         let initial_state = game.state.clone();
         let initial_community_cards = game.community_cards.len();
@@ -918,7 +1621,7 @@ mod tests {
     fn test_multiple_invalid_actions_do_not_advance_game() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         let initial_state = game.state.clone();
         let initial_community_cards = game.community_cards.len();
@@ -946,7 +1649,7 @@ mod tests {
     fn test_game_progression_after_valid_actions() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         let initial_state = game.state.clone();
         assert_eq!(initial_state, GameState::PreFlop);
@@ -985,7 +1688,7 @@ mod tests {
     fn test_interleaved_invalid_and_valid_actions() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         // Fazer uma ação inválida
         let wrong_player_id = if game.players[game.current_player_index].id == "player1" {
@@ -1019,7 +1722,7 @@ mod tests {
     fn test_betting_round_completion_logic() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         let initial_state = game.state.clone();
         assert_eq!(initial_state, GameState::PreFlop);
@@ -1062,7 +1765,7 @@ mod tests {
     fn test_rapid_invalid_actions_stress_test() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         let initial_state = game.state.clone();
         let initial_community_cards = game.community_cards.len();
@@ -1096,7 +1799,7 @@ mod tests {
     fn test_has_betting_round_completed_logic() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         // No início do PreFlop, há big blind, então current_bet > 0
         assert!(game.current_bet > 0);
@@ -1133,7 +1836,7 @@ mod tests {
     fn test_invalid_actions_should_not_advance_game_state() {
         let players = create_test_players();
         let mut game = Game::new(players);
-        game.start_round();
+        game.start_round().unwrap();
 
         let initial_state = game.state.clone();
         let initial_community_cards = game.community_cards.len();
@@ -1206,9 +1909,6 @@ mod tests {
 
     #[test]
     fn test_hand_evaluation_royal_flush() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1240,15 +1940,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::RoyalFlush);
     }
 
     #[test]
     fn test_hand_evaluation_straight_flush() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1280,15 +1977,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::StraightFlush);
     }
 
     #[test]
     fn test_hand_evaluation_four_of_a_kind() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1320,15 +2014,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::FourOfAKind);
     }
 
     #[test]
     fn test_hand_evaluation_full_house() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1360,15 +2051,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::FullHouse);
     }
 
     #[test]
     fn test_hand_evaluation_flush() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1400,15 +2088,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::Flush);
     }
 
     #[test]
     fn test_hand_evaluation_straight() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1440,15 +2125,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::Straight);
     }
 
     #[test]
     fn test_hand_evaluation_wheel_straight() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1480,15 +2162,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::Straight);
     }
 
     #[test]
     fn test_hand_evaluation_three_of_a_kind() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1520,15 +2199,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::ThreeOfAKind);
     }
 
     #[test]
     fn test_hand_evaluation_two_pair() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1560,15 +2236,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::TwoPair);
     }
 
     #[test]
     fn test_hand_evaluation_one_pair() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1600,15 +2273,12 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::OnePair);
     }
 
     #[test]
     fn test_hand_evaluation_high_card() {
-        let players = create_test_players();
-        let game = Game::new(players);
-
         let cards = vec![
             Card {
                 suit: Suit::Hearts,
@@ -1640,7 +2310,7 @@ mod tests {
             },
         ];
 
-        let evaluation = game.evaluate_hand(cards);
+        let evaluation = evaluator::evaluate(&cards);
         assert_eq!(evaluation.rank, HandRank::HighCard);
     }
 
@@ -1650,7 +2320,7 @@ mod tests {
         let mut game = Game::new(players);
 
         // Começar a rodada
-        game.start_round();
+        game.start_round().unwrap();
 
         // Verificar que os jogadores receberam cartas
         for player in &game.players {
@@ -1705,4 +2375,764 @@ mod tests {
         let total_chips: u32 = game.players.iter().map(|p| p.chips).sum();
         assert!(total_chips > 0);
     }
+
+    fn client_action(action: PlayerAction, action_id: &str) -> GameActionMessage {
+        GameActionMessage {
+            player_id: String::new(),
+            action,
+            action_id: Some(action_id.to_string()),
+            hand_number: Some(1),
+            sequence: None,
+        }
+    }
+
+    #[test]
+    fn test_duplicate_action_id_is_applied_once() {
+        let players = create_test_players();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+
+        let current_player_id = game.players[game.current_player_index].id.clone();
+        let message = client_action(PlayerAction::Call, "acao-1");
+
+        assert!(game
+            .process_client_action(&current_player_id, &message)
+            .is_ok());
+        let pot_after_call = game.pot;
+        let player_after_call = game.current_player_index;
+
+        // Reenvio após falha de rede não deve aplicar a ação de novo
+        let retry = game.process_client_action(&current_player_id, &message);
+        assert_eq!(retry.unwrap_err(), GameError::DuplicateAction);
+        assert_eq!(game.pot, pot_after_call);
+        assert_eq!(game.current_player_index, player_after_call);
+        assert_eq!(game.action_sequence, 1);
+    }
+
+    #[test]
+    fn test_retry_after_the_hand_rolls_over_is_not_reapplied() {
+        let players = create_test_players();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+
+        let current_player_id = game.players[game.current_player_index].id.clone();
+        let mut message = client_action(PlayerAction::Call, "acao-1");
+        message.hand_number = None;
+        let result = game.process_client_action(&current_player_id, &message);
+        assert_eq!(result.unwrap_err(), GameError::MissingHandNumber);
+        assert_eq!(game.action_sequence, 0);
+
+        message.hand_number = Some(1);
+        game.process_client_action(&current_player_id, &message)
+            .unwrap();
+
+        // A mão vira e o mesmo jogador abre a próxima; o reenvio atrasado
+        // ainda aponta para a mão 1
+        game.next_hand().unwrap();
+        while game.players[game.current_player_index].id != current_player_id {
+            let next = game.players[game.current_player_index].id.clone();
+            game.process_action(&next, PlayerAction::Call).unwrap();
+        }
+        let result = game.process_client_action(&current_player_id, &message);
+        assert_eq!(result.unwrap_err(), GameError::StaleAction);
+        assert_eq!(game.hand_number, 2);
+    }
+
+    #[test]
+    fn test_stale_sequence_is_rejected() {
+        let players = create_test_players();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+        assert_eq!(game.hand_number, 1);
+
+        let first_player_id = game.players[game.current_player_index].id.clone();
+        let mut first = client_action(PlayerAction::Call, "acao-1");
+        first.sequence = Some(0);
+        assert!(game.process_client_action(&first_player_id, &first).is_ok());
+
+        // O próximo jogador envia uma ação que ainda espera a sequência 0
+        let next_player_id = game.players[game.current_player_index].id.clone();
+        let mut stale = client_action(PlayerAction::Call, "acao-2");
+        stale.sequence = Some(0);
+        let result = game.process_client_action(&next_player_id, &stale);
+        assert_eq!(result.unwrap_err(), GameError::StaleAction);
+        assert_eq!(game.action_sequence, 1);
+
+        stale.sequence = Some(1);
+        assert!(game.process_client_action(&next_player_id, &stale).is_ok());
+    }
+
+    #[test]
+    fn test_action_for_previous_hand_is_rejected() {
+        let players = create_test_players();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+        game.next_hand().unwrap();
+        assert_eq!(game.hand_number, 2);
+        assert_eq!(game.action_sequence, 0);
+
+        let current_player_id = game.players[game.current_player_index].id.clone();
+        let mut message = client_action(PlayerAction::Call, "acao-1");
+        message.hand_number = Some(1);
+
+        let result = game.process_client_action(&current_player_id, &message);
+        assert_eq!(result.unwrap_err(), GameError::StaleAction);
+    }
+
+    #[test]
+    fn test_raise_below_big_blind_is_rejected() {
+        let players = create_test_players();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+
+        let current_player_id = game.players[game.current_player_index].id.clone();
+        let result = game.process_action(&current_player_id, PlayerAction::Raise(1));
+        assert_eq!(result.unwrap_err(), GameError::RaiseTooSmall);
+
+        let result = game.process_action(&current_player_id, PlayerAction::Raise(game.big_blind));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_reraise_must_match_the_last_raise() {
+        let players = create_test_players();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+
+        // Aumento de 30 sobre o big blind: o próximo raise precisa de 30 ou mais
+        let first = game.players[game.current_player_index].id.clone();
+        game.process_action(&first, PlayerAction::Raise(30))
+            .unwrap();
+        assert_eq!(game.min_raise(), 30);
+
+        let second = game.players[game.current_player_index].id.clone();
+        let result = game.process_action(&second, PlayerAction::Raise(game.big_blind));
+        assert_eq!(result.unwrap_err(), GameError::RaiseTooSmall);
+        game.process_action(&second, PlayerAction::Raise(30))
+            .unwrap();
+
+        // Na rodada seguinte o mínimo volta a ser o big blind
+        while game.state == GameState::PreFlop {
+            let player = game.players[game.current_player_index].id.clone();
+            game.process_action(&player, PlayerAction::Call).unwrap();
+        }
+        assert_eq!(game.min_raise(), game.big_blind);
+    }
+
+    #[test]
+    fn test_action_after_hand_finished_is_rejected() {
+        let players = create_test_players();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+        game.state = GameState::Finished;
+
+        let current_player_id = game.players[game.current_player_index].id.clone();
+        let result = game.process_action(&current_player_id, PlayerAction::Call);
+        assert_eq!(result.unwrap_err(), GameError::HandAlreadyFinished);
+    }
+
+    fn play_to_showdown(game: &mut Game) {
+        while game.state != GameState::Finished {
+            let current_player_id = game.players[game.current_player_index].id.clone();
+            let action = if game.current_bet > game.players[game.current_player_index].current_bet {
+                PlayerAction::Call
+            } else {
+                PlayerAction::Check
+            };
+            game.process_action(&current_player_id, action).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_hand_history_records_complete_hand() {
+        let players = create_test_players();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+
+        // Durante a mão o histórico não expõe o deck
+        assert!(game.hand_history.as_ref().unwrap().deck.is_empty());
+
+        play_to_showdown(&mut game);
+        let history = game.hand_history.clone().unwrap();
+        assert_eq!(history.deck.len(), 52);
+
+        assert!(history.is_finished());
+        assert_eq!(history.hand_number, 1);
+        assert_eq!(history.seats.len(), 3);
+        for seat in &history.seats {
+            assert_eq!(seat.starting_stack, 1000);
+            assert_eq!(seat.hole_cards.len(), 2);
+        }
+
+        // Blinds são as duas primeiras ações
+        assert_eq!(history.actions[0].kind, ActionKind::SmallBlind);
+        assert_eq!(history.actions[0].amount, 5);
+        assert_eq!(history.actions[1].kind, ActionKind::BigBlind);
+        assert_eq!(history.actions[1].amount, 10);
+
+        assert_eq!(history.board.flop.len(), 3);
+        assert!(history.board.turn.is_some());
+        assert!(history.board.river.is_some());
+        assert_eq!(history.board.cards().len(), 5);
+
+        assert_eq!(history.showdown.len(), 3);
+        let total_put_in: u32 = history.actions.iter().map(|a| a.amount).sum();
+        let total_won: u32 = history.winnings.iter().map(|w| w.amount).sum();
+        assert_eq!(total_put_in, 30);
+        assert_eq!(total_won, total_put_in);
+    }
+
+    #[test]
+    fn test_hand_history_records_action_amounts_per_street() {
+        let players = create_test_players();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+
+        let raiser = game.players[game.current_player_index].id.clone();
+        game.process_action(&raiser, PlayerAction::Raise(20))
+            .unwrap();
+        let folder = game.players[game.current_player_index].id.clone();
+        game.process_action(&folder, PlayerAction::Fold).unwrap();
+
+        let history = game.hand_history.as_ref().unwrap();
+        let raise = &history.actions[2];
+        assert_eq!(raise.street, GameState::PreFlop);
+        assert_eq!(raise.kind, ActionKind::Raise);
+        assert_eq!(raise.player_id, raiser);
+        assert_eq!(raise.amount, 30); // 10 para pagar + 20 de aumento
+        assert_eq!(raise.total_bet, 30);
+        assert_eq!(raise.pot_after, 45);
+
+        let fold = &history.actions[3];
+        assert_eq!(fold.kind, ActionKind::Fold);
+        assert_eq!(fold.amount, 0);
+    }
+
+    #[test]
+    fn test_seeded_games_deal_the_same_cards() {
+        let deal = |seed| {
+            let mut game = Game::new(create_test_players());
+            game.deck_source = DeckSource::Seeded { seed };
+            game.start_round().unwrap();
+            play_to_showdown(&mut game);
+            let history = game.hand_history.unwrap();
+            (history.seats, history.board.cards())
+        };
+
+        let (seats_a, board_a) = deal(1234);
+        let (seats_b, board_b) = deal(1234);
+        assert_eq!(board_a, board_b);
+        for (a, b) in seats_a.iter().zip(&seats_b) {
+            assert_eq!(a.hole_cards, b.hole_cards);
+        }
+        let (_, other_board) = deal(4321);
+        assert_ne!(board_a, other_board);
+    }
+
+    #[test]
+    fn test_all_in_runs_out_the_board_and_records_equity() {
+        let mut game = Game::new(create_test_players());
+        // player1 recebe AA, player2 KK
+        let stacked = ["Ah", "Kc", "2d", "Ad", "Ks", "7c"].map(String::from);
+        game.deck_source = DeckSource::stacked(&stacked).unwrap();
+        game.start_round().unwrap();
+
+        assert!(game
+            .process_action("player1", PlayerAction::AllIn)
+            .unwrap()
+            .is_none());
+        assert!(game
+            .process_action("player2", PlayerAction::Call)
+            .unwrap()
+            .is_none());
+        // O big blind ainda precisa responder ao all-in
+        assert_eq!(game.state, GameState::PreFlop);
+        assert_eq!(game.players[game.current_player_index].id, "player3");
+
+        let result = game.process_action("player3", PlayerAction::Fold).unwrap();
+        assert!(result.is_some());
+        assert_eq!(game.state, GameState::Finished);
+        assert_eq!(game.community_cards.len(), 5);
+
+        // A equity fica pendente até ser calculada, fora do motor
+        let request = game.all_in_equity_request().unwrap();
+        assert!(game
+            .hand_history
+            .as_ref()
+            .unwrap()
+            .all_in
+            .as_ref()
+            .unwrap()
+            .players
+            .is_empty());
+        let mut late = game.clone();
+        late.hand_number += 1;
+        late.set_all_in_equity(&request, request.calculate());
+        assert!(late.all_in_equity_request().is_some());
+        game.calculate_all_in_equity();
+        assert!(game.all_in_equity_request().is_none());
+
+        let history = game.hand_history.unwrap();
+        let all_in = history.all_in.as_ref().unwrap();
+        assert_eq!(all_in.street, GameState::PreFlop);
+        assert!(all_in.board.is_empty());
+        assert_eq!(all_in.pot, 2010);
+        assert_eq!(all_in.players.len(), 2);
+        assert!((all_in.players[0].equity - 0.82).abs() < 0.02);
+        let ev: f64 = all_in.players.iter().map(|p| p.ev).sum();
+        assert!((ev - 2010.0).abs() < 1e-6);
+
+        assert_eq!(history.ev_net("player1"), all_in.players[0].ev - 1000.0);
+        assert_eq!(history.ev_net("player3"), -10.0);
+        assert_eq!(history.net("player3"), -10);
+    }
+
+    #[test]
+    fn test_raise_with_every_chip_is_an_all_in() {
+        let mut game = Game::new(create_test_players());
+        let stacked = ["Ah", "Kc", "2d", "Ad", "Ks", "7c"].map(String::from);
+        game.deck_source = DeckSource::stacked(&stacked).unwrap();
+        game.start_round().unwrap();
+
+        // 10 para pagar o big blind e 990 de aumento: as 1000 fichas
+        game.process_action("player1", PlayerAction::Raise(990))
+            .unwrap();
+        assert_eq!(game.players[0].chips, 0);
+        assert!(game.players[0].is_all_in);
+        game.process_action("player2", PlayerAction::Call).unwrap();
+        assert!(game
+            .process_action("player3", PlayerAction::Fold)
+            .unwrap()
+            .is_some());
+
+        assert!(game.all_in_equity_request().is_some());
+        game.calculate_all_in_equity();
+        let history = game.hand_history.as_ref().unwrap();
+        let all_in = history.all_in.as_ref().unwrap();
+        assert_eq!(all_in.street, GameState::PreFlop);
+        assert_eq!(all_in.pot, 2010);
+        assert_eq!(all_in.players.len(), 2);
+        assert!((all_in.players[0].equity - 0.82).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_short_all_in_ev_counts_only_the_pots_it_can_win() {
+        let mut players = create_test_players();
+        players[0].chips = 100;
+        let mut game = Game::new(players);
+        // player1 recebe AA, player2 KK e player3 72
+        let stacked = ["Ah", "Kc", "7d", "Ad", "Ks", "2c"].map(String::from);
+        game.deck_source = DeckSource::stacked(&stacked).unwrap();
+        game.start_round().unwrap();
+        game.process_action("player1", PlayerAction::AllIn).unwrap();
+        game.process_action("player2", PlayerAction::AllIn).unwrap();
+        game.process_action("player3", PlayerAction::Call).unwrap();
+        game.calculate_all_in_equity();
+
+        let history = game.hand_history.as_ref().unwrap();
+        let players = &history.all_in.as_ref().unwrap().players;
+        let ev = |id: &str| players.iter().find(|p| p.player_id == id).unwrap().ev;
+        // Os ases só disputam o pot principal de 300, mesmo com a maior equity
+        assert!(players[0].equity > 0.6);
+        assert!(ev("player1") < 300.0);
+        // O side pot de 1800 fica entre KK e 72
+        assert!(ev("player2") > 0.8 * 1800.0);
+        let total: f64 = players.iter().map(|p| p.ev).sum();
+        assert!((total - 2100.0).abs() < 1e-6);
+    }
+
+    fn all_in_with_runs(max_runs: u8) -> Game {
+        let mut game = Game::new(create_test_players());
+        game.rules = RoomRules::new(Some(max_runs)).unwrap();
+        let stacked = ["Ah", "Kc", "2d", "Ad", "Ks", "7c"].map(String::from);
+        game.deck_source = DeckSource::stacked(&stacked).unwrap();
+        game.start_round().unwrap();
+
+        game.process_action("player1", PlayerAction::AllIn).unwrap();
+        game.process_action("player2", PlayerAction::Call).unwrap();
+        assert!(game
+            .process_action("player3", PlayerAction::Fold)
+            .unwrap()
+            .is_none());
+        game
+    }
+
+    #[test]
+    fn test_all_in_players_run_it_twice() {
+        let mut game = all_in_with_runs(2);
+        let vote = game.run_it.clone().unwrap();
+        assert_eq!(vote.players, ["player1", "player2"]);
+        assert_eq!(vote.max_runs, 2);
+        assert!(game.hand_history.as_ref().unwrap().all_in.is_some());
+
+        assert_eq!(
+            game.process_action("player1", PlayerAction::Check),
+            Err(GameError::RunItPending)
+        );
+        assert_eq!(
+            game.choose_runs("player3", 2),
+            Err(GameError::RunItNotAvailable)
+        );
+        assert_eq!(
+            game.choose_runs("player1", 3),
+            Err(GameError::InvalidRunCount)
+        );
+
+        assert!(game.choose_runs("player1", 2).unwrap().is_none());
+        let result = game.choose_runs("player2", 2).unwrap().unwrap();
+        assert_eq!(result["type"], "run_it");
+        assert_eq!(result["pot_won"], 2010);
+        let runs = result["runs"].as_array().unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0]["pot_won"], 1005);
+        assert_eq!(runs[1]["pot_won"], 1005);
+        assert_eq!(game.state, GameState::Finished);
+        assert!(game.run_it.is_none());
+
+        let chips: u32 = game.players.iter().map(|p| p.chips).sum();
+        assert_eq!(chips, 3000);
+
+        let history = game.hand_history.clone().unwrap();
+        assert_eq!(history.runs.len(), 2);
+        let (first, second) = (&history.runs[0].board, &history.runs[1].board);
+        assert_eq!(first.len(), 5);
+        assert!(first.iter().all(|card| !second.contains(card)));
+        assert_eq!(history.board.cards(), *first);
+        let won: u32 = history.winnings.iter().map(|w| w.amount).sum();
+        assert_eq!(won, 2010);
+
+        assert!(crate::replay::replay_hand(&history).unwrap().consistent);
+    }
+
+    #[test]
+    fn test_run_it_uses_the_smallest_vote() {
+        let mut game = all_in_with_runs(3);
+        game.choose_runs("player1", 3).unwrap();
+        let result = game.choose_runs("player2", 1).unwrap().unwrap();
+        assert_eq!(result["type"], "showdown");
+        assert_eq!(game.state, GameState::Finished);
+        assert!(game.hand_history.unwrap().runs.is_empty());
+
+        assert_eq!(RoomRules::new(None).unwrap().max_runs, 1);
+        assert_eq!(RoomRules::new(Some(0)), Err(GameError::InvalidRunCount));
+    }
+
+    #[test]
+    fn test_expired_run_it_vote_runs_the_board_once() {
+        let mut game = all_in_with_runs(2);
+        assert!(game.choose_runs("player1", 2).unwrap().is_none());
+
+        // player2 nunca vota: no fim do prazo o voto dele conta como 1
+        let result = game.expire_run_it_vote().unwrap().unwrap();
+        assert_eq!(result["type"], "showdown");
+        assert_eq!(game.state, GameState::Finished);
+        assert!(game.run_it.is_none());
+        assert_eq!(game.expire_run_it_vote(), Err(GameError::RunItNotAvailable));
+    }
+
+    #[test]
+    fn test_player_leaving_the_run_it_vote_votes_for_one_run() {
+        let mut game = all_in_with_runs(2);
+        assert!(game.forfeit_run_it("player3").is_err());
+        assert!(game.forfeit_run_it("player1").unwrap().is_none());
+        // Quem já votou não perde o voto ao sair
+        assert!(game.forfeit_run_it("player1").is_err());
+
+        let result = game.choose_runs("player2", 2).unwrap().unwrap();
+        assert_eq!(result["type"], "showdown");
+        assert_eq!(game.state, GameState::Finished);
+    }
+
+    /// player1 recebe AA, player2 KK e player3 72; o board é Qc 8d 4h Js 3c.
+    fn stacked_reveal_game() -> Game {
+        let mut game = Game::new(create_test_players());
+        let stacked = [
+            "Ah", "Kc", "7d", "Ad", "Ks", "2c", "Qc", "8d", "4h", "Js", "3c",
+        ]
+        .map(String::from);
+        game.deck_source = DeckSource::stacked(&stacked).unwrap();
+        game.start_round().unwrap();
+        game
+    }
+
+    #[test]
+    fn test_uncontested_winner_chooses_to_show_and_rabbit_hunt() {
+        let mut game = stacked_reveal_game();
+        assert_eq!(game.show_hand("player1"), Err(GameError::HandNotFinished));
+        assert_eq!(game.rabbit_hunt("player2"), Err(GameError::HandNotFinished));
+
+        game.process_action("player1", PlayerAction::Raise(20))
+            .unwrap();
+        game.process_action("player2", PlayerAction::Fold).unwrap();
+        let result = game
+            .process_action("player3", PlayerAction::Fold)
+            .unwrap()
+            .unwrap();
+        assert_eq!(result["type"], "single_winner");
+        assert_eq!(result["winner"]["shown"], false);
+        assert!(result["winner"].get("hand").is_none());
+        let state = game.get_game_state();
+        assert!(state["players"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|p| p["hand"] == serde_json::json!([])));
+
+        let board = game.rabbit_hunt("player2").unwrap();
+        assert_eq!(board, parse_cards("Qc 8d 4h Js 3c").unwrap());
+        assert_eq!(game.rabbit_hunt("player3").unwrap(), board);
+        assert_eq!(game.rabbit_hunt("ghost"), Err(GameError::PlayerNotInRoom));
+        assert!(game.community_cards.is_empty());
+
+        let shown = game.show_hand("player1").unwrap();
+        assert_eq!(
+            shown["hand"],
+            serde_json::json!(parse_cards("Ah Ad").unwrap())
+        );
+        let result = game.get_hand_result().unwrap();
+        assert_eq!(result["winner"]["shown"], true);
+        assert_eq!(result["rabbit_hunt"], serde_json::json!(board));
+        assert_eq!(game.get_game_state()["players"][0]["hand"], shown["hand"]);
+    }
+
+    #[test]
+    fn test_showdown_starts_with_last_aggressor_and_losers_muck() {
+        let mut game = stacked_reveal_game();
+        game.process_action("player1", PlayerAction::Call).unwrap();
+        game.process_action("player2", PlayerAction::Call).unwrap();
+        for _ in 0..2 {
+            for player in ["player2", "player3", "player1"] {
+                game.process_action(player, PlayerAction::Check).unwrap();
+            }
+        }
+        game.process_action("player2", PlayerAction::Check).unwrap();
+        game.process_action("player3", PlayerAction::Raise(20))
+            .unwrap();
+        game.process_action("player1", PlayerAction::Call).unwrap();
+        let result = game
+            .process_action("player2", PlayerAction::Call)
+            .unwrap()
+            .unwrap();
+
+        // player3 apostou por último e abre primeiro; player1 ganha dele e
+        // mostra; player2 perde para os ases e vai para o muck
+        assert_eq!(game.reveal.show_order, ["player3", "player1", "player2"]);
+        assert_eq!(game.reveal.shown, ["player3", "player1"]);
+        let mucked: Vec<_> = result["all_hands"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|hand| hand["shown"] == false)
+            .collect();
+        assert_eq!(mucked.len(), 1);
+        assert_eq!(mucked[0]["id"], "player2");
+        assert!(mucked[0].get("hand").is_none());
+        assert_eq!(result["winners"][0]["id"], "player1");
+        assert_eq!(result["winners"][0]["shown"], true);
+
+        let history = game.hand_history.as_ref().unwrap();
+        let order: Vec<_> = history
+            .showdown
+            .iter()
+            .map(|record| (record.player_id.as_str(), record.mucked))
+            .collect();
+        assert_eq!(
+            order,
+            [("player3", false), ("player1", false), ("player2", true)]
+        );
+        assert_eq!(
+            game.rabbit_hunt("player2"),
+            Err(GameError::RabbitHuntNotAvailable)
+        );
+    }
+
+    #[test]
+    fn test_checked_river_ignores_aggression_on_the_turn() {
+        let mut game = stacked_reveal_game();
+        game.process_action("player1", PlayerAction::Call).unwrap();
+        game.process_action("player2", PlayerAction::Call).unwrap();
+        for player in ["player2", "player3", "player1"] {
+            game.process_action(player, PlayerAction::Check).unwrap();
+        }
+        game.process_action("player2", PlayerAction::Check).unwrap();
+        game.process_action("player3", PlayerAction::Raise(20))
+            .unwrap();
+        game.process_action("player1", PlayerAction::Call).unwrap();
+        game.process_action("player2", PlayerAction::Call).unwrap();
+        for player in ["player2", "player3", "player1"] {
+            game.process_action(player, PlayerAction::Check).unwrap();
+        }
+
+        // A aposta do turn não conta: sem apostas no river, abre primeiro
+        // quem vem depois do dealer
+        assert_eq!(game.state, GameState::Finished);
+        assert_eq!(game.reveal.show_order, ["player2", "player3", "player1"]);
+        assert_eq!(game.reveal.shown, ["player2", "player1"]);
+    }
+
+    #[test]
+    fn test_all_in_run_out_opens_every_hand() {
+        let mut game = stacked_reveal_game();
+        game.process_action("player1", PlayerAction::AllIn).unwrap();
+        game.process_action("player2", PlayerAction::Call).unwrap();
+        let result = game
+            .process_action("player3", PlayerAction::Call)
+            .unwrap()
+            .unwrap();
+
+        // player1 foi o último agressor e abre primeiro; depois do all-in
+        // até o 72 de player3, que não ganha de ninguém, fica aberto
+        assert_eq!(game.reveal.show_order, ["player1", "player2", "player3"]);
+        assert_eq!(game.reveal.shown, ["player1", "player2", "player3"]);
+        assert!(result["all_hands"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|hand| hand["shown"] == true && hand.get("hand").is_some()));
+        let history = game.hand_history.as_ref().unwrap();
+        assert!(history.showdown.iter().all(|record| !record.mucked));
+    }
+
+    #[test]
+    fn test_player_without_auto_muck_shows_losing_hand() {
+        let mut game = stacked_reveal_game();
+        assert_eq!(
+            game.set_auto_muck("ninguem", false),
+            Err(GameError::PlayerNotInRoom)
+        );
+        game.set_auto_muck("player2", false).unwrap();
+        assert_eq!(game.get_game_state()["players"][1]["auto_muck"], false);
+
+        game.process_action("player1", PlayerAction::Call).unwrap();
+        game.process_action("player2", PlayerAction::Call).unwrap();
+        for _ in 0..3 {
+            for player in ["player2", "player3", "player1"] {
+                game.process_action(player, PlayerAction::Check).unwrap();
+            }
+        }
+
+        // Sem apostas no river a ordem começa depois do dealer. player2 perde
+        // para os ases, mas desligou o auto-muck e abre na sua vez
+        assert_eq!(game.state, GameState::Finished);
+        assert_eq!(game.reveal.show_order, ["player2", "player3", "player1"]);
+        assert_eq!(game.reveal.shown, ["player2", "player1"]);
+        let history = game.hand_history.as_ref().unwrap();
+        assert!(history.was_shown("player2"));
+        assert!(!history.was_shown("player3"));
+
+        // Religado, volta a valer o muck automático
+        game.set_auto_muck("player2", true).unwrap();
+        assert!(game.shows_losing_hands.is_empty());
+    }
+
+    #[test]
+    fn test_short_all_in_wins_only_the_main_pot() {
+        let mut players = create_test_players();
+        players[0].chips = 100;
+        let mut game = Game::new(players);
+        let stacked = [
+            "Ah", "Kc", "7d", "Ad", "Ks", "2c", "Qc", "8d", "4h", "Js", "3c",
+        ]
+        .map(String::from);
+        game.deck_source = DeckSource::stacked(&stacked).unwrap();
+        game.start_round().unwrap();
+
+        game.process_action("player1", PlayerAction::AllIn).unwrap();
+        game.process_action("player2", PlayerAction::AllIn).unwrap();
+        let result = game
+            .process_action("player3", PlayerAction::Call)
+            .unwrap()
+            .unwrap();
+
+        // Os ases de player1 só disputam o que ele cobriu; o side pot fica
+        // com os reis de player2
+        assert_eq!(result["pot_won"], 2100);
+        assert_eq!(
+            result["pots"],
+            serde_json::json!([
+                {"amount": 300, "eligible": ["player1", "player2", "player3"], "winners": ["player1"]},
+                {"amount": 1800, "eligible": ["player2", "player3"], "winners": ["player2"]}
+            ])
+        );
+        let chips: Vec<u32> = game.players.iter().map(|p| p.chips).collect();
+        assert_eq!(chips, [300, 1800, 0]);
+
+        let hands = result["all_hands"].as_array().unwrap();
+        let hand = |id: &str| hands.iter().find(|hand| hand["id"] == id).unwrap();
+        let pots = |id: &str| -> Vec<(u64, u64, u64, &str)> {
+            hand(id)["pots"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|pot| {
+                    (
+                        pot["pot"].as_u64().unwrap(),
+                        pot["amount"].as_u64().unwrap(),
+                        pot["won"].as_u64().unwrap(),
+                        pot["explanation"]["verdict"]["outcome"].as_str().unwrap(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(pots("player1"), [(0, 300, 300, "won")]);
+        assert_eq!(
+            pots("player2"),
+            [(0, 300, 0, "lost_on_rank"), (1, 1800, 1800, "won")]
+        );
+
+        // Os reis perdem o main pot para os ases, mas são a melhor mão do
+        // side pot: a explicação da mão é a do pot que ela ganhou
+        let kings = &hand("player2")["explanation"];
+        assert_eq!(kings["verdict"]["outcome"], "won");
+        let main_pot: Message =
+            serde_json::from_value(hand("player2")["pots"][0]["explanation"]["summary"].clone())
+                .unwrap();
+        assert_eq!(
+            main_pot.render(Locale::En),
+            "Pair of Kings loses to Pair of Aces: Ace beats King"
+        );
+        let winners: Vec<_> = result["winners"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hand| hand["id"].clone())
+            .collect();
+        assert_eq!(winners, ["player1", "player2"]);
+        let history = game.hand_history.as_ref().unwrap();
+        assert_eq!(history.won("player2"), 1800);
+    }
+
+    #[test]
+    fn test_showdown_explains_hands_and_reports_pots() {
+        let mut game = stacked_reveal_game();
+        game.process_action("player1", PlayerAction::Call).unwrap();
+        game.process_action("player2", PlayerAction::Call).unwrap();
+        for _ in 0..3 {
+            for player in ["player2", "player3", "player1"] {
+                game.process_action(player, PlayerAction::Check).unwrap();
+            }
+        }
+        let result = game.get_hand_result().unwrap();
+        assert_eq!(result["pot_won"], 30);
+        assert_eq!(result["pots"][0]["winners"], serde_json::json!(["player1"]));
+
+        let hands = result["all_hands"].as_array().unwrap();
+        let hand = |id: &str| hands.iter().find(|hand| hand["id"] == id).unwrap();
+        assert_eq!(hand("player1")["explanation"]["verdict"]["outcome"], "won");
+        assert_eq!(hand("player1")["pots"][0]["won"], 30);
+
+        // player2 abre primeiro (sem apostas no river) e perde o pot com
+        // os reis; player3 não ganha dos ases e vai para o muck
+        let kings = &hand("player2")["explanation"];
+        assert_eq!(kings["verdict"]["outcome"], "lost_on_rank");
+        let summary: Message = serde_json::from_value(kings["summary"].clone()).unwrap();
+        assert_eq!(summary.key, "showdown.lost_on_rank");
+        assert_eq!(
+            summary.render(Locale::En),
+            "Pair of Kings loses to Pair of Aces: Ace beats King"
+        );
+        assert_eq!(hand("player2")["pots"][0]["won"], 0);
+        assert!(hand("player3").get("explanation").is_none());
+        assert_eq!(hand("player3")["pots"][0]["won"], 0);
+    }
 }
@@ -1,25 +1,67 @@
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
 };
 use std::collections::HashMap;
 use uuid::Uuid;
 
-use crate::{models::*, AppState};
+use crate::{
+    auth::{bearer_token, hash_token, player_with_token, verify_player},
+    error::GameError,
+    fairness::FairnessState,
+    history::{HandHistory, HistoryViewer},
+    i18n::{translate, Locale},
+    models::*,
+    ohh::OhhDocument,
+    shuffle::DeckSource,
+    websocket::{
+        broadcast_game_state, handle_auto_muck, handle_game_action, handle_rabbit_hunt,
+        handle_run_it, handle_show_hand,
+    },
+    AppState,
+};
 
-pub async fn health_check() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "status": "healthy",
-        "service": "poker-api",
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    }))
+/// Prefixo da sala usada no armazenamento para as mãos que cada jogador
+/// importou de outros sites (`imported:<player_id>`).
+const IMPORTED_HANDS_ROOM: &str = "imported";
+
+/// Quantas mãos importadas cada jogador pode manter gravadas.
+const MAX_IMPORTED_HANDS: usize = 200;
+
+/// Saúde do serviço. Enquanto as gravações no banco ou no journal estiverem
+/// falhando a resposta é `503` com `status: "degraded"` e o último erro.
+pub async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
+    let storage = state.storage_health();
+    let (status, label) = if storage.consecutive_failures == 0 {
+        (StatusCode::OK, "healthy")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "degraded")
+    };
+
+    (
+        status,
+        Json(serde_json::json!({
+            "status": label,
+            "service": "poker-api",
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "storage": storage
+        })),
+    )
 }
 
 pub async fn create_room(
     State(state): State<AppState>,
     Json(request): Json<CreateRoomRequest>,
-) -> Result<Json<CreateRoomResponse>, StatusCode> {
+) -> Result<Json<CreateRoomResponse>, GameError> {
+    let deck_source = match (request.seed, request.stacked_deck) {
+        (None, None) => DeckSource::Random,
+        _ if !state.test_mode => return Err(GameError::TestModeDisabled),
+        (_, Some(cards)) => DeckSource::stacked(&cards)?,
+        (Some(seed), None) => DeckSource::Seeded { seed },
+    };
+    let rules = RoomRules::new(request.max_runs)?;
+
     let room_id = Uuid::new_v4().to_string()[..8].to_string();
     let player_id = Uuid::new_v4().to_string();
 
@@ -33,8 +75,13 @@ pub async fn create_room(
         is_all_in: false,
     };
 
+    let player_token = Uuid::new_v4().to_string();
+    let fairness = FairnessState::default();
+    let next_commitment = fairness.next_commitment();
     let mut players = HashMap::new();
     players.insert(player_id.clone(), creator);
+    let mut player_tokens = HashMap::new();
+    player_tokens.insert(player_id.clone(), hash_token(&player_token));
     let room = Room {
         id: room_id.clone(),
         creator_id: player_id.clone(),
@@ -42,32 +89,39 @@ pub async fn create_room(
         game: None,
         max_players: request.max_players.unwrap_or(6),
         websocket_senders: HashMap::new(),
+        player_tokens,
+        deck_source,
+        fairness,
+        rules,
     };
+    let written = state.persist(&room);
     state.rooms.insert(room_id.clone(), room);
+    written.written().await;
 
-    Ok(Json(CreateRoomResponse { room_id, player_id }))
+    Ok(Json(CreateRoomResponse {
+        room_id,
+        player_id,
+        player_token,
+        next_commitment,
+    }))
 }
 
 pub async fn join_room(
     Path(room_id): Path<String>,
     State(state): State<AppState>,
+    locale: Locale,
     Json(request): Json<JoinRoomRequest>,
-) -> Result<Json<JoinRoomResponse>, StatusCode> {
-    let mut room = state.rooms.get_mut(&room_id).ok_or(StatusCode::NOT_FOUND)?;
+) -> Result<Json<JoinRoomResponse>, GameError> {
+    let mut room = state
+        .rooms
+        .get_mut(&room_id)
+        .ok_or(GameError::RoomNotFound)?;
     if room.players.len() >= room.max_players {
-        return Ok(Json(JoinRoomResponse {
-            success: false,
-            message: "Sala lotada".to_string(),
-            player_id: None,
-        }));
+        return Err(GameError::RoomFull);
     }
 
     if room.game.is_some() {
-        return Ok(Json(JoinRoomResponse {
-            success: false,
-            message: "Jogo já iniciado".to_string(),
-            player_id: None,
-        }));
+        return Err(GameError::GameAlreadyStarted);
     }
 
     let player_id = Uuid::new_v4().to_string();
@@ -80,7 +134,11 @@ pub async fn join_room(
         is_folded: false,
         is_all_in: false,
     };
+    let player_token = Uuid::new_v4().to_string();
     room.players.insert(player_id.clone(), player);
+    room.player_tokens
+        .insert(player_id.clone(), hash_token(&player_token));
+    let written = state.persist(&room);
 
     // Notificar outros jogadores via WebSocket
     let message = serde_json::json!({
@@ -91,56 +149,59 @@ pub async fn join_room(
     });
 
     for sender in room.websocket_senders.values() {
-        let _ = sender.send(message.to_string());
+        let _ = sender.send(message.clone());
     }
+    drop(room);
+    written.written().await;
 
     Ok(Json(JoinRoomResponse {
         success: true,
-        message: "Entrou na sala com sucesso".to_string(),
+        message: translate(locale, "room_joined").to_string(),
         player_id: Some(player_id),
+        player_token: Some(player_token),
     }))
 }
 
+/// Inicia o jogo. O estado retornado traz as hole cards de quem enviar o
+/// próprio token em `Authorization: Bearer <token>`.
 pub async fn start_game(
     Path(room_id): Path<String>,
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    let mut room = state.rooms.get_mut(&room_id).ok_or(StatusCode::NOT_FOUND)?;
+    locale: Locale,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, GameError> {
+    let mut room = state
+        .rooms
+        .get_mut(&room_id)
+        .ok_or(GameError::RoomNotFound)?;
 
     if room.players.len() < 2 {
-        return Ok(Json(serde_json::json!({
-            "success": false,
-            "message": "Precisa de pelo menos 2 jogadores para iniciar"
-        })));
+        return Err(GameError::NotEnoughPlayers);
     }
 
     if room.game.is_some() {
-        return Ok(Json(serde_json::json!({
-            "success": false,
-            "message": "Jogo já iniciado"
-        })));
+        return Err(GameError::GameAlreadyStarted);
     }
     let players: Vec<Player> = room.players.values().cloned().collect();
     let mut game = crate::models::Game::new(players);
-    game.start_round();
+    game.deck_source = room.deck_source.clone();
+    game.fairness = room.fairness.clone();
+    game.rules = room.rules.clone();
+    game.start_round()?;
 
-    let game_state = game.get_game_state();
+    let game_state = game.game_state_for(viewer(&room, &headers).as_deref());
 
     // Notificar todos os jogadores via WebSocket
-    let message = serde_json::json!({
-        "type": "game_started",
-        "data": game_state
-    });
-
-    for sender in room.websocket_senders.values() {
-        let _ = sender.send(message.to_string());
-    }
+    broadcast_game_state(&room.websocket_senders, "game_started", &game);
 
     room.game = Some(game);
+    let written = state.persist(&room);
+    drop(room);
+    written.written().await;
 
     Ok(Json(serde_json::json!({
         "success": true,
-        "message": "Jogo iniciado",
+        "message": translate(locale, "game_started"),
         "game_state": game_state
     })))
 }
@@ -148,55 +209,418 @@ pub async fn start_game(
 pub async fn get_hand_result(
     Path(room_id): Path<String>,
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    let room = state.rooms.get(&room_id).ok_or(StatusCode::NOT_FOUND)?;
+) -> Result<Json<serde_json::Value>, GameError> {
+    let room = state.rooms.get(&room_id).ok_or(GameError::RoomNotFound)?;
 
-    let game = room.game.as_ref().ok_or(StatusCode::BAD_REQUEST)?;
+    let game = room.game.as_ref().ok_or(GameError::GameNotStarted)?;
 
-    if let Some(result) = game.get_hand_result() {
-        Ok(Json(serde_json::json!({
-            "success": true,
-            "result": result
-        })))
-    } else {
-        Ok(Json(serde_json::json!({
-            "success": false,
-            "message": "Jogo ainda não terminou"
-        })))
-    }
+    let result = game.get_hand_result().ok_or(GameError::HandNotFinished)?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "result": result
+    })))
 }
 
+/// Inicia a próxima mão. Como em `start_game`, o estado retornado traz as
+/// hole cards de quem enviar o próprio token.
 pub async fn next_hand(
     Path(room_id): Path<String>,
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    let mut room = state.rooms.get_mut(&room_id).ok_or(StatusCode::NOT_FOUND)?;
+    locale: Locale,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, GameError> {
+    let mut guard = state
+        .rooms
+        .get_mut(&room_id)
+        .ok_or(GameError::RoomNotFound)?;
+    let viewer = viewer(&guard, &headers);
 
-    let game = room.game.as_mut().ok_or(StatusCode::BAD_REQUEST)?;
+    let room = &mut *guard;
+    let game = room.game.as_mut().ok_or(GameError::GameNotStarted)?;
 
     if !matches!(game.state, GameState::Finished) {
-        return Ok(Json(serde_json::json!({
-            "success": false,
-            "message": "Mão atual ainda não terminou"
-        })));
+        return Err(GameError::HandNotFinished);
     }
 
-    game.next_hand();
-    let game_state = game.get_game_state();
+    game.next_hand()?;
+    let game_state = game.game_state_for(viewer.as_deref());
 
     // Notificar todos os jogadores via WebSocket
-    let message = serde_json::json!({
-        "type": "new_hand_started",
-        "data": game_state
-    });
+    broadcast_game_state(&room.websocket_senders, "new_hand_started", game);
+    let written = state.persist(room);
+    drop(guard);
+    written.written().await;
 
-    for sender in room.websocket_senders.values() {
-        let _ = sender.send(message.to_string());
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": translate(locale, "new_hand_started"),
+        "game_state": game_state
+    })))
+}
+
+/// Envia uma ação de jogo via HTTP. Exige o token do jogador no header
+/// `Authorization: Bearer <token>` e responde com o resultado da validação.
+pub async fn submit_action(
+    Path(room_id): Path<String>,
+    State(state): State<AppState>,
+    locale: Locale,
+    headers: HeaderMap,
+    Json(request): Json<GameActionMessage>,
+) -> Result<Json<serde_json::Value>, GameError> {
+    {
+        let room = state.rooms.get(&room_id).ok_or(GameError::RoomNotFound)?;
+        authorize(&room, &headers, &request.player_id)?;
     }
 
+    let game_state = handle_game_action(&state, &room_id, &request.player_id, &request).await?;
+
     Ok(Json(serde_json::json!({
         "success": true,
-        "message": "Nova mão iniciada",
+        "message": translate(locale, "action_registered"),
         "game_state": game_state
     })))
 }
+
+/// Voto de um jogador all-in em quantas vezes distribuir o resto do board.
+pub async fn run_it(
+    Path(room_id): Path<String>,
+    State(state): State<AppState>,
+    locale: Locale,
+    headers: HeaderMap,
+    Json(request): Json<RunItRequest>,
+) -> Result<Json<serde_json::Value>, GameError> {
+    {
+        let room = state.rooms.get(&room_id).ok_or(GameError::RoomNotFound)?;
+        authorize(&room, &headers, &request.player_id)?;
+    }
+
+    let game_state = handle_run_it(&state, &room_id, &request.player_id, request.runs).await?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": translate(locale, "run_it_registered"),
+        "game_state": game_state
+    })))
+}
+
+/// Abre as cartas do jogador depois do fim da mão (quem ganhou sem showdown
+/// ou foi para o muck).
+pub async fn show_hand(
+    Path(room_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<RevealRequest>,
+) -> Result<Json<serde_json::Value>, GameError> {
+    {
+        let room = state.rooms.get(&room_id).ok_or(GameError::RoomNotFound)?;
+        authorize(&room, &headers, &request.player_id)?;
+    }
+
+    let message = handle_show_hand(&state, &room_id, &request.player_id).await?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "hand": message["data"]["hand"]
+    })))
+}
+
+/// Liga ou desliga o auto-muck das mãos que perdem no showdown.
+pub async fn auto_muck(
+    Path(room_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<AutoMuckRequest>,
+) -> Result<Json<serde_json::Value>, GameError> {
+    {
+        let room = state.rooms.get(&room_id).ok_or(GameError::RoomNotFound)?;
+        authorize(&room, &headers, &request.player_id)?;
+    }
+
+    let result = handle_auto_muck(&state, &room_id, &request.player_id, request.enabled).await?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "auto_muck": result["auto_muck"]
+    })))
+}
+
+/// Revela o board que teria saído numa mão que acabou antes do river.
+pub async fn rabbit_hunt(
+    Path(room_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<RevealRequest>,
+) -> Result<Json<serde_json::Value>, GameError> {
+    {
+        let room = state.rooms.get(&room_id).ok_or(GameError::RoomNotFound)?;
+        authorize(&room, &headers, &request.player_id)?;
+    }
+
+    let message = handle_rabbit_hunt(&state, &room_id, &request.player_id).await?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "board": message["data"]["board"]
+    })))
+}
+
+/// Registra a client seed do jogador. Ela entra na mistura do deck a partir da
+/// próxima mão, cujo compromisso já foi publicado.
+pub async fn set_client_seed(
+    Path(room_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<ClientSeedRequest>,
+) -> Result<Json<serde_json::Value>, GameError> {
+    let mut guard = state
+        .rooms
+        .get_mut(&room_id)
+        .ok_or(GameError::RoomNotFound)?;
+    authorize(&guard, &headers, &request.player_id)?;
+
+    let room = &mut *guard;
+    let fairness = match room.game.as_mut() {
+        Some(game) => &mut game.fairness,
+        None => &mut room.fairness,
+    };
+    fairness
+        .client_seeds
+        .insert(request.player_id, request.client_seed);
+    let next_commitment = fairness.next_commitment();
+    let next_client_seeds = fairness.client_seeds.clone();
+    let written = state.persist(room);
+    drop(guard);
+    written.written().await;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "next_commitment": next_commitment,
+        "next_client_seeds": next_client_seeds
+    })))
+}
+
+pub async fn list_hands(
+    Path(room_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, GameError> {
+    if !state.rooms.contains_key(&room_id) {
+        return Err(GameError::RoomNotFound);
+    }
+
+    let viewer = history_viewer(&state, &headers)?;
+    let hands = redact(state.store.load_hands(&room_id)?, &viewer);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "hands": hands
+    })))
+}
+
+/// Resultado de cada jogador nas mãos da sala, real e ajustado pelo EV dos
+/// all-ins.
+pub async fn session_results(
+    Path(room_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, GameError> {
+    if !state.rooms.contains_key(&room_id) {
+        return Err(GameError::RoomNotFound);
+    }
+
+    let hands = state.store.load_hands(&room_id)?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "players": crate::history::session_results(&hands)
+    })))
+}
+
+/// Baixa as mãos finalizadas da sala no formato de texto do PokerStars, para
+/// importação em ferramentas como HoldemManager e PokerTracker.
+pub async fn export_pokerstars(
+    Path(room_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, GameError> {
+    if !state.rooms.contains_key(&room_id) {
+        return Err(GameError::RoomNotFound);
+    }
+
+    let viewer = history_viewer(&state, &headers)?;
+    // O dono do token é o herói do histórico, como numa exportação do cliente
+    let hero = match &viewer {
+        HistoryViewer::Player(player_id) => Some(player_id.as_str()),
+        _ => None,
+    };
+    let hands = redact(state.store.load_hands(&room_id)?, &viewer);
+    let text = crate::pokerstars::export_hands(&hands, &room_id, hero);
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/plain; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"poker-{}.txt\"", room_id),
+            ),
+        ],
+        text,
+    ))
+}
+
+pub async fn get_hand(
+    Path(hand_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, GameError> {
+    let viewer = history_viewer(&state, &headers)?;
+    let hand = state
+        .store
+        .load_hand(&hand_id)?
+        .ok_or(GameError::HandNotFound)?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "hand": hand.redacted(&viewer)
+    })))
+}
+
+/// Reproduz uma mão gravada passo a passo, re-executando o motor a partir do
+/// deck gravado, e aponta divergências em relação ao resultado gravado.
+pub async fn replay_hand(
+    Path(hand_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, GameError> {
+    let viewer = history_viewer(&state, &headers)?;
+    let hand = state
+        .store
+        .load_hand(&hand_id)?
+        .ok_or(GameError::HandNotFound)?;
+    let replay = crate::replay::replay_hand(&hand)?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "replay": replay.redacted(&hand, &viewer)
+    })))
+}
+
+/// Prova de embaralhamento de uma mão e a verificação dela: os compromissos
+/// das posições formam o publicado antes da mão e as cartas abertas na mesa
+/// batem com eles. As cartas do muck e as não distribuídas ficam escondidas.
+pub async fn verify_hand(
+    Path(hand_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, GameError> {
+    let viewer = history_viewer(&state, &headers)?;
+    let hand = state
+        .store
+        .load_hand(&hand_id)?
+        .ok_or(GameError::HandNotFound)?;
+    let shuffle = hand.fairness.as_ref().ok_or(GameError::HandNotVerifiable)?;
+    // A prova abre só as cartas que o consultante vê no histórico
+    let proof = shuffle.proof(&hand.redacted(&viewer).visible_cards());
+    let verification = crate::fairness::verify(&proof);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "proof": proof,
+        "verification": verification
+    })))
+}
+
+/// Calcula a equity de cada mão. O cálculo roda fora do executor, já que a
+/// enumeração e o Monte Carlo podem levar algumas centenas de milissegundos.
+pub async fn calculate_equity(
+    Json(request): Json<EquityRequest>,
+) -> Result<Json<serde_json::Value>, GameError> {
+    let equity = tokio::task::spawn_blocking(move || crate::equity::from_request(&request))
+        .await
+        .map_err(|_| GameError::Internal)??;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "equity": equity
+    })))
+}
+
+/// Exporta uma mão gravada no formato Open Hand History.
+pub async fn export_ohh(
+    Path(hand_id): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<OhhDocument>, GameError> {
+    let viewer = history_viewer(&state, &headers)?;
+    let hand = state
+        .store
+        .load_hand(&hand_id)?
+        .ok_or(GameError::HandNotFound)?
+        .redacted(&viewer);
+
+    Ok(Json(crate::ohh::export_hand(&hand, &hand.game_id)))
+}
+
+/// Importa uma mão Open Hand History externa. A mão é avaliada, gravada no
+/// histórico e pode depois ser consultada por `GET /hands/:hand_id`.
+/// Exige o token de um jogador sentado em alguma sala, e cada jogador guarda
+/// no máximo `MAX_IMPORTED_HANDS` mãos importadas.
+pub async fn import_ohh(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(document): Json<OhhDocument>,
+) -> Result<Json<serde_json::Value>, GameError> {
+    let token = bearer_token(&headers).ok_or(GameError::Unauthorized)?;
+    let player_id = state
+        .rooms
+        .iter()
+        .find_map(|room| player_with_token(&room, token))
+        .ok_or(GameError::Unauthorized)?;
+
+    let imports = format!("{}:{}", IMPORTED_HANDS_ROOM, player_id);
+    if state.store.count_hands(&imports)? >= MAX_IMPORTED_HANDS {
+        return Err(GameError::ImportLimitReached);
+    }
+    let hand = crate::ohh::import_hand(&document)?;
+    state.store.save_hand(&imports, &hand)?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "hand": hand
+    })))
+}
+
+/// Quem consulta o histórico, pelo `Authorization: Bearer <token>`: o admin
+/// (`ADMIN_TOKEN`), um jogador de alguma sala ou, sem token, o público. Um
+/// token que não é de ninguém é rejeitado.
+fn history_viewer(state: &AppState, headers: &HeaderMap) -> Result<HistoryViewer, GameError> {
+    let Some(token) = bearer_token(headers) else {
+        return Ok(HistoryViewer::Public);
+    };
+    if state.is_admin(token) {
+        return Ok(HistoryViewer::Admin);
+    }
+    state
+        .rooms
+        .iter()
+        .find_map(|room| player_with_token(&room, token))
+        .map(HistoryViewer::Player)
+        .ok_or(GameError::Unauthorized)
+}
+
+fn redact(hands: Vec<HandHistory>, viewer: &HistoryViewer) -> Vec<HandHistory> {
+    hands.iter().map(|hand| hand.redacted(viewer)).collect()
+}
+
+/// Jogador da sala dono do token em `Authorization: Bearer <token>`, se houver.
+fn viewer(room: &Room, headers: &HeaderMap) -> Option<String> {
+    bearer_token(headers).and_then(|token| player_with_token(room, token))
+}
+
+/// Confere o token `Authorization: Bearer <token>` do jogador na sala.
+fn authorize(room: &Room, headers: &HeaderMap, player_id: &str) -> Result<(), GameError> {
+    verify_player(room, player_id, bearer_token(headers))
+}
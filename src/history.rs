@@ -0,0 +1,454 @@
+use serde::{Deserialize, Serialize};
+
+use crate::equity::{self, EquityOptions};
+use crate::fairness::FairShuffle;
+use crate::models::*;
+
+/// Histórico estruturado de uma mão, gravado pelo motor do jogo desde o
+/// embaralhamento até a distribuição do pot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandHistory {
+    pub hand_id: String,
+    pub game_id: String,
+    pub hand_number: u64,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub dealer_index: usize,
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub seats: Vec<SeatRecord>,
+    /// Ordem do deck logo após o embaralhamento (as cartas saem do fim)
+    pub deck: Vec<Card>,
+    pub actions: Vec<ActionRecord>,
+    pub board: BoardRecord,
+    pub showdown: Vec<ShowdownRecord>,
+    pub winnings: Vec<PotAward>,
+    /// Embaralhamento comprometido da mão, com a server seed (que só o admin
+    /// vê); a prova pública sai de `FairShuffle::proof`
+    #[serde(default)]
+    pub fairness: Option<FairShuffle>,
+    /// Equity de cada jogador quando a mão foi decidida num all-in
+    #[serde(default)]
+    pub all_in: Option<AllInRecord>,
+    /// Boards distribuídos quando os jogadores all-in escolheram rodar mais de
+    /// uma vez; `board` e `showdown` guardam a primeira
+    #[serde(default)]
+    pub runs: Vec<RunRecord>,
+    /// Jogadores que abriram as cartas depois do fim da mão (`show_hand`)
+    #[serde(default)]
+    pub shown: Vec<String>,
+}
+
+/// Quem consulta um histórico gravado. O público vê só as cartas abertas na
+/// mesa, um jogador da mão vê também as suas e o admin vê tudo, inclusive o
+/// deck.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryViewer {
+    Public,
+    Player(String),
+    Admin,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeatRecord {
+    pub seat: usize,
+    pub player_id: String,
+    pub name: String,
+    pub starting_stack: u32,
+    pub hole_cards: Vec<Card>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ActionKind {
+    SmallBlind,
+    BigBlind,
+    Fold,
+    Check,
+    Call,
+    Raise,
+    AllIn,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRecord {
+    pub street: GameState,
+    pub player_id: String,
+    pub kind: ActionKind,
+    /// Fichas colocadas no pot nesta ação
+    pub amount: u32,
+    /// Aposta total do jogador na rodada após a ação
+    pub total_bet: u32,
+    pub pot_after: u32,
+}
+
+/// Como uma ação que coloca fichas aparece nos formatos de exportação.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wager {
+    Bet,
+    Raise,
+    Call,
+}
+
+impl ActionRecord {
+    /// Classifica raises e all-ins como bet, raise ou call a partir da maior
+    /// aposta da rua antes desta ação.
+    pub fn wager(&self, street_bet: u32) -> Wager {
+        if self.total_bet <= street_bet {
+            Wager::Call
+        } else if street_bet == 0 {
+            Wager::Bet
+        } else {
+            Wager::Raise
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoardRecord {
+    pub flop: Vec<Card>,
+    pub turn: Option<Card>,
+    pub river: Option<Card>,
+}
+
+impl BoardRecord {
+    pub fn cards(&self) -> Vec<Card> {
+        let mut cards = self.flop.clone();
+        cards.extend(self.turn.clone());
+        cards.extend(self.river.clone());
+        cards
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShowdownRecord {
+    pub player_id: String,
+    pub hole_cards: Vec<Card>,
+    /// Ausente nas mãos que foram para o muck, fora da visão do admin
+    #[serde(default)]
+    pub hand_rank: Option<HandRank>,
+    pub best_hand: Vec<Card>,
+    /// O jogador perdeu sem precisar mostrar e não abriu as cartas
+    #[serde(default)]
+    pub mucked: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PotAward {
+    pub player_id: String,
+    pub amount: u32,
+}
+
+/// Um pot da mão, o principal ou um side pot, com os jogadores na disputa
+/// que cobriram a aposta dele.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pot {
+    pub amount: u32,
+    pub eligible: Vec<String>,
+}
+
+/// Situação no momento em que as apostas fecharam com jogadores all-in antes
+/// do river: o board até ali, o pot e a equity de cada jogador na disputa.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllInRecord {
+    pub street: GameState,
+    pub board: Vec<Card>,
+    pub pot: u32,
+    /// Vazio enquanto a equity não foi calculada (veja `AllInEquityRequest`)
+    pub players: Vec<AllInEquity>,
+}
+
+/// Cálculo pendente da equity de um all-in. No pré-flop ele é um Monte Carlo
+/// de 100 mil amostras, então o motor só registra o all-in e o cálculo pode
+/// rodar em outra thread, fora do lock da sala.
+#[derive(Debug, Clone)]
+pub struct AllInEquityRequest {
+    pub hand_number: u64,
+    player_ids: Vec<String>,
+    hands: Vec<Vec<Card>>,
+    board: Vec<Card>,
+    pots: Vec<Pot>,
+}
+
+impl AllInEquityRequest {
+    pub(crate) fn new(
+        hand_number: u64,
+        players: &[&Player],
+        board: &[Card],
+        pots: Vec<Pot>,
+    ) -> Self {
+        Self {
+            hand_number,
+            player_ids: players.iter().map(|p| p.id.clone()).collect(),
+            hands: players.iter().map(|p| p.hand.clone()).collect(),
+            board: board.to_vec(),
+            pots,
+        }
+    }
+
+    /// Calcula a equity e o EV de cada jogador (a parte lenta). A equity é
+    /// contra todos na disputa; o EV soma, em cada pot que o jogador disputa,
+    /// a equity contra quem disputa aquele pot vezes o valor dele.
+    pub fn calculate(&self) -> Vec<AllInEquity> {
+        let Ok(equity) =
+            equity::calculate(&self.hands, &self.board, &[], &EquityOptions::default())
+        else {
+            return Vec::new();
+        };
+        let everyone: Vec<f64> = equity.players.iter().map(|p| p.equity).collect();
+
+        let mut ev = vec![0.0; self.player_ids.len()];
+        for pot in &self.pots {
+            let (seats, dead): (Vec<usize>, Vec<usize>) = (0..self.player_ids.len())
+                .partition(|&seat| pot.eligible.contains(&self.player_ids[seat]));
+            let shares = match seats.len() {
+                0 => continue,
+                1 => vec![1.0],
+                _ if dead.is_empty() => everyone.clone(),
+                _ => {
+                    // As cartas de quem não disputa o pot já saíram do deck
+                    let hands: Vec<Vec<Card>> =
+                        seats.iter().map(|&seat| self.hands[seat].clone()).collect();
+                    let dead: Vec<Card> = dead
+                        .iter()
+                        .flat_map(|&seat| self.hands[seat].clone())
+                        .collect();
+                    match equity::calculate(&hands, &self.board, &dead, &EquityOptions::default()) {
+                        Ok(result) => result.players.iter().map(|p| p.equity).collect(),
+                        Err(_) => continue,
+                    }
+                }
+            };
+            for (&seat, share) in seats.iter().zip(shares) {
+                ev[seat] += share * pot.amount as f64;
+            }
+        }
+
+        self.player_ids
+            .iter()
+            .zip(everyone)
+            .zip(ev)
+            .map(|((player_id, equity), ev)| AllInEquity {
+                player_id: player_id.clone(),
+                equity,
+                ev,
+            })
+            .collect()
+    }
+}
+
+/// Uma das vezes em que o board foi distribuído: o board completo e a parte
+/// do pot que cada vencedor levou nela.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub board: Vec<Card>,
+    pub winnings: Vec<PotAward>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllInEquity {
+    pub player_id: String,
+    pub equity: f64,
+    /// Parte dos pots que a equity vale: em cada pot que o jogador disputa,
+    /// a equity contra quem disputa ele vezes o valor do pot
+    pub ev: f64,
+}
+
+/// Resultado de um jogador ao longo das mãos de uma sessão. `ev_net` troca o
+/// resultado das mãos decididas em all-in pelo valor esperado com a equity do
+/// momento do all-in, tirando a sorte do board.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionResult {
+    pub player_id: String,
+    pub name: String,
+    pub hands: usize,
+    pub all_ins: usize,
+    pub net: i64,
+    pub ev_net: f64,
+}
+
+impl HandHistory {
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    pub fn seat(&self, player_id: &str) -> Option<&SeatRecord> {
+        self.seats.iter().find(|seat| seat.player_id == player_id)
+    }
+
+    /// Fichas que o jogador colocou no pot durante a mão.
+    pub fn invested(&self, player_id: &str) -> u32 {
+        self.actions
+            .iter()
+            .filter(|action| action.player_id == player_id)
+            .map(|action| action.amount)
+            .sum()
+    }
+
+    /// Divide as fichas apostadas na mão em pot principal e side pots, um
+    /// por nível de aposta dos jogadores na disputa (`contenders`). As fichas
+    /// de quem foldou entram em cada pot até onde elas cobrem.
+    pub fn pots(&self, contenders: &[String]) -> Vec<Pot> {
+        let invested: Vec<u32> = self
+            .seats
+            .iter()
+            .map(|seat| self.invested(&seat.player_id))
+            .collect();
+        let mut levels: Vec<u32> = contenders.iter().map(|id| self.invested(id)).collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut previous = 0;
+        for level in levels {
+            pots.push(Pot {
+                amount: invested
+                    .iter()
+                    .map(|&chips| chips.min(level) - chips.min(previous))
+                    .sum(),
+                eligible: contenders
+                    .iter()
+                    .filter(|id| self.invested(id) >= level)
+                    .cloned()
+                    .collect(),
+            });
+            previous = level;
+        }
+        // O que quem foldou apostou acima de todos fica no último pot
+        let above: u32 = invested
+            .iter()
+            .map(|&chips| chips.saturating_sub(previous))
+            .sum();
+        if let Some(last) = pots.last_mut() {
+            last.amount += above;
+        }
+        pots.retain(|pot| pot.amount > 0);
+        pots
+    }
+
+    pub fn won(&self, player_id: &str) -> u32 {
+        self.winnings
+            .iter()
+            .filter(|award| award.player_id == player_id)
+            .map(|award| award.amount)
+            .sum()
+    }
+
+    pub fn net(&self, player_id: &str) -> i64 {
+        self.won(player_id) as i64 - self.invested(player_id) as i64
+    }
+
+    /// As cartas do jogador foram abertas na mesa: no showdown ou depois do
+    /// fim da mão.
+    pub fn was_shown(&self, player_id: &str) -> bool {
+        self.shown.iter().any(|id| id == player_id)
+            || self
+                .showdown
+                .iter()
+                .any(|record| record.player_id == player_id && !record.mucked)
+    }
+
+    /// Cópia do histórico para `viewer`: sem as hole cards que não foram
+    /// abertas (a não ser as do próprio jogador) e, fora da visão do admin,
+    /// sem o deck, que traz as cartas não distribuídas e as do rabbit hunt.
+    pub fn redacted(&self, viewer: &HistoryViewer) -> HandHistory {
+        let mut hand = self.clone();
+        if *viewer == HistoryViewer::Admin {
+            return hand;
+        }
+
+        let visible = |player_id: &str| {
+            self.was_shown(player_id)
+                || matches!(viewer, HistoryViewer::Player(id) if id == player_id)
+        };
+        for seat in &mut hand.seats {
+            if !visible(&seat.player_id) {
+                seat.hole_cards.clear();
+            }
+        }
+        for record in &mut hand.showdown {
+            if !visible(&record.player_id) {
+                record.hole_cards.clear();
+                record.best_hand.clear();
+                record.hand_rank = None;
+            }
+        }
+        hand.deck.clear();
+        if let Some(fairness) = &mut hand.fairness {
+            fairness.server_seed.clear();
+        }
+        hand
+    }
+
+    /// Cartas que `redacted` deixa à vista: o deck inteiro para o admin, ou o
+    /// board (de todas as vezes) e as hole cards que ficaram no histórico.
+    pub fn visible_cards(&self) -> Vec<Card> {
+        if !self.deck.is_empty() {
+            return self.deck.clone();
+        }
+        let mut cards = self.board.cards();
+        for run in &self.runs {
+            cards.extend(run.board.iter().cloned());
+        }
+        for seat in &self.seats {
+            cards.extend(seat.hole_cards.iter().cloned());
+        }
+        cards
+    }
+
+    /// Resultado ajustado pelo EV: nas mãos decididas em all-in, o valor
+    /// esperado da equity no lugar do que o jogador de fato ganhou.
+    pub fn ev_net(&self, player_id: &str) -> f64 {
+        let all_in_ev = self.all_in.as_ref().and_then(|all_in| {
+            all_in
+                .players
+                .iter()
+                .find(|player| player.player_id == player_id)
+                .map(|player| player.ev)
+        });
+        match all_in_ev {
+            Some(ev) => ev - self.invested(player_id) as f64,
+            None => self.net(player_id) as f64,
+        }
+    }
+}
+
+/// Soma o resultado real e o ajustado pelo EV de cada jogador, na ordem em
+/// que apareceram nas mãos.
+pub fn session_results(hands: &[HandHistory]) -> Vec<SessionResult> {
+    let mut results: Vec<SessionResult> = Vec::new();
+    for hand in hands.iter().filter(|hand| hand.is_finished()) {
+        for seat in &hand.seats {
+            let index = match results
+                .iter()
+                .position(|result| result.player_id == seat.player_id)
+            {
+                Some(index) => index,
+                None => {
+                    results.push(SessionResult {
+                        player_id: seat.player_id.clone(),
+                        name: seat.name.clone(),
+                        hands: 0,
+                        all_ins: 0,
+                        net: 0,
+                        ev_net: 0.0,
+                    });
+                    results.len() - 1
+                }
+            };
+
+            let result = &mut results[index];
+            result.hands += 1;
+            if hand.all_in.as_ref().is_some_and(|all_in| {
+                all_in
+                    .players
+                    .iter()
+                    .any(|player| player.player_id == seat.player_id)
+            }) {
+                result.all_ins += 1;
+            }
+            result.net += hand.net(&seat.player_id);
+            result.ev_net += hand.ev_net(&seat.player_id);
+        }
+    }
+    results
+}
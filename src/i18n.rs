@@ -0,0 +1,523 @@
+#[cfg(feature = "server")]
+use axum::{
+    async_trait,
+    body::{to_bytes, Body},
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use std::convert::Infallible;
+
+#[cfg(feature = "server")]
+use crate::error::GameError;
+use crate::models::Rank;
+
+/// Idiomas suportados nas mensagens exibidas aos jogadores.
+/// Na entrada aceita qualquer tag que `from_tag` reconheça (`en-GB`, `pt_PT`...).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "pt-BR")]
+    PtBr,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(tag: String) -> Result<Self, Self::Error> {
+        Locale::from_tag(&tag).ok_or_else(|| format!("idioma não suportado: {tag}"))
+    }
+}
+
+impl Locale {
+    /// Interpreta uma tag de idioma (`pt-BR`, `en-US`, `en`...).
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "pt" => Some(Locale::PtBr),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    /// Escolhe o idioma suportado de maior prioridade no `Accept-Language`.
+    /// Idiomas com `q=0` são recusados pelo cliente e ficam de fora.
+    pub fn from_accept_language(value: &str) -> Self {
+        let mut accepted: Vec<(Locale, f32)> = value
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let locale = parts.next().and_then(Locale::from_tag)?;
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (quality > 0.0).then_some((locale, quality))
+            })
+            .collect();
+
+        // Ordenação estável: no empate vale a ordem do header
+        accepted.sort_by(|a, b| b.1.total_cmp(&a.1));
+        accepted
+            .first()
+            .map(|&(locale, _)| locale)
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "server")]
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(Locale::from_accept_language)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "server")]
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Locale::from_headers(&parts.headers))
+    }
+}
+
+/// Catálogo de mensagens, indexado pelos códigos de erro e de evento.
+pub fn translate(locale: Locale, key: &str) -> &'static str {
+    match locale {
+        Locale::PtBr => pt_br(key),
+        Locale::En => en(key).unwrap_or_else(|| pt_br(key)),
+    }
+}
+
+/// Mensagem do catálogo com os seus parâmetros. O motor guarda só a chave e
+/// os parâmetros; o texto é montado no idioma de cada cliente por
+/// [`localize`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    pub key: String,
+    #[serde(default)]
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Message {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            params: serde_json::Map::new(),
+        }
+    }
+
+    /// Acrescenta um parâmetro: um rank, um número ou outra [`Message`].
+    pub fn with(mut self, name: &str, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).unwrap_or_default();
+        self.params.insert(name.to_string(), value);
+        self
+    }
+
+    /// Monta o texto trocando cada `{parâmetro}` do modelo pelo valor. Ranks
+    /// aceitam `{rank:plural}` e números `{kicker:ordinal}`.
+    pub fn render(&self, locale: Locale) -> String {
+        let mut text = String::new();
+        let mut rest = translate(locale, &self.key);
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let Some(end) = rest.find('}') else {
+                break;
+            };
+            let (name, style) = rest[1..end].split_once(':').unwrap_or((&rest[1..end], ""));
+            text.push_str(&self.render_param(locale, name, style));
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        text
+    }
+
+    fn render_param(&self, locale: Locale, name: &str, style: &str) -> String {
+        let Some(value) = self.params.get(name) else {
+            return String::new();
+        };
+        if let Ok(message) = serde_json::from_value::<Message>(value.clone()) {
+            return message.render(locale);
+        }
+        if let Ok(rank) = serde_json::from_value::<Rank>(value.clone()) {
+            return rank_name(locale, rank, style == "plural").to_string();
+        }
+        match (value.as_u64(), style) {
+            (Some(n), "ordinal") => ordinal(locale, n).to_string(),
+            _ => value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string),
+        }
+    }
+}
+
+/// Acrescenta o `text` no idioma pedido a toda [`Message`] serializada dentro
+/// de uma resposta, mantendo `key` e `params` para o cliente.
+pub fn localize(locale: Locale, value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let is_message = map.len() == 2 || map.len() == 3 && map.contains_key("text");
+            if is_message && map.get("key").is_some_and(|k| k.is_string()) {
+                if let Some(serde_json::Value::Object(params)) = map.get("params") {
+                    let message = Message {
+                        key: map["key"].as_str().unwrap_or_default().to_string(),
+                        params: params.clone(),
+                    };
+                    map.insert("text".to_string(), message.render(locale).into());
+                    return;
+                }
+            }
+            map.values_mut().for_each(|v| localize(locale, v));
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| localize(locale, v)),
+        _ => {}
+    }
+}
+
+fn rank_name(locale: Locale, rank: Rank, plural: bool) -> &'static str {
+    let names = match (locale, plural) {
+        (Locale::PtBr, false) => [
+            "Dois", "Três", "Quatro", "Cinco", "Seis", "Sete", "Oito", "Nove", "Dez", "Valete",
+            "Dama", "Rei", "Ás",
+        ],
+        (Locale::PtBr, true) => [
+            "Dois", "Três", "Quatros", "Cincos", "Seis", "Setes", "Oitos", "Noves", "Dez",
+            "Valetes", "Damas", "Reis", "Ases",
+        ],
+        (Locale::En, false) => [
+            "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack",
+            "Queen", "King", "Ace",
+        ],
+        (Locale::En, true) => [
+            "Twos", "Threes", "Fours", "Fives", "Sixes", "Sevens", "Eights", "Nines", "Tens",
+            "Jacks", "Queens", "Kings", "Aces",
+        ],
+    };
+    names[rank as usize - 2]
+}
+
+/// Ordinal do kicker; o primeiro fica sem ordinal ("no kicker").
+fn ordinal(locale: Locale, n: u64) -> &'static str {
+    let ordinals = match locale {
+        Locale::PtBr => ["", "segundo ", "terceiro ", "quarto "],
+        Locale::En => ["", "second ", "third ", "fourth "],
+    };
+    ordinals[(n.max(1) as usize - 1).min(3)]
+}
+
+fn pt_br(key: &str) -> &'static str {
+    match key {
+        // Erros
+        "room_not_found" => "Sala não encontrada",
+        "room_full" => "Sala lotada",
+        "player_not_in_room" => "Jogador não pertence à sala",
+        "unauthorized" => "Token do jogador inválido",
+        "test_mode_disabled" => "Seed e deck empilhado só são aceitos em modo de teste",
+        "invalid_deck" => "Deck empilhado inválido: cartas desconhecidas ou repetidas",
+        "game_not_started" => "Jogo ainda não iniciado",
+        "game_already_started" => "Jogo já iniciado",
+        "not_enough_players" => "Precisa de pelo menos 2 jogadores para iniciar",
+        "hand_not_finished" => "Mão atual ainda não terminou",
+        "hand_already_finished" => "A mão já terminou",
+        "hand_not_found" => "Mão não encontrada",
+        "hand_not_replayable" => "A mão não tem o deck gravado e não pode ser reproduzida",
+        "hand_not_verifiable" => "A mão não tem prova de embaralhamento para verificar",
+        "rabbit_hunt_not_available" => "A mão foi até o river, não há board para revelar",
+        "invalid_hand_history" => "Histórico de mão inválido",
+        "import_limit_reached" => "Limite de mãos importadas atingido",
+        "invalid_equity_request" => {
+            "Cálculo de equity inválido: confira as mãos, o board e as cartas mortas"
+        }
+        "not_your_turn" => "Não é sua vez de jogar",
+        "already_folded" => "Jogador já foldou",
+        "cannot_check" => "Não é possível dar check, há uma aposta a ser igualada",
+        "insufficient_chips" => "Fichas insuficientes para essa aposta",
+        "raise_too_small" => "Aumento menor que o mínimo permitido",
+        "stale_action" => "Ação desatualizada: a mão ou a rodada de apostas já mudou",
+        "duplicate_action" => "Ação já processada",
+        "missing_hand_number" => "Ações com action_id precisam informar o hand_number",
+        "run_it_pending" => {
+            "Aguardando os jogadores decidirem quantas vezes o board será distribuído"
+        }
+        "run_it_not_available" => {
+            "Não há decisão de quantas vezes distribuir o board para este jogador"
+        }
+        "invalid_run_count" => {
+            "Número de vezes para distribuir o board fora do permitido pela sala"
+        }
+        "deck_unavailable" => "O deck selado da mão não pôde ser aberto",
+        "internal_error" => "Erro interno do servidor",
+        // Eventos
+        "room_joined" => "Entrou na sala com sucesso",
+        "game_started" => "Jogo iniciado",
+        "new_hand_started" => "Nova mão iniciada",
+        "action_registered" => "Ação registrada",
+        "run_it_registered" => "Escolha registrada",
+        // Mãos no showdown
+        "hand.royal_flush" => "Royal flush",
+        "hand.straight_flush" => "Straight flush, {high} no topo",
+        "hand.four_of_a_kind" => "Quadra de {rank:plural}",
+        "hand.full_house" => "Full house, {rank:plural} com {over:plural}",
+        "hand.flush" => "Flush, {high} no topo",
+        "hand.straight" => "Sequência, {high} no topo",
+        "hand.three_of_a_kind" => "Trinca de {rank:plural}",
+        "hand.two_pair" => "Dois pares, {high:plural} e {low:plural}",
+        "hand.one_pair" => "Par de {rank:plural}",
+        "hand.high_card" => "Carta alta, {high}",
+        "showdown.won" => "Ganha com {hand}",
+        "showdown.tied" => "Divide o pot com {hand}",
+        "showdown.lost_on_category" => "{hand} perde para {winner_hand}",
+        "showdown.lost_on_rank" => "{hand} perde para {winner_hand}: {winner} ganha de {rank}",
+        "showdown.lost_on_kicker" => {
+            "{hand} perde para {winner_hand} no {kicker:ordinal}kicker: {winner} ganha de {rank}"
+        }
+        _ => "Erro desconhecido",
+    }
+}
+
+fn en(key: &str) -> Option<&'static str> {
+    let message = match key {
+        // Erros
+        "room_not_found" => "Room not found",
+        "room_full" => "Room is full",
+        "player_not_in_room" => "Player is not in this room",
+        "unauthorized" => "Invalid player token",
+        "test_mode_disabled" => "Seeds and stacked decks are only accepted in test mode",
+        "invalid_deck" => "Invalid stacked deck: unknown or repeated cards",
+        "game_not_started" => "Game has not started yet",
+        "game_already_started" => "Game already started",
+        "not_enough_players" => "At least 2 players are needed to start",
+        "hand_not_finished" => "Current hand has not finished yet",
+        "hand_already_finished" => "The hand is already over",
+        "hand_not_found" => "Hand not found",
+        "hand_not_replayable" => "The hand has no recorded deck and cannot be replayed",
+        "hand_not_verifiable" => "The hand has no shuffle proof to verify",
+        "rabbit_hunt_not_available" => {
+            "The hand reached the river, there is no board left to reveal"
+        }
+        "invalid_hand_history" => "Invalid hand history",
+        "import_limit_reached" => "Imported hand limit reached",
+        "invalid_equity_request" => "Invalid equity request: check the hands, board and dead cards",
+        "not_your_turn" => "It is not your turn",
+        "already_folded" => "Player has already folded",
+        "cannot_check" => "Cannot check, there is a bet to call",
+        "insufficient_chips" => "Not enough chips for this bet",
+        "raise_too_small" => "Raise is smaller than the minimum allowed",
+        "stale_action" => "Stale action: the hand or betting round has changed",
+        "duplicate_action" => "Action already processed",
+        "missing_hand_number" => "Actions with an action_id must include the hand_number",
+        "run_it_pending" => "Waiting for the players to choose how many times to run the board",
+        "run_it_not_available" => "There is no run-it choice pending for this player",
+        "invalid_run_count" => "Run count is outside what the room allows",
+        "deck_unavailable" => "The sealed deck of the hand could not be opened",
+        "internal_error" => "Internal server error",
+        // Eventos
+        "room_joined" => "Joined the room successfully",
+        "game_started" => "Game started",
+        "new_hand_started" => "New hand started",
+        "action_registered" => "Action registered",
+        "run_it_registered" => "Choice registered",
+        // Mãos no showdown
+        "hand.royal_flush" => "Royal Flush",
+        "hand.straight_flush" => "Straight Flush, {high} high",
+        "hand.four_of_a_kind" => "Four of a Kind, {rank:plural}",
+        "hand.full_house" => "Full House, {rank:plural} full of {over:plural}",
+        "hand.flush" => "Flush, {high} high",
+        "hand.straight" => "Straight, {high} high",
+        "hand.three_of_a_kind" => "Three of a Kind, {rank:plural}",
+        "hand.two_pair" => "Two Pair, {high:plural} and {low:plural}",
+        "hand.one_pair" => "Pair of {rank:plural}",
+        "hand.high_card" => "High Card, {high}",
+        "showdown.won" => "Wins with {hand}",
+        "showdown.tied" => "Splits the pot with {hand}",
+        "showdown.lost_on_category" => "{hand} loses to {winner_hand}",
+        "showdown.lost_on_rank" => "{hand} loses to {winner_hand}: {winner} beats {rank}",
+        "showdown.lost_on_kicker" => {
+            "{hand} loses to {winner_hand} on the {kicker:ordinal}kicker: {winner} beats {rank}"
+        }
+        _ => return None,
+    };
+    Some(message)
+}
+
+/// Middleware que traduz as respostas de erro para o idioma do
+/// `Accept-Language` da requisição.
+#[cfg(feature = "server")]
+pub async fn localize_errors(request: Request, next: Next) -> Response {
+    let locale = Locale::from_headers(request.headers());
+    let response = next.run(request).await;
+
+    if locale == Locale::default() {
+        return response;
+    }
+
+    match response.extensions().get::<GameError>() {
+        Some(error) => error.localized_response(locale),
+        None => response,
+    }
+}
+
+/// Middleware que acrescenta o texto das [`Message`] das respostas JSON no
+/// idioma do `Accept-Language` da requisição.
+#[cfg(feature = "server")]
+pub async fn localize_messages(request: Request, next: Next) -> Response {
+    let locale = Locale::from_headers(request.headers());
+    let response = next.run(request).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    // Só reescreve as respostas que trazem alguma mensagem
+    if !bytes.windows(8).any(|window| window == b"\"params\"") {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(mut value) => {
+            localize(locale, &mut value);
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(value.to_string())
+        }
+        Err(_) => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::GameError;
+
+    #[test]
+    fn test_accept_language_prefers_highest_quality() {
+        assert_eq!(
+            Locale::from_accept_language("en-US,en;q=0.9,pt-BR;q=0.8"),
+            Locale::En
+        );
+        assert_eq!(
+            Locale::from_accept_language("fr-FR, pt-BR;q=0.7, en;q=0.5"),
+            Locale::PtBr
+        );
+        assert_eq!(Locale::from_accept_language("de, fr"), Locale::PtBr);
+        assert_eq!(Locale::from_accept_language(""), Locale::PtBr);
+        assert_eq!(
+            Locale::from_accept_language("en;q=0.5, pt-BR;q=0.9"),
+            Locale::PtBr
+        );
+    }
+
+    #[test]
+    fn test_accept_language_skips_refused_languages() {
+        assert_eq!(Locale::from_accept_language("en;q=0, pt-BR"), Locale::PtBr);
+        assert_eq!(Locale::from_accept_language("pt;q=0, en;q=0.3"), Locale::En);
+        // Sem nenhum idioma aceito, vale o padrão
+        assert_eq!(Locale::from_accept_language("en;q=0"), Locale::PtBr);
+        assert_eq!(Locale::from_accept_language("en;q=abc"), Locale::PtBr);
+    }
+
+    #[test]
+    fn test_every_error_is_translated() {
+        let errors = [
+            GameError::RoomNotFound,
+            GameError::RoomFull,
+            GameError::PlayerNotInRoom,
+            GameError::Unauthorized,
+            GameError::TestModeDisabled,
+            GameError::InvalidDeck,
+            GameError::GameNotStarted,
+            GameError::GameAlreadyStarted,
+            GameError::NotEnoughPlayers,
+            GameError::HandNotFinished,
+            GameError::HandAlreadyFinished,
+            GameError::HandNotFound,
+            GameError::HandNotReplayable,
+            GameError::HandNotVerifiable,
+            GameError::RabbitHuntNotAvailable,
+            GameError::InvalidHandHistory,
+            GameError::ImportLimitReached,
+            GameError::InvalidEquityRequest,
+            GameError::NotYourTurn,
+            GameError::AlreadyFolded,
+            GameError::CannotCheck,
+            GameError::InsufficientChips,
+            GameError::RaiseTooSmall,
+            GameError::StaleAction,
+            GameError::DuplicateAction,
+            GameError::MissingHandNumber,
+            GameError::RunItPending,
+            GameError::RunItNotAvailable,
+            GameError::InvalidRunCount,
+            GameError::DeckUnavailable,
+            GameError::Internal,
+        ];
+
+        for error in errors {
+            assert_ne!(pt_br(error.code()), "Erro desconhecido", "{:?}", error);
+            assert!(en(error.code()).is_some(), "{:?} sem tradução", error);
+        }
+    }
+
+    #[test]
+    fn test_messages_render_in_each_locale() {
+        let kings = Message::new("hand.one_pair").with("rank", Rank::King);
+        let aces = Message::new("hand.one_pair").with("rank", Rank::Ace);
+        let summary = Message::new("showdown.lost_on_kicker")
+            .with("hand", &kings)
+            .with("winner_hand", &aces)
+            .with("kicker", 2)
+            .with("rank", Rank::Queen)
+            .with("winner", Rank::Jack);
+        assert_eq!(
+            summary.render(Locale::En),
+            "Pair of Kings loses to Pair of Aces on the second kicker: Jack beats Queen"
+        );
+        assert_eq!(
+            summary.render(Locale::PtBr),
+            "Par de Reis perde para Par de Ases no segundo kicker: Valete ganha de Dama"
+        );
+
+        let mut response = serde_json::json!({"result": [{"summary": summary, "pot": 10}]});
+        localize(Locale::PtBr, &mut response);
+        let localized = &response["result"][0]["summary"];
+        assert_eq!(localized["key"], "showdown.lost_on_kicker");
+        assert_eq!(localized["params"]["kicker"], 2);
+        assert_eq!(localized["text"], summary.render(Locale::PtBr));
+        assert!(localized["params"]["hand"].get("text").is_none());
+        assert_eq!(response["result"][0]["pot"], 10);
+    }
+
+    #[test]
+    fn test_locale_from_join_payload() {
+        let locale: Locale = serde_json::from_value(serde_json::json!("en")).unwrap();
+        assert_eq!(locale, Locale::En);
+        let locale: Locale = serde_json::from_value(serde_json::json!("pt-BR")).unwrap();
+        assert_eq!(locale, Locale::PtBr);
+        for (tag, expected) in [
+            ("en-GB", Locale::En),
+            ("EN", Locale::En),
+            ("pt_PT", Locale::PtBr),
+        ] {
+            let locale: Locale = serde_json::from_value(serde_json::json!(tag)).unwrap();
+            assert_eq!(locale, expected);
+        }
+        assert!(serde_json::from_value::<Locale>(serde_json::json!("fr")).is_err());
+    }
+}
@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::storage::{RoomSnapshot, StorageError};
+
+/// Entrada do journal: o estado completo da sala (incluindo o `Game` com
+/// `deck`, `round_bets` e `current_player_index`) após uma ação.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub room_id: String,
+    pub recorded_at: String,
+    pub room: RoomSnapshot,
+}
+
+/// Journal append-only em JSON lines. Cada ação gera uma linha com o snapshot
+/// da sala, gravada com `fsync` antes de seguir. Na inicialização a última
+/// entrada válida de cada sala é restaurada. O arquivo é compactado na
+/// inicialização e a cada `compact_every` entradas, para não crescer sem
+/// limite num servidor que fica muito tempo no ar.
+pub struct GameJournal {
+    path: PathBuf,
+    file: Mutex<JournalFile>,
+    compact_every: usize,
+}
+
+struct JournalFile {
+    file: File,
+    /// Entradas gravadas desde a última compactação
+    appended: usize,
+}
+
+impl GameJournal {
+    /// Entradas entre duas compactações, por padrão.
+    pub const COMPACT_EVERY: usize = 1000;
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+        Ok(Self {
+            path,
+            file: Mutex::new(JournalFile { file, appended: 0 }),
+            compact_every: Self::COMPACT_EVERY,
+        })
+    }
+
+    /// Troca o número de entradas entre duas compactações.
+    pub fn with_compact_every(mut self, entries: usize) -> Self {
+        self.compact_every = entries.max(1);
+        self
+    }
+
+    pub fn append(&self, room: &RoomSnapshot) -> Result<(), StorageError> {
+        let entry = JournalEntry {
+            room_id: room.id.clone(),
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            room: room.clone(),
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+
+        let mut journal = self.file.lock().unwrap();
+        journal.file.write_all(&line)?;
+        journal.file.sync_data()?;
+
+        journal.appended += 1;
+        if journal.appended >= self.compact_every {
+            self.compact_locked(&mut journal)?;
+        }
+        Ok(())
+    }
+
+    /// Lê o journal e retorna o último snapshot de cada sala. Linhas
+    /// inválidas (por exemplo, uma escrita interrompida por um crash) são
+    /// ignoradas.
+    pub fn replay(&self) -> Result<Vec<RoomSnapshot>, StorageError> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut latest: HashMap<String, RoomSnapshot> = HashMap::new();
+        let mut order: Vec<String> = Vec::new();
+
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<JournalEntry>(&line) {
+                Ok(entry) => {
+                    if !latest.contains_key(&entry.room_id) {
+                        order.push(entry.room_id.clone());
+                    }
+                    latest.insert(entry.room_id, entry.room);
+                }
+                Err(error) => {
+                    eprintln!("Ignorando linha {} do journal: {}", number + 1, error);
+                }
+            }
+        }
+
+        Ok(order
+            .into_iter()
+            .filter_map(|room_id| latest.remove(&room_id))
+            .collect())
+    }
+
+    /// Reescreve o journal mantendo só o último snapshot de cada sala. O
+    /// arquivo novo é gravado ao lado e renomeado, então um crash no meio da
+    /// compactação não perde o journal antigo.
+    pub fn compact(&self) -> Result<(), StorageError> {
+        let mut journal = self.file.lock().unwrap();
+        self.compact_locked(&mut journal)
+    }
+
+    /// Compacta com o arquivo travado, para que nenhuma entrada gravada
+    /// durante a compactação se perca.
+    fn compact_locked(&self, journal: &mut JournalFile) -> Result<(), StorageError> {
+        let rooms = self.replay()?;
+        let temp_path = self.path.with_extension("compacting");
+
+        {
+            let mut temp = File::create(&temp_path)?;
+            for room in &rooms {
+                let entry = JournalEntry {
+                    room_id: room.id.clone(),
+                    recorded_at: chrono::Utc::now().to_rfc3339(),
+                    room: room.clone(),
+                };
+                serde_json::to_writer(&mut temp, &entry)?;
+                temp.write_all(b"\n")?;
+            }
+            temp.sync_all()?;
+        }
+
+        std::fs::rename(&temp_path, &self.path)?;
+        journal.file = OpenOptions::new().append(true).open(&self.path)?;
+        journal.appended = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+
+    fn temp_journal_path() -> PathBuf {
+        std::env::temp_dir().join(format!("poker-{}.journal", uuid::Uuid::new_v4()))
+    }
+
+    fn snapshot(room_id: &str) -> RoomSnapshot {
+        let players: Vec<Player> = ["player1", "player2"]
+            .iter()
+            .map(|id| Player {
+                id: id.to_string(),
+                name: id.to_string(),
+                chips: 1000,
+                hand: Vec::new(),
+                current_bet: 0,
+                is_folded: false,
+                is_all_in: false,
+            })
+            .collect();
+        let mut game = Game::new(players.clone());
+        game.start_round().unwrap();
+
+        RoomSnapshot {
+            id: room_id.to_string(),
+            creator_id: "player1".to_string(),
+            players: players.iter().map(|p| (p.id.clone(), p.clone())).collect(),
+            game: Some(game),
+            max_players: 6,
+            player_tokens: HashMap::new(),
+            deck_source: Default::default(),
+            fairness: Default::default(),
+            rules: Default::default(),
+        }
+    }
+
+    fn play_one_action(room: &mut RoomSnapshot) {
+        let game = room.game.as_mut().unwrap();
+        let player_id = game.players[game.current_player_index].id.clone();
+        game.process_action(&player_id, PlayerAction::Call).unwrap();
+    }
+
+    #[test]
+    fn test_replay_resumes_latest_game_state() {
+        let path = temp_journal_path();
+        let journal = GameJournal::open(&path).unwrap();
+
+        let mut room = snapshot("sala1");
+        journal.append(&room).unwrap();
+        play_one_action(&mut room);
+        journal.append(&room).unwrap();
+        journal.append(&snapshot("sala2")).unwrap();
+
+        let restored = journal.replay().unwrap();
+        assert_eq!(restored.len(), 2);
+
+        let game = restored[0].game.as_ref().unwrap();
+        let expected = room.game.as_ref().unwrap();
+        assert_eq!(restored[0].id, "sala1");
+        assert_eq!(game.action_sequence, 1);
+        assert_eq!(game.current_player_index, expected.current_player_index);
+        assert_eq!(game.round_bets, expected.round_bets);
+        assert_eq!(game.deck.len(), expected.deck.len());
+        for (restored_card, card) in game
+            .deck
+            .reveal()
+            .unwrap()
+            .iter()
+            .zip(&expected.deck.reveal().unwrap())
+        {
+            assert_eq!(restored_card.suit, card.suit);
+            assert_eq!(restored_card.rank as u8, card.rank as u8);
+        }
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_journal_does_not_reveal_undealt_cards() {
+        let path = temp_journal_path();
+        let journal = GameJournal::open(&path).unwrap();
+        let room = snapshot("sala1");
+        journal.append(&room).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let game = room.game.as_ref().unwrap();
+        let mut undealt = game.deck.reveal().unwrap();
+        undealt.truncate(game.deck.len());
+        for card in undealt {
+            assert!(!contents.contains(&serde_json::to_string(&card).unwrap()));
+        }
+        assert!(!contents.contains(&game.fairness.next_server_seed));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_replay_ignores_truncated_entry() {
+        let path = temp_journal_path();
+        let journal = GameJournal::open(&path).unwrap();
+        journal.append(&snapshot("sala1")).unwrap();
+
+        // Simula um crash no meio de uma escrita
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"room_id\":\"sala1\",\"recorded_at\":")
+            .unwrap();
+
+        let restored = journal.replay().unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].game.as_ref().unwrap().action_sequence, 0);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_compact_keeps_latest_entry_per_room() {
+        let path = temp_journal_path();
+        let journal = GameJournal::open(&path).unwrap();
+
+        let mut room = snapshot("sala1");
+        for _ in 0..2 {
+            journal.append(&room).unwrap();
+            play_one_action(&mut room);
+        }
+        journal.append(&room).unwrap();
+
+        journal.compact().unwrap();
+        let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+        assert_eq!(lines, 1);
+
+        // O journal continua aceitando novas entradas após a compactação
+        journal.append(&snapshot("sala2")).unwrap();
+        let restored = journal.replay().unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].game.as_ref().unwrap().action_sequence, 2);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_journal_compacts_while_running() {
+        let path = temp_journal_path();
+        let journal = GameJournal::open(&path).unwrap().with_compact_every(4);
+
+        let mut room = snapshot("sala1");
+        let other = snapshot("sala2");
+        journal.append(&other).unwrap();
+        for _ in 0..5 {
+            journal.append(&room).unwrap();
+            play_one_action(&mut room);
+        }
+        journal.append(&room).unwrap();
+
+        // A 4ª entrada compactou o arquivo para uma linha por sala; depois
+        // dela vieram mais 3
+        let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+        assert_eq!(lines, 5);
+
+        let restored = journal.replay().unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].id, "sala2");
+        assert_eq!(restored[1].id, "sala1");
+        assert_eq!(restored[1].game.as_ref().unwrap().action_sequence, 5);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
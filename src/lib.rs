@@ -0,0 +1,40 @@
+//! Motor de Texas Hold'em e servidor da API de poker.
+//!
+//! O motor (`models`, `game`, `evaluator`, históricos e embaralhamento) não
+//! depende do servidor e pode ser usado por bots e serviços de análise. O
+//! servidor HTTP/WebSocket, a persistência e o journal ficam atrás da feature
+//! `server`, ligada por padrão.
+
+pub mod equity;
+pub mod error;
+pub mod evaluator;
+pub mod fairness;
+pub mod game;
+pub mod history;
+pub mod i18n;
+pub mod models;
+pub mod notation;
+pub mod ohh;
+pub mod pokerstars;
+pub mod range;
+pub mod replay;
+pub mod sealed;
+pub mod shuffle;
+
+#[cfg(feature = "server")]
+pub mod auth;
+#[cfg(feature = "server")]
+pub mod handlers;
+#[cfg(feature = "server")]
+pub mod journal;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "server")]
+pub mod sse;
+#[cfg(feature = "server")]
+pub mod storage;
+#[cfg(feature = "server")]
+pub mod websocket;
+
+#[cfg(feature = "server")]
+pub use server::AppState;
@@ -1,43 +1,60 @@
-mod game;
-mod handlers;
-mod models;
-mod websocket;
-
-#[cfg(test)]
-mod integration_tests;
-
-use axum::{
-    routing::{get, post},
-    Router,
-};
-use dashmap::DashMap;
 use std::sync::Arc;
 use tokio::net::TcpListener;
-use tower_http::cors::CorsLayer;
 
-use crate::models::*;
-
-#[derive(Clone)]
-pub struct AppState {
-    pub rooms: Arc<DashMap<String, Room>>,
-}
+use poker_api::journal::GameJournal;
+use poker_api::sealed;
+use poker_api::server::{app, AppState};
+use poker_api::storage::{MemoryStore, RoomStore, SqliteStore};
+use poker_api::websocket;
 
 #[tokio::main]
 async fn main() {
-    let state = AppState {
-        rooms: Arc::new(DashMap::new()),
+    // Chave mestra que cifra os decks ainda não distribuídos. Fica fora do banco
+    // para que um dump não revele cartas futuras; DECK_KEY_PATH=off usa uma
+    // chave efêmera (mãos em andamento não sobrevivem a um restart)
+    let key_path = std::env::var("DECK_KEY_PATH").unwrap_or_else(|_| "data/deck.key".to_string());
+    if key_path != "off" {
+        let key = sealed::load_or_create_key(&key_path).expect("Falha ao ler a chave dos decks");
+        sealed::set_master_key(key);
+    }
+
+    // Caminho do banco via variável de ambiente (no Render, o disco fica em /app/data)
+    let database_path =
+        std::env::var("DATABASE_PATH").unwrap_or_else(|_| "data/poker.db".to_string());
+    let store: Arc<dyn RoomStore> = if database_path == ":memory:" {
+        Arc::new(MemoryStore::default())
+    } else {
+        Arc::new(SqliteStore::open(&database_path).expect("Falha ao abrir o banco de dados"))
     };
+    let mut state = AppState::restore(store).expect("Falha ao restaurar as salas");
+
+    // Journal das mãos em andamento; JOURNAL_PATH=off desativa
+    let journal_path =
+        std::env::var("JOURNAL_PATH").unwrap_or_else(|_| "data/games.journal".to_string());
+    if journal_path != "off" {
+        let journal = GameJournal::open(&journal_path).expect("Falha ao abrir o journal");
+        state = state
+            .with_journal(journal)
+            .expect("Falha ao restaurar o journal");
+    }
+    // POKER_TEST_MODE=1 libera seeds fixas e decks empilhados na criação de salas
+    let test_mode = std::env::var("POKER_TEST_MODE").is_ok_and(|value| value == "1");
+    state = state.with_test_mode(test_mode);
+    // ADMIN_TOKEN dá acesso aos históricos completos, com o deck e as cartas do muck
+    let admin_token = std::env::var("ADMIN_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty());
+    state = state.with_admin_token(admin_token.as_deref());
+    println!(
+        "{} sala(s) restaurada(s) de {}",
+        state.rooms.len(),
+        database_path
+    );
+
+    // Votações de run it abertas antes do restart voltam a ter prazo
+    websocket::resume_run_it_votes(&state);
 
-    let app = Router::new()
-        .route("/health", get(handlers::health_check))
-        .route("/room", post(handlers::create_room))
-        .route("/room/:room_id/join", post(handlers::join_room))
-        .route("/room/:room_id/ws", get(websocket::websocket_handler))
-        .route("/room/:room_id/start", post(handlers::start_game))
-        .route("/room/:room_id/result", get(handlers::get_hand_result))
-        .route("/room/:room_id/next", post(handlers::next_hand))
-        .with_state(state)
-        .layer(CorsLayer::permissive());
+    let app = app(state);
 
     // Configura a porta via variável de ambiente (necessário para o Render)
     let port = std::env::var("PORT").unwrap_or_else(|_| "3000".to_string());
@@ -1,171 +1,328 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tokio::sync::mpsc;
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-pub enum Suit {
-    Hearts,
-    Diamonds,
-    Clubs,
-    Spades,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum Rank {
-    Two = 2,
-    Three = 3,
-    Four = 4,
-    Five = 5,
-    Six = 6,
-    Seven = 7,
-    Eight = 8,
-    Nine = 9,
-    Ten = 10,
-    Jack = 11,
-    Queen = 12,
-    King = 13,
-    Ace = 14,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Card {
-    pub suit: Suit,
-    pub rank: Rank,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Player {
-    pub id: String,
-    pub name: String,
-    pub chips: u32,
-    pub hand: Vec<Card>,
-    pub current_bet: u32,
-    pub is_folded: bool,
-    pub is_all_in: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum GameState {
-    Waiting,
-    PreFlop,
-    Flop,
-    Turn,
-    River,
-    Showdown,
-    Finished,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum PlayerAction {
-    Fold,
-    Check,
-    Call,
-    Raise(u32),
-    AllIn,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Game {
-    pub id: String,
-    pub players: Vec<Player>,
-    pub deck: Vec<Card>,
-    pub community_cards: Vec<Card>,
-    pub pot: u32,
-    pub current_bet: u32,
-    pub current_player_index: usize,
-    pub dealer_index: usize,
-    pub small_blind: u32,
-    pub big_blind: u32,
-    pub state: GameState,
-    pub round_bets: HashMap<String, u32>,
-}
-
-#[derive(Debug)]
-pub struct Room {
-    pub id: String,
-    pub creator_id: String,
-    pub players: HashMap<String, Player>,
-    pub game: Option<Game>,
-    pub max_players: usize,
-    pub websocket_senders: HashMap<String, mpsc::UnboundedSender<String>>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateRoomRequest {
-    pub creator_name: String,
-    pub max_players: Option<usize>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateRoomResponse {
-    pub room_id: String,
-    pub player_id: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct JoinRoomRequest {
-    pub player_name: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct JoinRoomResponse {
-    pub success: bool,
-    pub message: String,
-    pub player_id: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct WebSocketMessage {
-    pub message_type: String,
-    pub data: serde_json::Value,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GameActionMessage {
-    pub player_id: String,
-    pub action: PlayerAction,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
-pub enum HandRank {
-    HighCard = 1,
-    OnePair = 2,
-    TwoPair = 3,
-    ThreeOfAKind = 4,
-    Straight = 5,
-    Flush = 6,
-    FullHouse = 7,
-    FourOfAKind = 8,
-    StraightFlush = 9,
-    RoyalFlush = 10,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HandEvaluation {
-    pub rank: HandRank,
-    pub kickers: Vec<u8>, // Cartas que desempatam
-    pub cards: Vec<Card>, // As 5 melhores cartas
-}
-
-impl PartialEq for HandEvaluation {
-    fn eq(&self, other: &Self) -> bool {
-        self.rank == other.rank && self.kickers == other.kickers
-    }
-}
-
-impl Eq for HandEvaluation {}
-
-impl PartialOrd for HandEvaluation {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for HandEvaluation {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.rank.cmp(&other.rank) {
-            std::cmp::Ordering::Equal => self.kickers.cmp(&other.kickers),
-            other => other,
-        }
-    }
-}
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tokio::sync::mpsc;
+
+use crate::fairness::FairnessState;
+use crate::history::HandHistory;
+use crate::i18n::Locale;
+use crate::notation::CardFormat;
+use crate::sealed::SealedDeck;
+use crate::shuffle::DeckSource;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Suit {
+    Hearts,
+    Diamonds,
+    Clubs,
+    Spades,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Rank {
+    Two = 2,
+    Three = 3,
+    Four = 4,
+    Five = 5,
+    Six = 6,
+    Seven = 7,
+    Eight = 8,
+    Nine = 9,
+    Ten = 10,
+    Jack = 11,
+    Queen = 12,
+    King = 13,
+    Ace = 14,
+}
+
+/// Serializada como `{"suit", "rank"}`; na leitura também aceita a notação
+/// curta (`"Ah"`). Ver `crate::notation`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "crate::notation::StoredCard")]
+pub struct Card {
+    pub suit: Suit,
+    pub rank: Rank,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Player {
+    pub id: String,
+    pub name: String,
+    pub chips: u32,
+    pub hand: Vec<Card>,
+    pub current_bet: u32,
+    pub is_folded: bool,
+    pub is_all_in: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum GameState {
+    Waiting,
+    PreFlop,
+    Flop,
+    Turn,
+    River,
+    Showdown,
+    Finished,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PlayerAction {
+    Fold,
+    Check,
+    Call,
+    Raise(u32),
+    AllIn,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Game {
+    pub id: String,
+    pub players: Vec<Player>,
+    pub deck: SealedDeck, // Cartas ainda não distribuídas, cifradas
+    pub community_cards: Vec<Card>,
+    pub pot: u32,
+    pub current_bet: u32,
+    #[serde(default)]
+    pub last_raise: u32, // Tamanho do último bet ou raise da rodada de apostas
+    pub current_player_index: usize,
+    pub dealer_index: usize,
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub state: GameState,
+    pub round_bets: HashMap<String, u32>,
+    #[serde(default)]
+    pub hand_number: u64,
+    #[serde(default)]
+    pub action_sequence: u32, // Ações aplicadas na mão atual
+    #[serde(default)]
+    pub applied_action_ids: HashSet<(String, String)>, // (player_id, action_id) da mão atual
+    #[serde(default)]
+    pub hand_history: Option<HandHistory>, // Histórico da mão atual (ou da última finalizada)
+    #[serde(default)]
+    pub deck_source: DeckSource, // Origem da ordem do deck de cada mão
+    #[serde(default)]
+    pub fairness: FairnessState, // Commit-reveal do embaralhamento
+    #[serde(default)]
+    pub rules: RoomRules,
+    #[serde(default)]
+    pub run_it: Option<RunItVote>, // Escolha do número de boards em andamento
+    #[serde(default)]
+    pub reveal: HandReveal, // Cartas abertas no fim da mão
+    #[serde(default)]
+    pub hand_result: Option<serde_json::Value>, // Resultado completo da última mão, antes do muck
+    #[serde(default)]
+    pub shows_losing_hands: HashSet<String>, // Jogadores com o auto-muck desligado
+}
+
+/// Regras opcionais escolhidas na criação da sala.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RoomRules {
+    /// Máximo de vezes que o board pode ser distribuído num all-in (1 = desligado)
+    pub max_runs: u8,
+}
+
+impl Default for RoomRules {
+    fn default() -> Self {
+        Self { max_runs: 1 }
+    }
+}
+
+/// O que foi mostrado no fim da mão. Só as mãos em `shown` aparecem no
+/// resultado; as demais foram para o muck.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HandReveal {
+    pub show_order: Vec<String>, // Ordem do showdown: último agressor primeiro
+    pub shown: Vec<String>,
+    pub rabbit_hunt: Option<Vec<Card>>, // Board que teria saído se a mão continuasse
+}
+
+/// Votação aberta quando a ação termina com jogadores all-in e a sala permite
+/// distribuir o board mais de uma vez. Vale o menor número votado.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RunItVote {
+    pub players: Vec<String>, // Jogadores que ainda disputam o pot
+    pub max_runs: u8,
+    pub votes: HashMap<String, u8>,
+}
+
+#[derive(Debug)]
+pub struct Room {
+    pub id: String,
+    pub creator_id: String,
+    pub players: HashMap<String, Player>,
+    pub game: Option<Game>,
+    pub max_players: usize,
+    pub websocket_senders: HashMap<String, mpsc::UnboundedSender<serde_json::Value>>,
+    pub player_tokens: HashMap<String, String>, // player_id -> SHA-256 do token secreto
+    pub deck_source: DeckSource,                // Repassado ao jogo quando ele é iniciado
+    pub fairness: FairnessState, // Seed comprometida e client seeds antes do jogo começar
+    pub rules: RoomRules,        // Repassadas ao jogo quando ele é iniciado
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateRoomRequest {
+    pub creator_name: String,
+    pub max_players: Option<usize>,
+    pub seed: Option<u64>, // Seed fixa do embaralhamento (modo de teste)
+    pub stacked_deck: Option<Vec<String>>, // Deck empilhado em notação curta (modo de teste)
+    pub max_runs: Option<u8>, // Permite distribuir o board mais de uma vez nos all-ins
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateRoomResponse {
+    pub room_id: String,
+    pub player_id: String,
+    pub player_token: String,
+    pub next_commitment: String, // sha256 da server seed da primeira mão
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientSeedRequest {
+    pub player_id: String,
+    pub client_seed: String,
+}
+
+/// Pedido de um jogador depois do fim da mão (mostrar as cartas, rabbit hunt).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevealRequest {
+    pub player_id: String,
+}
+
+/// Liga ou desliga o auto-muck das mãos que perdem no showdown.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoMuckRequest {
+    pub player_id: String,
+    pub enabled: bool,
+}
+
+/// Voto de um jogador all-in em quantas vezes distribuir o resto do board.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunItRequest {
+    pub player_id: String,
+    pub runs: u8,
+}
+
+/// Cartas em notação padrão (`"AsKd"`, `"Qh Jh Th"`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EquityRequest {
+    /// Mão exata ou range (`"QQ+, AKs"`) de cada jogador
+    pub hands: Vec<String>,
+    #[serde(default)]
+    pub board: String,
+    #[serde(default)]
+    pub dead: String,
+    /// Força o Monte Carlo com este número de amostras
+    pub samples: Option<u32>,
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JoinRoomRequest {
+    pub player_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JoinRoomResponse {
+    pub success: bool,
+    pub message: String,
+    pub player_id: Option<String>,
+    pub player_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebSocketMessage {
+    pub message_type: String,
+    pub data: serde_json::Value,
+}
+
+/// Codificação das mensagens trafegadas no WebSocket, negociada por conexão
+/// via `?encoding=` na URL. O esquema das mensagens é o mesmo nas duas.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WireEncoding {
+    #[default]
+    Json,
+    #[serde(alias = "messagepack")]
+    Msgpack,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WebSocketParams {
+    pub encoding: Option<WireEncoding>,
+    #[serde(default)]
+    pub cards: CardFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventStreamParams {
+    pub player_id: String,
+    /// Token do jogador; `EventSource` não envia headers, então ele também
+    /// pode vir na query no lugar do `Authorization: Bearer`
+    #[serde(default)]
+    pub player_token: Option<String>,
+    #[serde(default)]
+    pub cards: CardFormat,
+    /// Idioma das mensagens; sem ele vale o `Accept-Language`
+    #[serde(default)]
+    pub locale: Option<Locale>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameActionMessage {
+    pub player_id: String,
+    pub action: PlayerAction,
+    /// Id gerado pelo cliente; reenvios com o mesmo id na mesma mão são
+    /// aplicados uma única vez. Exige `hand_number`
+    #[serde(default)]
+    pub action_id: Option<String>,
+    /// Mão e sequência esperadas (`hand_number`/`action_sequence` do estado do jogo)
+    #[serde(default)]
+    pub hand_number: Option<u64>,
+    #[serde(default)]
+    pub sequence: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandRank {
+    HighCard = 1,
+    OnePair = 2,
+    TwoPair = 3,
+    ThreeOfAKind = 4,
+    Straight = 5,
+    Flush = 6,
+    FullHouse = 7,
+    FourOfAKind = 8,
+    StraightFlush = 9,
+    RoyalFlush = 10,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandEvaluation {
+    pub rank: HandRank,
+    pub kickers: Vec<u8>, // Cartas que desempatam
+    pub cards: Vec<Card>, // As 5 melhores cartas
+}
+
+impl PartialEq for HandEvaluation {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank && self.kickers == other.kickers
+    }
+}
+
+impl Eq for HandEvaluation {}
+
+impl PartialOrd for HandEvaluation {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HandEvaluation {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match self.rank.cmp(&other.rank) {
+            std::cmp::Ordering::Equal => self.kickers.cmp(&other.kickers),
+            other => other,
+        }
+    }
+}
@@ -0,0 +1,355 @@
+#[cfg(feature = "server")]
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::*;
+
+/// Carta ou lista de cartas que não está na notação padrão (`As`, `Td`, `2c`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardError {
+    pub input: String,
+}
+
+impl ParseCardError {
+    fn new(input: &str) -> Self {
+        Self {
+            input: input.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "carta inválida: {:?}", self.input)
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rank = match self {
+            Rank::Two => '2',
+            Rank::Three => '3',
+            Rank::Four => '4',
+            Rank::Five => '5',
+            Rank::Six => '6',
+            Rank::Seven => '7',
+            Rank::Eight => '8',
+            Rank::Nine => '9',
+            Rank::Ten => 'T',
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+            Rank::Ace => 'A',
+        };
+        write!(f, "{}", rank)
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(rank), None) = (chars.next(), chars.next()) else {
+            return Err(ParseCardError::new(s));
+        };
+        match rank.to_ascii_uppercase() {
+            '2' => Ok(Rank::Two),
+            '3' => Ok(Rank::Three),
+            '4' => Ok(Rank::Four),
+            '5' => Ok(Rank::Five),
+            '6' => Ok(Rank::Six),
+            '7' => Ok(Rank::Seven),
+            '8' => Ok(Rank::Eight),
+            '9' => Ok(Rank::Nine),
+            'T' => Ok(Rank::Ten),
+            'J' => Ok(Rank::Jack),
+            'Q' => Ok(Rank::Queen),
+            'K' => Ok(Rank::King),
+            'A' => Ok(Rank::Ace),
+            _ => Err(ParseCardError::new(s)),
+        }
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suit = match self {
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+            Suit::Spades => 's',
+        };
+        write!(f, "{}", suit)
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "h" => Ok(Suit::Hearts),
+            "d" => Ok(Suit::Diamonds),
+            "c" => Ok(Suit::Clubs),
+            "s" => Ok(Suit::Spades),
+            _ => Err(ParseCardError::new(s)),
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(rank), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(ParseCardError::new(s));
+        };
+        Ok(Card {
+            rank: rank
+                .to_string()
+                .parse()
+                .map_err(|_| ParseCardError::new(s))?,
+            suit: suit
+                .to_string()
+                .parse()
+                .map_err(|_| ParseCardError::new(s))?,
+        })
+    }
+}
+
+impl Card {
+    /// Notação curta usada nos formatos de histórico: rank + naipe (`Ah`, `Td`).
+    pub fn notation(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn from_notation(notation: &str) -> Option<Card> {
+        notation.parse().ok()
+    }
+}
+
+/// Lê uma mão ou um board em notação padrão, com ou sem separadores:
+/// `"AsKd"`, `"Qh Jh Th"` e `"Qh,Jh,Th"` são aceitos.
+pub fn parse_cards(notation: &str) -> Result<Vec<Card>, ParseCardError> {
+    let chars: Vec<char> = notation
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .collect();
+    if !chars.len().is_multiple_of(2) {
+        return Err(ParseCardError::new(notation));
+    }
+
+    chars
+        .chunks(2)
+        .map(|pair| pair.iter().collect::<String>().parse())
+        .collect()
+}
+
+/// Cartas em notação padrão separadas por espaço (`"Qh Jh Th"`).
+pub fn format_cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(Card::notation)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Representações aceitas na leitura de uma carta: o objeto
+/// `{"suit": "Hearts", "rank": "Ace"}` ou a notação `"Ah"`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum StoredCard {
+    Notation(String),
+    Fields { suit: Suit, rank: Rank },
+}
+
+impl TryFrom<StoredCard> for Card {
+    type Error = ParseCardError;
+
+    fn try_from(stored: StoredCard) -> Result<Self, Self::Error> {
+        match stored {
+            StoredCard::Notation(notation) => notation.parse(),
+            StoredCard::Fields { suit, rank } => Ok(Card { suit, rank }),
+        }
+    }
+}
+
+/// Formato das cartas nas respostas, escolhido por cliente. O padrão mantém o
+/// objeto `{"suit", "rank"}`; no compacto cada carta vira a sua notação.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CardFormat {
+    #[default]
+    Verbose,
+    Compact,
+}
+
+impl CardFormat {
+    /// Converte as cartas de uma mensagem já serializada para este formato.
+    pub fn apply(self, value: &mut serde_json::Value) {
+        if self == CardFormat::Verbose {
+            return;
+        }
+
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(card) = card_object(map) {
+                    *value = serde_json::Value::String(card.notation());
+                    return;
+                }
+                map.values_mut().for_each(|v| self.apply(v));
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|v| self.apply(v)),
+            _ => {}
+        }
+    }
+
+    /// Lê o header `X-Card-Format` ou o parâmetro `cards` da query string.
+    #[cfg(feature = "server")]
+    pub fn from_request(headers: &HeaderMap, query: Option<&str>) -> Self {
+        let from_query = query.and_then(|query| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("cards="))
+        });
+        headers
+            .get("x-card-format")
+            .and_then(|value| value.to_str().ok())
+            .or(from_query)
+            .and_then(|value| serde_json::from_value(value.into()).ok())
+            .unwrap_or_default()
+    }
+}
+
+fn card_object(map: &serde_json::Map<String, serde_json::Value>) -> Option<Card> {
+    if map.len() != 2 {
+        return None;
+    }
+    let suit = serde_json::from_value(map.get("suit")?.clone()).ok()?;
+    let rank = serde_json::from_value(map.get("rank")?.clone()).ok()?;
+    Some(Card { suit, rank })
+}
+
+/// Middleware que reescreve as cartas das respostas JSON quando o cliente
+/// pede o formato compacto.
+#[cfg(feature = "server")]
+pub async fn compact_cards(request: Request, next: Next) -> Response {
+    let format = CardFormat::from_request(request.headers(), request.uri().query());
+    let response = next.run(request).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if format == CardFormat::Verbose || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(mut value) => {
+            format.apply(&mut value);
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(value.to_string())
+        }
+        Err(_) => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_round_trip() {
+        for card in Game::create_deck() {
+            let notation = card.to_string();
+            assert_eq!(notation.parse::<Card>().unwrap(), card);
+        }
+        assert_eq!(
+            "td".parse::<Card>().unwrap(),
+            Card {
+                suit: Suit::Diamonds,
+                rank: Rank::Ten
+            }
+        );
+        for invalid in ["", "A", "Ahh", "1h", "Ax", "10h"] {
+            assert!(invalid.parse::<Card>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parse_hands_and_boards() {
+        let hand = parse_cards("AsKd").unwrap();
+        assert_eq!(format_cards(&hand), "As Kd");
+        assert_eq!(
+            parse_cards("Qh Jh Th").unwrap(),
+            parse_cards("Qh,Jh,Th").unwrap()
+        );
+        assert_eq!(parse_cards("  ").unwrap(), Vec::new());
+        assert!(parse_cards("AsK").is_err());
+        assert_eq!(parse_cards("As Kx").unwrap_err().input, "Kx");
+    }
+
+    #[test]
+    fn test_card_deserializes_from_both_forms() {
+        let verbose: Card = serde_json::from_str(r#"{"suit":"Spades","rank":"Ace"}"#).unwrap();
+        let compact: Card = serde_json::from_str(r#""As""#).unwrap();
+        assert_eq!(verbose, compact);
+        assert!(serde_json::from_str::<Card>(r#""Zz""#).is_err());
+        // A serialização padrão continua verbosa
+        assert_eq!(serde_json::to_value(&compact).unwrap()["rank"], "Ace");
+    }
+
+    #[test]
+    fn test_compact_format_rewrites_only_cards() {
+        let mut message = serde_json::json!({
+            "type": "game_update",
+            "data": {
+                "pot": 15,
+                "community_cards": [{"suit": "Hearts", "rank": "Ace"}],
+                "players": [{"id": "p1", "hand": [{"suit": "Clubs", "rank": "Ten"}]}],
+                "other": {"suit": "Hearts", "rank": "Ace", "extra": 1}
+            }
+        });
+        let original = message.clone();
+
+        CardFormat::Verbose.apply(&mut message);
+        assert_eq!(message, original);
+
+        CardFormat::Compact.apply(&mut message);
+        assert_eq!(
+            message["data"]["community_cards"],
+            serde_json::json!(["Ah"])
+        );
+        assert_eq!(
+            message["data"]["players"][0]["hand"],
+            serde_json::json!(["Tc"])
+        );
+        assert_eq!(message["data"]["other"], original["data"]["other"]);
+        assert_eq!(message["data"]["pot"], 15);
+    }
+}
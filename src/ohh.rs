@@ -0,0 +1,708 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::error::GameError;
+use crate::evaluator;
+use crate::history::*;
+use crate::models::*;
+
+/// Versão da especificação Open Hand History gerada pelo exportador.
+pub const OHH_SPEC_VERSION: &str = "1.4.6";
+
+/// Documento Open Hand History (https://hh-specs.handhistory.org). Os valores
+/// de `amount` das ações são as fichas colocadas no pot pela ação.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhhDocument {
+    pub ohh: OhhHand,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhhHand {
+    pub spec_version: String,
+    pub site_name: String,
+    #[serde(default)]
+    pub network_name: String,
+    #[serde(default)]
+    pub internal_version: String,
+    #[serde(default)]
+    pub tournament: bool,
+    pub game_number: String,
+    pub start_date_utc: String,
+    #[serde(default)]
+    pub table_name: String,
+    pub table_size: usize,
+    pub game_type: String,
+    pub bet_limit: OhhBetLimit,
+    #[serde(default)]
+    pub currency: String,
+    #[serde(default)]
+    pub ante_amount: f64,
+    pub small_blind_amount: f64,
+    pub big_blind_amount: f64,
+    pub dealer_seat: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hero_player_id: Option<u32>,
+    pub players: Vec<OhhPlayer>,
+    pub rounds: Vec<OhhRound>,
+    #[serde(default)]
+    pub pots: Vec<OhhPot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhhBetLimit {
+    pub bet_type: String,
+    #[serde(default)]
+    pub bet_cap: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhhPlayer {
+    pub id: u32,
+    pub seat: usize,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    pub starting_stack: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhhRound {
+    pub id: u32,
+    pub street: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cards: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<OhhAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhhAction {
+    pub action_number: u32,
+    pub player_id: u32,
+    pub action: String,
+    #[serde(default)]
+    pub amount: f64,
+    #[serde(default)]
+    pub is_allin: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cards: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhhPot {
+    pub number: u32,
+    pub amount: f64,
+    #[serde(default)]
+    pub rake: f64,
+    pub player_wins: Vec<OhhPlayerWin>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OhhPlayerWin {
+    pub player_id: u32,
+    pub win_amount: f64,
+    #[serde(default)]
+    pub contributed_rake: f64,
+}
+
+const STREETS: [(GameState, &str); 4] = [
+    (GameState::PreFlop, "Preflop"),
+    (GameState::Flop, "Flop"),
+    (GameState::Turn, "Turn"),
+    (GameState::River, "River"),
+];
+
+/// Converte uma mão gravada para Open Hand History. Os `id` dos jogadores no
+/// OHH são os índices dos assentos.
+pub fn export_hand(hand: &HandHistory, table_name: &str) -> OhhDocument {
+    let ohh_id = |player_id: &str| {
+        hand.seat(player_id)
+            .map(|seat| seat.seat as u32)
+            .unwrap_or_default()
+    };
+
+    let players = hand
+        .seats
+        .iter()
+        .map(|seat| OhhPlayer {
+            id: seat.seat as u32,
+            seat: seat.seat + 1,
+            name: seat.name.clone(),
+            display: None,
+            starting_stack: seat.starting_stack as f64,
+        })
+        .collect();
+
+    let mut rounds = Vec::new();
+    let mut action_number = 0;
+    let mut next_action = |player_id: u32, action: &str, amount: u32, is_allin, cards| {
+        action_number += 1;
+        OhhAction {
+            action_number,
+            player_id,
+            action: action.to_string(),
+            amount: amount as f64,
+            is_allin,
+            cards,
+        }
+    };
+
+    for (street, name) in STREETS {
+        let cards = match street {
+            GameState::Flop => hand.board.flop.clone(),
+            GameState::Turn => hand.board.turn.iter().cloned().collect(),
+            GameState::River => hand.board.river.iter().cloned().collect(),
+            _ => Vec::new(),
+        };
+        if street != GameState::PreFlop && cards.is_empty() {
+            break;
+        }
+
+        let mut actions = Vec::new();
+        if street == GameState::PreFlop {
+            for seat in &hand.seats {
+                actions.push(next_action(
+                    seat.seat as u32,
+                    "Dealt Cards",
+                    0,
+                    false,
+                    notation(&seat.hole_cards),
+                ));
+            }
+        }
+
+        let mut street_bet = 0;
+        for action in hand.actions.iter().filter(|action| action.street == street) {
+            let label = match action.kind {
+                ActionKind::SmallBlind => "Post SB",
+                ActionKind::BigBlind => "Post BB",
+                ActionKind::Fold => "Fold",
+                ActionKind::Check => "Check",
+                ActionKind::Call => "Call",
+                ActionKind::Raise | ActionKind::AllIn => match action.wager(street_bet) {
+                    Wager::Bet => "Bet",
+                    Wager::Raise => "Raise",
+                    Wager::Call => "Call",
+                },
+            };
+            street_bet = street_bet.max(action.total_bet);
+            actions.push(next_action(
+                ohh_id(&action.player_id),
+                label,
+                action.amount,
+                action.kind == ActionKind::AllIn,
+                Vec::new(),
+            ));
+        }
+
+        rounds.push(OhhRound {
+            id: rounds.len() as u32,
+            street: name.to_string(),
+            cards: notation(&cards),
+            actions,
+        });
+    }
+
+    if !hand.showdown.is_empty() {
+        let actions = hand
+            .showdown
+            .iter()
+            .map(|shown| {
+                next_action(
+                    ohh_id(&shown.player_id),
+                    if shown.mucked {
+                        "Mucks Cards"
+                    } else {
+                        "Shows Cards"
+                    },
+                    0,
+                    false,
+                    notation(&shown.hole_cards),
+                )
+            })
+            .collect();
+        rounds.push(OhhRound {
+            id: rounds.len() as u32,
+            street: "Showdown".to_string(),
+            cards: Vec::new(),
+            actions,
+        });
+    }
+
+    let pots = pot_winnings(hand)
+        .into_iter()
+        .enumerate()
+        .map(|(number, (pot, awards))| OhhPot {
+            number: number as u32,
+            amount: pot.amount as f64,
+            rake: 0.0,
+            player_wins: awards
+                .iter()
+                .map(|award| OhhPlayerWin {
+                    player_id: ohh_id(&award.player_id),
+                    win_amount: award.amount as f64,
+                    contributed_rake: 0.0,
+                })
+                .collect(),
+        })
+        .collect();
+
+    OhhDocument {
+        ohh: OhhHand {
+            spec_version: OHH_SPEC_VERSION.to_string(),
+            site_name: "poker-api".to_string(),
+            network_name: "poker-api".to_string(),
+            internal_version: env!("CARGO_PKG_VERSION").to_string(),
+            tournament: false,
+            game_number: hand.hand_id.clone(),
+            start_date_utc: hand.started_at.clone(),
+            table_name: table_name.to_string(),
+            table_size: hand.seats.len(),
+            game_type: "Holdem".to_string(),
+            bet_limit: OhhBetLimit {
+                bet_type: "NL".to_string(),
+                bet_cap: 0.0,
+            },
+            currency: "Chips".to_string(),
+            ante_amount: 0.0,
+            small_blind_amount: hand.small_blind as f64,
+            big_blind_amount: hand.big_blind as f64,
+            dealer_seat: hand.dealer_index + 1,
+            hero_player_id: None,
+            players,
+            rounds,
+            pots,
+        },
+    }
+}
+
+/// Importa uma mão Open Hand History de Hold'em para o formato interno. As
+/// mãos dos jogadores que chegam ao showdown com cartas conhecidas são
+/// avaliadas pelo nosso avaliador, independentemente do que o site informou.
+pub fn import_hand(document: &OhhDocument) -> Result<HandHistory, GameError> {
+    let ohh = &document.ohh;
+    if ohh.game_type != "Holdem" || ohh.players.is_empty() {
+        return Err(GameError::InvalidHandHistory);
+    }
+
+    let mut players = ohh.players.clone();
+    players.sort_by_key(|player| player.seat);
+    let player_ids: HashMap<u32, String> = players
+        .iter()
+        .map(|player| (player.id, player.id.to_string()))
+        .collect();
+    let player_id = |id: u32| {
+        player_ids
+            .get(&id)
+            .cloned()
+            .ok_or(GameError::InvalidHandHistory)
+    };
+
+    let mut seats: Vec<SeatRecord> = players
+        .iter()
+        .enumerate()
+        .map(|(index, player)| SeatRecord {
+            seat: index,
+            player_id: player.id.to_string(),
+            name: player.name.clone(),
+            starting_stack: chips(player.starting_stack),
+            hole_cards: Vec::new(),
+        })
+        .collect();
+    let dealer_index = players
+        .iter()
+        .position(|player| player.seat == ohh.dealer_seat)
+        .unwrap_or_default();
+
+    let mut actions = Vec::new();
+    let mut board = BoardRecord::default();
+    let mut folded = Vec::new();
+    let mut mucked = Vec::new();
+    let mut pot = 0;
+
+    for round in &ohh.rounds {
+        let street = match round.street.as_str() {
+            "Preflop" => GameState::PreFlop,
+            "Flop" => GameState::Flop,
+            "Turn" => GameState::Turn,
+            "River" => GameState::River,
+            "Showdown" => GameState::Showdown,
+            _ => return Err(GameError::InvalidHandHistory),
+        };
+        let cards = parse_cards(&round.cards)?;
+        match street {
+            GameState::Flop if cards.len() == 3 => board.flop = cards,
+            GameState::Turn if cards.len() == 1 => board.turn = cards.into_iter().next(),
+            GameState::River if cards.len() == 1 => board.river = cards.into_iter().next(),
+            GameState::PreFlop | GameState::Showdown if cards.is_empty() => {}
+            _ => return Err(GameError::InvalidHandHistory),
+        }
+
+        let mut bets: HashMap<String, u32> = HashMap::new();
+        for action in &round.actions {
+            let player_id = player_id(action.player_id)?;
+            let kind = match action.action.as_str() {
+                "Dealt Cards" | "Shows Cards" | "Mucks Cards" => {
+                    if action.action == "Mucks Cards" {
+                        mucked.push(player_id.clone());
+                    }
+                    let cards = parse_cards(&action.cards)?;
+                    if let Some(seat) = seats.iter_mut().find(|s| s.player_id == player_id) {
+                        if !cards.is_empty() {
+                            seat.hole_cards = cards;
+                        }
+                    }
+                    continue;
+                }
+                "Post SB" => ActionKind::SmallBlind,
+                "Post BB" => ActionKind::BigBlind,
+                "Fold" => ActionKind::Fold,
+                "Check" => ActionKind::Check,
+                _ if action.is_allin => ActionKind::AllIn,
+                "Call" => ActionKind::Call,
+                "Bet" | "Raise" => ActionKind::Raise,
+                // Demais ações (mucks, sit out, chat...) não afetam o pot
+                _ => continue,
+            };
+            if kind == ActionKind::Fold {
+                folded.push(player_id.clone());
+            }
+
+            let amount = chips(action.amount);
+            let total_bet = bets.entry(player_id.clone()).or_default();
+            *total_bet += amount;
+            pot += amount;
+            actions.push(ActionRecord {
+                street: street.clone(),
+                player_id,
+                kind,
+                amount,
+                total_bet: *total_bet,
+                pot_after: pot,
+            });
+        }
+    }
+
+    // Uma carta não pode aparecer duas vezes entre as mãos e o board
+    let board_cards = board.cards();
+    let mut dealt: Vec<&Card> = seats.iter().flat_map(|seat| &seat.hole_cards).collect();
+    dealt.extend(&board_cards);
+    if dealt
+        .iter()
+        .enumerate()
+        .any(|(i, card)| dealt[..i].contains(card))
+    {
+        return Err(GameError::InvalidHandHistory);
+    }
+
+    let showdown = if board_cards.len() == 5 {
+        seats
+            .iter()
+            .filter(|seat| seat.hole_cards.len() == 2 && !folded.contains(&seat.player_id))
+            .map(|seat| {
+                let mut cards = seat.hole_cards.clone();
+                cards.extend(board_cards.iter().cloned());
+                let evaluation = evaluator::evaluate(&cards);
+                ShowdownRecord {
+                    player_id: seat.player_id.clone(),
+                    hole_cards: seat.hole_cards.clone(),
+                    hand_rank: Some(evaluation.rank),
+                    best_hand: evaluation.cards,
+                    mucked: mucked.contains(&seat.player_id),
+                }
+            })
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    // Só existe showdown com pelo menos dois jogadores na mão
+    let showdown = if showdown.len() >= 2 {
+        showdown
+    } else {
+        Vec::new()
+    };
+
+    let mut winnings: Vec<PotAward> = Vec::new();
+    for win in ohh.pots.iter().flat_map(|pot| &pot.player_wins) {
+        let player_id = player_id(win.player_id)?;
+        match winnings
+            .iter_mut()
+            .find(|award| award.player_id == player_id)
+        {
+            Some(award) => award.amount += chips(win.win_amount),
+            None => winnings.push(PotAward {
+                player_id,
+                amount: chips(win.win_amount),
+            }),
+        }
+    }
+
+    Ok(HandHistory {
+        hand_id: uuid::Uuid::new_v4().to_string(),
+        game_id: ohh.game_number.clone(),
+        hand_number: ohh.game_number.parse().unwrap_or_default(),
+        started_at: ohh.start_date_utc.clone(),
+        finished_at: Some(ohh.start_date_utc.clone()),
+        dealer_index,
+        small_blind: chips(ohh.small_blind_amount),
+        big_blind: chips(ohh.big_blind_amount),
+        seats,
+        deck: Vec::new(),
+        actions,
+        board,
+        showdown,
+        winnings,
+        fairness: None,
+        all_in: None,
+        runs: Vec::new(),
+        shown: Vec::new(),
+    })
+}
+
+/// Divide os ganhos de cada jogador entre o pot principal e os side pots. O
+/// histórico guarda só o total de cada um; como os pots são aninhados, quem
+/// ganhou um pot também ganha os seguintes que disputa, então os pots são
+/// distribuídos do último para o principal entre os jogadores que disputam
+/// cada um e ainda têm ganhos a receber.
+fn pot_winnings(hand: &HandHistory) -> Vec<(Pot, Vec<PotAward>)> {
+    let folded: Vec<&str> = hand
+        .actions
+        .iter()
+        .filter(|action| action.kind == ActionKind::Fold)
+        .map(|action| action.player_id.as_str())
+        .collect();
+    let contenders: Vec<String> = hand
+        .seats
+        .iter()
+        .filter(|seat| !folded.contains(&seat.player_id.as_str()))
+        .map(|seat| seat.player_id.clone())
+        .collect();
+    let mut remaining: HashMap<&str, u32> = hand
+        .winnings
+        .iter()
+        .map(|award| (award.player_id.as_str(), award.amount))
+        .collect();
+
+    let pots = hand.pots(&contenders);
+    let mut awards: Vec<Vec<PotAward>> = vec![Vec::new(); pots.len()];
+    for (pot, pot_awards) in pots.iter().zip(&mut awards).rev() {
+        let mut winners: Vec<&String> = pot
+            .eligible
+            .iter()
+            .filter(|id| remaining.get(id.as_str()).is_some_and(|&left| left > 0))
+            .collect();
+        // Quem tem menos a receber fica com a sua parte primeiro, e o resto
+        // do pot se divide entre os demais
+        winners.sort_by_key(|id| remaining[id.as_str()]);
+        let mut left = pot.amount;
+        for (i, player_id) in winners.iter().enumerate() {
+            let share = left.div_ceil((winners.len() - i) as u32);
+            let owed = remaining.get_mut(player_id.as_str()).unwrap();
+            let amount = share.min(*owed);
+            *owed -= amount;
+            left -= amount;
+            pot_awards.push(PotAward {
+                player_id: player_id.to_string(),
+                amount,
+            });
+        }
+        // Mantém a ordem dos assentos
+        pot_awards.sort_by_key(|award| hand.seat(&award.player_id).map(|seat| seat.seat));
+    }
+    pots.into_iter().zip(awards).collect()
+}
+
+fn chips(amount: f64) -> u32 {
+    amount.max(0.0).round() as u32
+}
+
+fn notation(cards: &[Card]) -> Vec<String> {
+    cards.iter().map(Card::notation).collect()
+}
+
+fn parse_cards(cards: &[String]) -> Result<Vec<Card>, GameError> {
+    cards
+        .iter()
+        .map(|card| Card::from_notation(card).ok_or(GameError::InvalidHandHistory))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHOWDOWN_JSON: &str = include_str!("../tests/fixtures/hand_showdown.json");
+    const OHH_JSON: &str = include_str!("../tests/fixtures/ohh_external.json");
+
+    fn fixture_hand() -> HandHistory {
+        serde_json::from_str(SHOWDOWN_JSON).unwrap()
+    }
+
+    #[test]
+    fn test_export_uses_ohh_layout() {
+        let document = export_hand(&fixture_hand(), "abc12345");
+        let ohh = &document.ohh;
+
+        assert_eq!(ohh.spec_version, OHH_SPEC_VERSION);
+        assert_eq!(ohh.dealer_seat, 1);
+        assert_eq!(ohh.players.len(), 3);
+        let streets: Vec<_> = ohh.rounds.iter().map(|r| r.street.as_str()).collect();
+        assert_eq!(streets, ["Preflop", "Flop", "Turn", "River", "Showdown"]);
+        assert_eq!(ohh.rounds[1].cards, ["Ah", "7c", "2d"]);
+
+        let preflop: Vec<_> = ohh.rounds[0]
+            .actions
+            .iter()
+            .map(|a| a.action.as_str())
+            .collect();
+        assert_eq!(
+            preflop,
+            [
+                "Dealt Cards",
+                "Dealt Cards",
+                "Dealt Cards",
+                "Post SB",
+                "Post BB",
+                "Raise",
+                "Fold",
+                "Call"
+            ]
+        );
+        // Bet no flop, já que não havia aposta na rua
+        assert_eq!(ohh.rounds[1].actions[1].action, "Bet");
+        assert_eq!(ohh.pots[0].amount, 145.0);
+        assert_eq!(ohh.pots[0].player_wins[0].win_amount, 145.0);
+    }
+
+    #[test]
+    fn test_export_splits_side_pots() {
+        let players = (1..=3)
+            .map(|i| Player {
+                id: format!("player{}", i),
+                name: format!("Player {}", i),
+                chips: if i == 1 { 100 } else { 1000 },
+                hand: Vec::new(),
+                current_bet: 0,
+                is_folded: false,
+                is_all_in: false,
+            })
+            .collect();
+        let mut game = Game::new(players);
+        let stacked = [
+            "Ah", "Kc", "7d", "Ad", "Ks", "2c", "Qc", "8d", "4h", "Js", "3c",
+        ]
+        .map(String::from);
+        game.deck_source = crate::shuffle::DeckSource::stacked(&stacked).unwrap();
+        game.start_round().unwrap();
+        game.process_action("player1", PlayerAction::AllIn).unwrap();
+        game.process_action("player2", PlayerAction::AllIn).unwrap();
+        game.process_action("player3", PlayerAction::Call).unwrap();
+
+        // Os ases de player1 levam o pot principal e os reis de player2 o side pot
+        let document = export_hand(game.hand_history.as_ref().unwrap(), "mesa");
+        let wins = |pot: &OhhPot| -> Vec<(u32, f64)> {
+            pot.player_wins
+                .iter()
+                .map(|win| (win.player_id, win.win_amount))
+                .collect()
+        };
+        let pots = &document.ohh.pots;
+        assert_eq!(pots.len(), 2);
+        assert_eq!((pots[0].number, pots[0].amount), (0, 300.0));
+        assert_eq!(wins(&pots[0]), [(0, 300.0)]);
+        assert_eq!((pots[1].number, pots[1].amount), (1, 1800.0));
+        assert_eq!(wins(&pots[1]), [(1, 1800.0)]);
+
+        let imported = import_hand(&document).unwrap();
+        let won: Vec<u32> = imported.winnings.iter().map(|award| award.amount).collect();
+        assert_eq!(won, [300, 1800]);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let hand = fixture_hand();
+        let json = serde_json::to_string(&export_hand(&hand, "abc12345")).unwrap();
+        let imported = import_hand(&serde_json::from_str(&json).unwrap()).unwrap();
+
+        assert_eq!(imported.seats.len(), hand.seats.len());
+        for (imported, original) in imported.seats.iter().zip(&hand.seats) {
+            assert_eq!(imported.name, original.name);
+            assert_eq!(imported.starting_stack, original.starting_stack);
+            assert_eq!(imported.hole_cards, original.hole_cards);
+        }
+        assert_eq!(imported.board.cards(), hand.board.cards());
+        let amounts = |h: &HandHistory| h.actions.iter().map(|a| a.amount).collect::<Vec<_>>();
+        assert_eq!(amounts(&imported), amounts(&hand));
+        assert_eq!(imported.showdown.len(), 2);
+        assert_eq!(imported.showdown[0].hand_rank, Some(HandRank::TwoPair));
+        assert_eq!(imported.winnings[0].amount, 145);
+    }
+
+    #[test]
+    fn test_import_external_hand_is_evaluated() {
+        let document: OhhDocument = serde_json::from_str(OHH_JSON).unwrap();
+        let hand = import_hand(&document).unwrap();
+
+        assert_eq!(hand.small_blind, 1);
+        assert_eq!(hand.big_blind, 2);
+        assert_eq!(hand.seats[hand.dealer_index].name, "Hero");
+        assert_eq!(hand.board.cards().len(), 5);
+
+        // O avaliador identifica o flush do Hero e o trinca do vilão
+        let rank = |name: &str| {
+            let seat = hand.seats.iter().find(|s| s.name == name).unwrap();
+            hand.showdown
+                .iter()
+                .find(|s| s.player_id == seat.player_id)
+                .unwrap()
+                .hand_rank
+        };
+        assert_eq!(rank("Hero"), Some(HandRank::Flush));
+        assert_eq!(rank("Villain"), Some(HandRank::ThreeOfAKind));
+        assert_eq!(hand.winnings.len(), 1);
+        assert_eq!(hand.winnings[0].amount, 40);
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_cards_and_games() {
+        let mut document: OhhDocument = serde_json::from_str(OHH_JSON).unwrap();
+        document.ohh.rounds[1].cards[0] = "Zz".to_string();
+        assert_eq!(
+            import_hand(&document).unwrap_err(),
+            GameError::InvalidHandHistory
+        );
+
+        // Uma carta do board repetida nas mãos de um jogador
+        let mut document: OhhDocument = serde_json::from_str(OHH_JSON).unwrap();
+        let board_card = document.ohh.rounds[1].cards[0].clone();
+        let shown = document
+            .ohh
+            .rounds
+            .iter_mut()
+            .flat_map(|round| &mut round.actions)
+            .rfind(|action| !action.cards.is_empty())
+            .unwrap();
+        shown.cards[0] = board_card;
+        assert_eq!(
+            import_hand(&document).unwrap_err(),
+            GameError::InvalidHandHistory
+        );
+
+        // A mesma carta nas mãos de dois jogadores
+        let hand = fixture_hand();
+        let mut document = export_hand(&hand, "abc12345");
+        let alice_card = document.ohh.rounds[0].actions[0].cards[0].clone();
+        document.ohh.rounds[0].actions[1].cards[0] = alice_card;
+        assert_eq!(
+            import_hand(&document).unwrap_err(),
+            GameError::InvalidHandHistory
+        );
+
+        let mut document: OhhDocument = serde_json::from_str(OHH_JSON).unwrap();
+        document.ohh.game_type = "Omaha".to_string();
+        assert_eq!(
+            import_hand(&document).unwrap_err(),
+            GameError::InvalidHandHistory
+        );
+    }
+}
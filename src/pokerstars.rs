@@ -0,0 +1,652 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::history::*;
+use crate::models::*;
+use crate::notation::format_cards;
+
+/// Exporta um conjunto de mãos no formato de texto do PokerStars, separadas
+/// por linhas em branco, pronto para importação no HoldemManager/PokerTracker.
+pub fn export_hands(hands: &[HandHistory], table_name: &str, hero: Option<&str>) -> String {
+    hands
+        .iter()
+        .filter(|hand| hand.is_finished())
+        .map(|hand| export_hand(hand, table_name, hero))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Converte uma mão finalizada para o formato de texto do PokerStars, do ponto
+/// de vista de `hero`: como no cliente do PokerStars, só as cartas dele saem em
+/// `Dealt to`. As dos outros aparecem apenas no showdown.
+pub fn export_hand(hand: &HandHistory, table_name: &str, hero: Option<&str>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "PokerStars Hand #{}: Hold'em No Limit ({}/{}) - {}",
+        numeric_hand_id(&hand.hand_id),
+        hand.small_blind,
+        hand.big_blind,
+        format_timestamp(&hand.started_at)
+    );
+    let _ = writeln!(
+        out,
+        "Table '{}' {}-max Seat #{} is the button",
+        table_name,
+        hand.seats.len(),
+        hand.dealer_index + 1
+    );
+    for seat in &hand.seats {
+        let _ = writeln!(
+            out,
+            "Seat {}: {} ({} in chips)",
+            seat.seat + 1,
+            seat.name,
+            seat.starting_stack
+        );
+    }
+
+    let (blinds, actions): (Vec<_>, Vec<_>) = hand
+        .actions
+        .iter()
+        .partition(|action| matches!(action.kind, ActionKind::SmallBlind | ActionKind::BigBlind));
+
+    for blind in &blinds {
+        let label = match blind.kind {
+            ActionKind::SmallBlind => "small blind",
+            _ => "big blind",
+        };
+        let _ = writeln!(
+            out,
+            "{}: posts {} {}",
+            player_name(hand, &blind.player_id),
+            label,
+            blind.amount
+        );
+    }
+
+    out.push_str("*** HOLE CARDS ***\n");
+    let hero_seat = hero.and_then(|hero| hand.seat(hero));
+    if let Some(seat) = hero_seat.filter(|seat| !seat.hole_cards.is_empty()) {
+        let _ = writeln!(
+            out,
+            "Dealt to {} [{}]",
+            seat.name,
+            format_cards(&seat.hole_cards)
+        );
+    }
+
+    // Maior aposta da rua, usada para distinguir bets de raises
+    let mut street_bet = blinds.iter().map(|b| b.total_bet).max().unwrap_or(0);
+    let mut folded_on: HashMap<&str, &GameState> = HashMap::new();
+    let board = &hand.board;
+
+    for street in [
+        GameState::PreFlop,
+        GameState::Flop,
+        GameState::Turn,
+        GameState::River,
+    ] {
+        match street {
+            GameState::Flop if board.flop.len() == 3 => {
+                let _ = writeln!(out, "*** FLOP *** [{}]", format_cards(&board.flop));
+            }
+            GameState::Turn => match &board.turn {
+                Some(turn) => {
+                    let _ = writeln!(
+                        out,
+                        "*** TURN *** [{}] [{}]",
+                        format_cards(&board.flop),
+                        turn.notation()
+                    );
+                }
+                None => break,
+            },
+            GameState::River => match (&board.turn, &board.river) {
+                (Some(turn), Some(river)) => {
+                    let mut previous = board.flop.clone();
+                    previous.push(turn.clone());
+                    let _ = writeln!(
+                        out,
+                        "*** RIVER *** [{}] [{}]",
+                        format_cards(&previous),
+                        river.notation()
+                    );
+                }
+                _ => break,
+            },
+            GameState::PreFlop => {}
+            _ => break,
+        }
+        if street != GameState::PreFlop {
+            street_bet = 0;
+        }
+
+        for action in actions.iter().filter(|action| action.street == street) {
+            let player = player_name(hand, &action.player_id);
+            let line = match action.kind {
+                ActionKind::Fold => {
+                    folded_on.insert(action.player_id.as_str(), &action.street);
+                    "folds".to_string()
+                }
+                ActionKind::Check => "checks".to_string(),
+                ActionKind::Call => format!("calls {}", action.amount),
+                ActionKind::Raise | ActionKind::AllIn => {
+                    let all_in = if action.kind == ActionKind::AllIn {
+                        " and is all-in"
+                    } else {
+                        ""
+                    };
+                    match action.wager(street_bet) {
+                        Wager::Call => format!("calls {}{}", action.amount, all_in),
+                        Wager::Bet => format!("bets {}{}", action.amount, all_in),
+                        Wager::Raise => format!(
+                            "raises {} to {}{}",
+                            action.total_bet - street_bet,
+                            action.total_bet,
+                            all_in
+                        ),
+                    }
+                }
+                ActionKind::SmallBlind | ActionKind::BigBlind => continue,
+            };
+            street_bet = street_bet.max(action.total_bet);
+            let _ = writeln!(out, "{}: {}", player, line);
+        }
+    }
+
+    if !hand.showdown.is_empty() {
+        out.push_str("*** SHOW DOWN ***\n");
+        for shown in &hand.showdown {
+            if shown.mucked {
+                let _ = writeln!(out, "{}: mucks hand", player_name(hand, &shown.player_id));
+                continue;
+            }
+            let _ = writeln!(
+                out,
+                "{}: shows [{}] ({})",
+                player_name(hand, &shown.player_id),
+                format_cards(&shown.hole_cards),
+                describe_rank(shown.hand_rank)
+            );
+        }
+    }
+    for award in &hand.winnings {
+        let _ = writeln!(
+            out,
+            "{} collected {} from pot",
+            player_name(hand, &award.player_id),
+            award.amount
+        );
+    }
+
+    let total_pot: u32 = hand.actions.iter().map(|action| action.amount).sum();
+    out.push_str("*** SUMMARY ***\n");
+    let _ = writeln!(out, "Total pot {} | Rake 0", total_pot);
+    if !board.flop.is_empty() {
+        let _ = writeln!(out, "Board [{}]", format_cards(&board.cards()));
+    }
+
+    for seat in &hand.seats {
+        let mut line = format!("Seat {}: {}", seat.seat + 1, seat.name);
+        if seat.seat == hand.dealer_index {
+            line.push_str(" (button)");
+        }
+        for blind in blinds.iter().filter(|b| b.player_id == seat.player_id) {
+            line.push_str(match blind.kind {
+                ActionKind::SmallBlind => " (small blind)",
+                _ => " (big blind)",
+            });
+        }
+
+        let won = hand
+            .winnings
+            .iter()
+            .filter(|award| award.player_id == seat.player_id)
+            .map(|award| award.amount)
+            .sum::<u32>();
+        let shown = hand
+            .showdown
+            .iter()
+            .find(|shown| shown.player_id == seat.player_id);
+
+        match (folded_on.get(seat.player_id.as_str()), shown) {
+            (Some(street), _) => match street {
+                GameState::PreFlop => line.push_str(" folded before Flop"),
+                other => {
+                    let _ = write!(line, " folded on the {}", street_name(other));
+                }
+            },
+            (None, Some(shown)) if shown.mucked && shown.hole_cards.is_empty() => {
+                line.push_str(" mucked");
+            }
+            (None, Some(shown)) if shown.mucked => {
+                let _ = write!(line, " mucked [{}]", format_cards(&shown.hole_cards));
+            }
+            (None, Some(shown)) if won > 0 => {
+                let _ = write!(
+                    line,
+                    " showed [{}] and won ({}) with {}",
+                    format_cards(&shown.hole_cards),
+                    won,
+                    describe_rank(shown.hand_rank)
+                );
+            }
+            (None, Some(shown)) => {
+                let _ = write!(
+                    line,
+                    " showed [{}] and lost with {}",
+                    format_cards(&shown.hole_cards),
+                    describe_rank(shown.hand_rank)
+                );
+            }
+            (None, None) => {
+                let _ = write!(line, " collected ({})", won);
+            }
+        }
+        let _ = writeln!(out, "{}", line);
+    }
+
+    out
+}
+
+fn player_name<'a>(hand: &'a HandHistory, player_id: &'a str) -> &'a str {
+    hand.seat(player_id)
+        .map(|seat| seat.name.as_str())
+        .unwrap_or(player_id)
+}
+
+/// O PokerStars exige um número de mão; derivamos um estável a partir do
+/// início do UUID da mão.
+fn numeric_hand_id(hand_id: &str) -> u64 {
+    let hex: String = hand_id
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .take(15)
+        .collect();
+    u64::from_str_radix(&hex, 16).unwrap_or(0)
+}
+
+fn format_timestamp(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|date| {
+            date.with_timezone(&chrono::Utc)
+                .format("%Y/%m/%d %H:%M:%S UTC")
+                .to_string()
+        })
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+fn street_name(street: &GameState) -> &'static str {
+    match street {
+        GameState::Flop => "Flop",
+        GameState::Turn => "Turn",
+        GameState::River => "River",
+        _ => "Flop",
+    }
+}
+
+fn describe_rank(rank: Option<HandRank>) -> &'static str {
+    let Some(rank) = rank else {
+        return "a hand";
+    };
+    match rank {
+        HandRank::HighCard => "high card",
+        HandRank::OnePair => "a pair",
+        HandRank::TwoPair => "two pair",
+        HandRank::ThreeOfAKind => "three of a kind",
+        HandRank::Straight => "a straight",
+        HandRank::Flush => "a flush",
+        HandRank::FullHouse => "a full house",
+        HandRank::FourOfAKind => "four of a kind",
+        HandRank::StraightFlush => "a straight flush",
+        HandRank::RoyalFlush => "a royal flush",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHOWDOWN_JSON: &str = include_str!("../tests/fixtures/hand_showdown.json");
+    const SHOWDOWN_TXT: &str = include_str!("../tests/fixtures/hand_showdown.txt");
+    const FOLD_JSON: &str = include_str!("../tests/fixtures/hand_preflop_fold.json");
+    const FOLD_TXT: &str = include_str!("../tests/fixtures/hand_preflop_fold.txt");
+
+    fn fixture(json: &str) -> HandHistory {
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// Lê de volta um histórico exportado. O formato do PokerStars não traz
+    /// ids, então cada jogador fica identificado pelo nome.
+    fn parse_export(text: &str) -> HandHistory {
+        let mut lines = text.lines();
+        let header = lines.next().unwrap();
+        let (_, rest) = header.split_once('#').unwrap();
+        let (number, rest) = rest.split_once(": Hold'em No Limit (").unwrap();
+        let (blinds, date) = rest.split_once(") - ").unwrap();
+        let (small_blind, big_blind) = blinds.split_once('/').unwrap();
+        let (_, button) = lines.next().unwrap().split_once("Seat #").unwrap();
+
+        let mut hand = HandHistory {
+            hand_id: number.to_string(),
+            game_id: String::new(),
+            hand_number: 0,
+            started_at: date.to_string(),
+            finished_at: None,
+            dealer_index: button
+                .trim_end_matches(" is the button")
+                .parse::<usize>()
+                .unwrap()
+                - 1,
+            small_blind: small_blind.parse().unwrap(),
+            big_blind: big_blind.parse().unwrap(),
+            seats: Vec::new(),
+            deck: Vec::new(),
+            actions: Vec::new(),
+            board: BoardRecord::default(),
+            showdown: Vec::new(),
+            winnings: Vec::new(),
+            fairness: None,
+            all_in: None,
+            runs: Vec::new(),
+            shown: Vec::new(),
+        };
+
+        let cards = |text: &str| {
+            let (_, cards) = text.rsplit_once('[').unwrap();
+            crate::notation::parse_cards(cards.trim_end_matches(']')).unwrap()
+        };
+        let mut street = GameState::PreFlop;
+        // Quanto cada jogador já apostou na rua
+        let mut street_bets: HashMap<String, u32> = HashMap::new();
+        let mut pot = 0;
+        for line in lines {
+            if line.starts_with("*** SUMMARY ***") {
+                break;
+            }
+            if let Some(marker) = line.strip_prefix("*** ") {
+                let next = match marker.split(" ***").next().unwrap() {
+                    "FLOP" => {
+                        hand.board.flop = cards(line);
+                        GameState::Flop
+                    }
+                    "TURN" => {
+                        hand.board.turn = cards(line).pop();
+                        GameState::Turn
+                    }
+                    "RIVER" => {
+                        hand.board.river = cards(line).pop();
+                        GameState::River
+                    }
+                    _ => continue,
+                };
+                street = next;
+                street_bets.clear();
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("Seat ") {
+                let (seat, rest) = rest.split_once(": ").unwrap();
+                let (name, stack) = rest.split_once(" (").unwrap();
+                hand.seats.push(SeatRecord {
+                    seat: seat.parse::<usize>().unwrap() - 1,
+                    player_id: name.to_string(),
+                    name: name.to_string(),
+                    starting_stack: stack.trim_end_matches(" in chips)").parse().unwrap(),
+                    hole_cards: Vec::new(),
+                });
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("Dealt to ") {
+                let (name, _) = rest.split_once(" [").unwrap();
+                let seat = hand
+                    .seats
+                    .iter_mut()
+                    .find(|seat| seat.name == name)
+                    .unwrap();
+                seat.hole_cards = cards(rest);
+                continue;
+            }
+            if let Some((name, amount)) = line.split_once(" collected ") {
+                hand.winnings.push(PotAward {
+                    player_id: name.to_string(),
+                    amount: amount.trim_end_matches(" from pot").parse().unwrap(),
+                });
+                continue;
+            }
+
+            let (name, action) = line.split_once(": ").unwrap();
+            if let Some(rest) = action.strip_prefix("shows ") {
+                let (shown, rank) = rest.split_once(" (").unwrap();
+                let rank = rank.trim_end_matches(')');
+                hand.showdown.push(ShowdownRecord {
+                    player_id: name.to_string(),
+                    hole_cards: cards(shown),
+                    hand_rank: ALL_RANKS
+                        .into_iter()
+                        .find(|candidate| describe_rank(Some(*candidate)) == rank),
+                    best_hand: Vec::new(),
+                    mucked: false,
+                });
+                continue;
+            }
+            if action == "mucks hand" {
+                hand.showdown.push(ShowdownRecord {
+                    player_id: name.to_string(),
+                    hole_cards: Vec::new(),
+                    hand_rank: None,
+                    best_hand: Vec::new(),
+                    mucked: true,
+                });
+                continue;
+            }
+
+            let all_in = action.ends_with(" and is all-in");
+            let action = action.trim_end_matches(" and is all-in");
+            let words: Vec<&str> = action.split(' ').collect();
+            let committed = street_bets.get(name).copied().unwrap_or(0);
+            let (kind, total_bet) = match words.as_slice() {
+                ["folds"] => (ActionKind::Fold, committed),
+                ["checks"] => (ActionKind::Check, committed),
+                ["posts", "small", "blind", amount] => {
+                    (ActionKind::SmallBlind, amount.parse().unwrap())
+                }
+                ["posts", "big", "blind", amount] => {
+                    (ActionKind::BigBlind, amount.parse().unwrap())
+                }
+                ["calls", amount] => (ActionKind::Call, committed + amount.parse::<u32>().unwrap()),
+                ["bets", amount] => (ActionKind::Raise, amount.parse().unwrap()),
+                ["raises", _, "to", total] => (ActionKind::Raise, total.parse().unwrap()),
+                other => panic!("ação desconhecida: {:?}", other),
+            };
+            let kind = if all_in { ActionKind::AllIn } else { kind };
+            let amount = total_bet - committed;
+            pot += amount;
+            street_bets.insert(name.to_string(), total_bet);
+            hand.actions.push(ActionRecord {
+                street: street.clone(),
+                player_id: name.to_string(),
+                kind,
+                amount,
+                total_bet,
+                pot_after: pot,
+            });
+        }
+        hand
+    }
+
+    const ALL_RANKS: [HandRank; 10] = [
+        HandRank::HighCard,
+        HandRank::OnePair,
+        HandRank::TwoPair,
+        HandRank::ThreeOfAKind,
+        HandRank::Straight,
+        HandRank::Flush,
+        HandRank::FullHouse,
+        HandRank::FourOfAKind,
+        HandRank::StraightFlush,
+        HandRank::RoyalFlush,
+    ];
+
+    /// O que o texto exportado para `hero` preserva da mão gravada, com os
+    /// jogadores identificados pelo nome.
+    fn exported_view(hand: &HandHistory, hero: &str) -> serde_json::Value {
+        let mut hand = hand.clone();
+        let names: HashMap<String, String> = hand
+            .seats
+            .iter()
+            .map(|seat| (seat.player_id.clone(), seat.name.clone()))
+            .collect();
+        for seat in &mut hand.seats {
+            if seat.player_id != hero {
+                seat.hole_cards.clear();
+            }
+            seat.player_id = names[&seat.player_id].clone();
+        }
+        for action in &mut hand.actions {
+            action.player_id = names[&action.player_id].clone();
+        }
+        for shown in &mut hand.showdown {
+            shown.player_id = names[&shown.player_id].clone();
+            shown.best_hand.clear();
+        }
+        for award in &mut hand.winnings {
+            award.player_id = names[&award.player_id].clone();
+        }
+        comparable(&hand)
+    }
+
+    fn comparable(hand: &HandHistory) -> serde_json::Value {
+        serde_json::json!({
+            "dealer_index": hand.dealer_index,
+            "small_blind": hand.small_blind,
+            "big_blind": hand.big_blind,
+            "seats": hand.seats,
+            "actions": hand.actions,
+            "board": hand.board,
+            "showdown": hand.showdown,
+            "winnings": hand.winnings,
+        })
+    }
+
+    #[test]
+    fn test_export_showdown_matches_fixture() {
+        let hand = fixture(SHOWDOWN_JSON);
+        assert_eq!(
+            export_hand(&hand, "abc12345", Some("p-alice")),
+            SHOWDOWN_TXT
+        );
+    }
+
+    #[test]
+    fn test_fixtures_round_trip() {
+        for (json, txt) in [(SHOWDOWN_JSON, SHOWDOWN_TXT), (FOLD_JSON, FOLD_TXT)] {
+            let hand = fixture(json);
+            let parsed = parse_export(txt);
+            assert_eq!(parsed.hand_id, numeric_hand_id(&hand.hand_id).to_string());
+            assert_eq!(parsed.started_at, format_timestamp(&hand.started_at));
+            assert_eq!(comparable(&parsed), exported_view(&hand, "p-alice"));
+        }
+    }
+
+    #[test]
+    fn test_export_deals_only_to_the_hero() {
+        let hand = fixture(SHOWDOWN_JSON);
+        let text = export_hand(&hand, "abc12345", Some("p-bob"));
+        assert_eq!(text.matches("Dealt to ").count(), 1);
+        assert!(text.contains("Dealt to Bob [8h 3c]\n"));
+
+        let text = export_hand(&hand, "abc12345", None);
+        assert!(!text.contains("Dealt to "));
+    }
+
+    #[test]
+    fn test_export_mucked_hand() {
+        let mut hand = fixture(SHOWDOWN_JSON);
+        // Carol perde com QQ e não abre as cartas
+        hand.showdown[1].mucked = true;
+
+        // Só o admin (ou a própria Carol) vê as cartas do muck
+        let text = export_hand(&hand.redacted(&HistoryViewer::Admin), "abc12345", None);
+        assert!(text.contains("Carol: mucks hand\n"));
+        assert!(text.contains("Carol (big blind) mucked [Qh Qs]\n"));
+        assert!(!text.contains("Carol: shows"));
+
+        let text = export_hand(&hand.redacted(&HistoryViewer::Public), "abc12345", None);
+        assert!(text.contains("Carol: mucks hand\n"));
+        assert!(text.contains("Carol (big blind) mucked\n"));
+        assert!(!text.contains("Qh Qs"));
+        assert!(!text.contains("Dealt to Carol"));
+
+        let carol = hand.showdown[1].player_id.clone();
+        let text = export_hand(
+            &hand.redacted(&HistoryViewer::Player(carol.clone())),
+            "abc12345",
+            Some(&carol),
+        );
+        assert!(text.contains("Dealt to Carol [Qh Qs]\n"));
+    }
+
+    #[test]
+    fn test_export_preflop_fold_matches_fixture() {
+        let hand = fixture(FOLD_JSON);
+        assert_eq!(export_hand(&hand, "abc12345", Some("p-alice")), FOLD_TXT);
+    }
+
+    #[test]
+    fn test_export_hand_played_by_engine() {
+        let players = ["Alice", "Bob", "Carol"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Player {
+                id: format!("player{}", i + 1),
+                name: name.to_string(),
+                chips: 1000,
+                hand: Vec::new(),
+                current_bet: 0,
+                is_folded: false,
+                is_all_in: false,
+            })
+            .collect();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+        while game.state != GameState::Finished {
+            let player_id = game.players[game.current_player_index].id.clone();
+            game.process_action(&player_id, PlayerAction::Call).unwrap();
+        }
+
+        let hand = game.hand_history.unwrap();
+        let text = export_hand(&hand, "mesa", Some("player2"));
+        assert!(text.contains("*** RIVER ***"));
+        assert!(text.contains("*** SHOW DOWN ***"));
+        assert!(text.contains("Total pot 30 | Rake 0"));
+        assert!(text.contains("Dealt to Bob ["));
+        assert_eq!(text.matches("Dealt to ").count(), 1);
+    }
+
+    #[test]
+    fn test_export_hands_skips_unfinished_and_separates_hands() {
+        let finished = fixture(SHOWDOWN_JSON);
+        let mut unfinished = fixture(FOLD_JSON);
+        unfinished.finished_at = None;
+
+        let text = export_hands(&[finished.clone(), unfinished, finished], "abc12345", None);
+        assert_eq!(text.matches("PokerStars Hand #").count(), 2);
+        assert!(text.contains("\n\n\nPokerStars Hand #"));
+    }
+
+    #[test]
+    fn test_card_notation() {
+        let card = Card {
+            suit: Suit::Diamonds,
+            rank: Rank::Ten,
+        };
+        assert_eq!(card.notation(), "Td");
+        assert_eq!(Card::from_notation("Td"), Some(card));
+        assert_eq!(Card::from_notation("1x"), None);
+        assert_eq!(
+            numeric_hand_id("00000000-0000-4000-8000-000000000001"),
+            0x400
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::*;
+use crate::notation::parse_cards;
+
+/// Range que não segue a notação aceita (`QQ+, AKs, ATo+, 76s-54s`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRangeError {
+    pub input: String,
+}
+
+impl ParseRangeError {
+    fn new(input: &str) -> Self {
+        Self {
+            input: input.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "range inválido: {:?}", self.input)
+    }
+}
+
+impl std::error::Error for ParseRangeError {}
+
+/// Uma combinação de hole cards com o seu peso no range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedCombo {
+    pub cards: [Card; 2],
+    pub weight: f64,
+}
+
+impl WeightedCombo {
+    pub fn conflicts_with(&self, cards: &[Card]) -> bool {
+        self.cards.iter().any(|card| cards.contains(card))
+    }
+}
+
+/// Range de mãos expandido em combinações.
+///
+/// Aceita, separados por vírgula: pares (`QQ`), mãos suited/offsuit (`AKs`,
+/// `AKo`, ou `AK` para as duas), `+` (`QQ+`, `ATo+`), intervalos (`QQ-99`,
+/// `A5s-A2s`, `76s-54s`) e combinações exatas (`AsKd`). Cada item pode ter um
+/// peso entre 0 e 1 depois de `:` (`AKo:0.5`); itens repetidos ficam com o
+/// último peso.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Range {
+    pub combos: Vec<WeightedCombo>,
+}
+
+impl Range {
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Remove as combinações que usam alguma das cartas dadas (board, cartas
+    /// mortas).
+    pub fn without(&self, cards: &[Card]) -> Range {
+        Range {
+            combos: self
+                .combos
+                .iter()
+                .filter(|combo| !combo.conflicts_with(cards))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    fn insert(&mut self, cards: [Card; 2], weight: f64) {
+        let existing = self
+            .combos
+            .iter_mut()
+            .find(|combo| combo.cards.contains(&cards[0]) && combo.cards.contains(&cards[1]));
+        match existing {
+            Some(combo) => combo.weight = weight,
+            None => self.combos.push(WeightedCombo { cards, weight }),
+        }
+    }
+}
+
+impl From<[Card; 2]> for Range {
+    fn from(cards: [Card; 2]) -> Self {
+        Range {
+            combos: vec![WeightedCombo { cards, weight: 1.0 }],
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = ParseRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = Range::default();
+        for item in s.split(',') {
+            let item: String = item.chars().filter(|c| !c.is_whitespace()).collect();
+            let (notation, weight) = match item.split_once(':') {
+                Some((notation, weight)) => {
+                    let weight = weight
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|w| *w > 0.0 && *w <= 1.0)
+                        .ok_or_else(|| ParseRangeError::new(&item))?;
+                    (notation, weight)
+                }
+                None => (item.as_str(), 1.0),
+            };
+
+            for cards in expand(notation).ok_or_else(|| ParseRangeError::new(&item))? {
+                range.insert(cards, weight);
+            }
+        }
+
+        if range.is_empty() {
+            return Err(ParseRangeError::new(s));
+        }
+        Ok(range)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Suitedness {
+    Suited,
+    Offsuit,
+    Any,
+}
+
+/// Classe de mão: dois ranks (o maior primeiro) e se é suited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HandClass {
+    high: u8,
+    low: u8,
+    suited: Suitedness,
+}
+
+impl HandClass {
+    fn parse(notation: &str) -> Option<HandClass> {
+        let mut chars = notation.chars();
+        let first = rank_value(chars.next()?)?;
+        let second = rank_value(chars.next()?)?;
+        let suited = match chars.next() {
+            None => Suitedness::Any,
+            Some('s' | 'S') => Suitedness::Suited,
+            Some('o' | 'O') => Suitedness::Offsuit,
+            Some(_) => return None,
+        };
+        if chars.next().is_some() || (first == second && suited != Suitedness::Any) {
+            return None;
+        }
+
+        Some(HandClass {
+            high: first.max(second),
+            low: first.min(second),
+            suited,
+        })
+    }
+
+    fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    fn with_ranks(self, high: u8, low: u8) -> HandClass {
+        HandClass { high, low, ..self }
+    }
+
+    fn combos(&self) -> Vec<[Card; 2]> {
+        let deck = Game::create_deck();
+        let of_rank = |rank: u8| -> Vec<&Card> {
+            deck.iter().filter(|card| card.rank as u8 == rank).collect()
+        };
+
+        let mut combos = Vec::new();
+        for (i, first) in of_rank(self.high).into_iter().enumerate() {
+            for (j, second) in of_rank(self.low).into_iter().enumerate() {
+                // Nos pares cada combinação aparece uma vez só
+                if self.is_pair() && j <= i {
+                    continue;
+                }
+                let suited = first.suit == second.suit;
+                let wanted = match self.suited {
+                    Suitedness::Suited => suited,
+                    Suitedness::Offsuit => !suited,
+                    Suitedness::Any => true,
+                };
+                if wanted {
+                    combos.push([first.clone(), second.clone()]);
+                }
+            }
+        }
+        combos
+    }
+}
+
+fn rank_value(c: char) -> Option<u8> {
+    c.to_string().parse::<Rank>().ok().map(|rank| rank as u8)
+}
+
+/// Expande um item do range nas suas combinações.
+fn expand(notation: &str) -> Option<Vec<[Card; 2]>> {
+    // Combinação exata: "AsKd"
+    if let Ok(cards) = parse_cards(notation) {
+        let [first, second]: [Card; 2] = cards.try_into().ok()?;
+        return (first != second).then(|| vec![[first, second]]);
+    }
+
+    let classes = if let Some(start) = notation.strip_suffix('+') {
+        let start = HandClass::parse(start)?;
+        if start.is_pair() {
+            (start.high..=14).map(|r| start.with_ranks(r, r)).collect()
+        } else {
+            // ATo+: o kicker sobe até logo abaixo da carta alta
+            (start.low..start.high)
+                .map(|low| start.with_ranks(start.high, low))
+                .collect()
+        }
+    } else if let Some((from, to)) = notation.split_once('-') {
+        class_interval(HandClass::parse(from)?, HandClass::parse(to)?)?
+    } else {
+        vec![HandClass::parse(notation)?]
+    };
+
+    Some(classes.iter().flat_map(HandClass::combos).collect())
+}
+
+/// Intervalos `QQ-99`, `A5s-A2s` (kicker variando) e `76s-54s` (conectores
+/// com o mesmo gap), em qualquer ordem.
+fn class_interval(from: HandClass, to: HandClass) -> Option<Vec<HandClass>> {
+    if from.suited != to.suited {
+        return None;
+    }
+    let (top, bottom) = if from.high >= to.high && from.low >= to.low {
+        (from, to)
+    } else {
+        (to, from)
+    };
+
+    if top.is_pair() && bottom.is_pair() {
+        return Some(
+            (bottom.high..=top.high)
+                .map(|r| top.with_ranks(r, r))
+                .collect(),
+        );
+    }
+    if top.is_pair() || bottom.is_pair() {
+        return None;
+    }
+    if top.high == bottom.high {
+        return Some(
+            (bottom.low..=top.low)
+                .map(|low| top.with_ranks(top.high, low))
+                .collect(),
+        );
+    }
+    if top.high - top.low == bottom.high - bottom.low {
+        let gap = top.high - top.low;
+        return Some(
+            (bottom.high..=top.high)
+                .map(|high| top.with_ranks(high, high - gap))
+                .collect(),
+        );
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(notation: &str) -> Range {
+        notation.parse().unwrap()
+    }
+
+    #[test]
+    fn test_combo_counts() {
+        assert_eq!(range("QQ").len(), 6);
+        assert_eq!(range("AKs").len(), 4);
+        assert_eq!(range("AKo").len(), 12);
+        assert_eq!(range("AK").len(), 16);
+        assert_eq!(range("QQ+").len(), 18);
+        assert_eq!(range("ATo+").len(), 4 * 12);
+        assert_eq!(range("76s-54s").len(), 3 * 4);
+        assert_eq!(range("A5s-A2s").len(), 4 * 4);
+        assert_eq!(range("99-QQ").len(), 4 * 6);
+        assert_eq!(range("AsKd").len(), 1);
+        assert_eq!(range("QQ+, AKs, ATo+, 76s-54s").len(), 18 + 4 + 48 + 12);
+    }
+
+    #[test]
+    fn test_repeated_items_are_merged() {
+        let merged = range("QQ+, KK:0.5, KhKd");
+        assert_eq!(merged.len(), 18);
+        let kings: Vec<f64> = merged
+            .combos
+            .iter()
+            .filter(|combo| combo.cards[0].rank == Rank::King)
+            .map(|combo| combo.weight)
+            .collect();
+        assert_eq!(kings.iter().filter(|&&w| w == 0.5).count(), 5);
+        assert_eq!(kings.iter().filter(|&&w| w == 1.0).count(), 1);
+    }
+
+    #[test]
+    fn test_board_conflicts_are_removed() {
+        let board = parse_cards("As Kd 2c").unwrap();
+        assert_eq!(range("AA").without(&board).len(), 3);
+        assert_eq!(range("AKs").without(&board).len(), 2);
+        assert_eq!(range("AKo").without(&board).len(), 7);
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        for invalid in [
+            "", "QQs", "AKx", "AK+s", "AKs-QJo", "76s-A5s", "AsAs", "AKs:0", "AKs:1.5", "XX",
+        ] {
+            assert!(invalid.parse::<Range>().is_err(), "{:?}", invalid);
+        }
+    }
+}
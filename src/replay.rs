@@ -0,0 +1,412 @@
+use serde::Serialize;
+
+use crate::error::GameError;
+use crate::history::*;
+use crate::models::*;
+
+/// Replay de uma mão gravada: o estado do jogo após cada ação, obtido
+/// re-executando o motor a partir do deck gravado, e as divergências entre o
+/// que foi gravado e o que o motor reproduziu.
+#[derive(Debug, Clone, Serialize)]
+pub struct HandReplay {
+    pub hand_id: String,
+    pub steps: Vec<ReplayStep>,
+    pub divergences: Vec<Divergence>,
+    /// `true` quando o replay chegou ao mesmo resultado gravado
+    pub consistent: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayStep {
+    /// 0 é o estado após distribuir as cartas e postar os blinds
+    pub step: usize,
+    pub action: Option<ActionRecord>,
+    pub game_state: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Divergence {
+    pub step: usize,
+    pub field: String,
+    pub expected: serde_json::Value,
+    pub actual: serde_json::Value,
+}
+
+impl HandReplay {
+    /// Cópia do replay para `viewer`, com as mesmas regras de
+    /// `HandHistory::redacted`: fora da visão do admin, cada passo mostra só as
+    /// cartas abertas na mesa (e as do próprio jogador), e as divergências
+    /// trazem o campo mas não os valores.
+    pub fn redacted(&self, hand: &HandHistory, viewer: &HistoryViewer) -> HandReplay {
+        let mut replay = self.clone();
+        if *viewer == HistoryViewer::Admin {
+            return replay;
+        }
+
+        let visible = |player_id: &str| {
+            hand.was_shown(player_id)
+                || matches!(viewer, HistoryViewer::Player(id) if id == player_id)
+        };
+        for step in &mut replay.steps {
+            let players = step.game_state["players"].as_array_mut();
+            for player in players.into_iter().flatten() {
+                let hidden = player["id"].as_str().is_some_and(|id| !visible(id));
+                if hidden {
+                    player["hand"] = serde_json::json!([]);
+                }
+            }
+        }
+        for divergence in &mut replay.divergences {
+            divergence.expected = serde_json::Value::Null;
+            divergence.actual = serde_json::Value::Null;
+        }
+        replay
+    }
+}
+
+impl Game {
+    /// Recria o jogo no início da mão gravada, com os mesmos assentos, stacks,
+    /// dealer e deck. Falha com `InvalidDeck` se o deck gravado tiver cartas
+    /// repetidas ou desconhecidas.
+    pub fn from_history(hand: &HandHistory) -> Result<Game, GameError> {
+        let players = hand
+            .seats
+            .iter()
+            .map(|seat| Player {
+                id: seat.player_id.clone(),
+                name: seat.name.clone(),
+                chips: seat.starting_stack,
+                hand: Vec::new(),
+                current_bet: 0,
+                is_folded: false,
+                is_all_in: false,
+            })
+            .collect();
+
+        let mut game = Game::new(players);
+        game.id = hand.game_id.clone();
+        game.dealer_index = hand.dealer_index;
+        game.small_blind = hand.small_blind;
+        game.big_blind = hand.big_blind;
+        game.hand_number = hand.hand_number.saturating_sub(1);
+        // Mãos em que o board foi distribuído mais de uma vez abrem a mesma votação
+        game.rules.max_runs = hand.runs.len().clamp(1, u8::MAX as usize) as u8;
+        game.start_round_with_deck(hand.deck.clone())?;
+        Ok(game)
+    }
+}
+
+/// Reproduz a mão passo a passo. Mãos sem deck gravado (importadas de outros
+/// sites) não podem ser reproduzidas.
+pub fn replay_hand(hand: &HandHistory) -> Result<HandReplay, GameError> {
+    if hand.deck.is_empty() || hand.seats.len() < 2 {
+        return Err(GameError::HandNotReplayable);
+    }
+
+    let mut game = Game::from_history(hand)?;
+    let mut divergences = Vec::new();
+    let mut diverge = |step: usize, field: &str, expected: serde_json::Value, actual| {
+        divergences.push(Divergence {
+            step,
+            field: field.to_string(),
+            expected,
+            actual,
+        });
+    };
+
+    let replayed = |game: &Game| game.hand_history.clone().unwrap_or_else(|| hand.clone());
+
+    for (recorded, actual) in hand.seats.iter().zip(&replayed(&game).seats) {
+        if recorded.hole_cards != actual.hole_cards {
+            diverge(
+                0,
+                &format!("seats[{}].hole_cards", recorded.seat),
+                serde_json::json!(recorded.hole_cards),
+                serde_json::json!(actual.hole_cards),
+            );
+        }
+    }
+
+    let blinds = hand
+        .actions
+        .iter()
+        .take_while(|action| matches!(action.kind, ActionKind::SmallBlind | ActionKind::BigBlind))
+        .count();
+    let mut steps = vec![ReplayStep {
+        step: 0,
+        action: None,
+        game_state: game.full_game_state(),
+    }];
+
+    for (index, recorded) in hand.actions.iter().enumerate().skip(blinds) {
+        let step = steps.len();
+        let action = match to_player_action(&game, recorded) {
+            Some(action) => action,
+            None => {
+                diverge(
+                    step,
+                    "actions",
+                    serde_json::json!(recorded),
+                    serde_json::Value::Null,
+                );
+                break;
+            }
+        };
+
+        if let Err(error) = game.process_action(&recorded.player_id, action) {
+            diverge(
+                step,
+                "actions",
+                serde_json::json!(recorded),
+                serde_json::json!(error.code()),
+            );
+            break;
+        }
+
+        let history = replayed(&game);
+        if let Some(actual) = history.actions.get(index) {
+            compare_action(step, recorded, actual, &mut diverge);
+        }
+        steps.push(ReplayStep {
+            step,
+            action: Some(recorded.clone()),
+            game_state: game.full_game_state(),
+        });
+    }
+
+    // Os jogadores all-in repetem a escolha gravada de quantas vezes rodar
+    if let Some(vote) = game.run_it.clone() {
+        for player_id in &vote.players {
+            let _ = game.choose_runs(player_id, vote.max_runs);
+        }
+    }
+
+    let history = replayed(&game);
+    if history.actions.len() != hand.actions.len() {
+        diverge(
+            steps.len() - 1,
+            "actions.len",
+            serde_json::json!(hand.actions.len()),
+            serde_json::json!(history.actions.len()),
+        );
+    }
+    if history.board.cards() != hand.board.cards() {
+        diverge(
+            steps.len() - 1,
+            "board",
+            serde_json::json!(hand.board),
+            serde_json::json!(history.board),
+        );
+    }
+    let ranks = |h: &HandHistory| {
+        h.showdown
+            .iter()
+            .map(|s| (s.player_id.clone(), s.hand_rank))
+            .collect::<Vec<_>>()
+    };
+    if ranks(&history) != ranks(hand) {
+        diverge(
+            steps.len() - 1,
+            "showdown",
+            serde_json::json!(hand.showdown),
+            serde_json::json!(history.showdown),
+        );
+    }
+    let awards = |h: &HandHistory| {
+        h.winnings
+            .iter()
+            .map(|w| (w.player_id.clone(), w.amount))
+            .collect::<Vec<_>>()
+    };
+    if awards(&history) != awards(hand) {
+        diverge(
+            steps.len() - 1,
+            "winnings",
+            serde_json::json!(hand.winnings),
+            serde_json::json!(history.winnings),
+        );
+    }
+
+    Ok(HandReplay {
+        hand_id: hand.hand_id.clone(),
+        steps,
+        consistent: divergences.is_empty(),
+        divergences,
+    })
+}
+
+/// Traduz a ação gravada para a ação do motor no estado atual do replay.
+fn to_player_action(game: &Game, recorded: &ActionRecord) -> Option<PlayerAction> {
+    let player = game.players.iter().find(|p| p.id == recorded.player_id)?;
+    Some(match recorded.kind {
+        ActionKind::Fold => PlayerAction::Fold,
+        ActionKind::Check => PlayerAction::Check,
+        ActionKind::Call => PlayerAction::Call,
+        ActionKind::AllIn => PlayerAction::AllIn,
+        ActionKind::Raise => {
+            let to_call = game.current_bet.saturating_sub(player.current_bet);
+            PlayerAction::Raise(recorded.amount.saturating_sub(to_call))
+        }
+        // Os blinds são postados pelo próprio motor
+        ActionKind::SmallBlind | ActionKind::BigBlind => return None,
+    })
+}
+
+fn compare_action(
+    step: usize,
+    recorded: &ActionRecord,
+    actual: &ActionRecord,
+    diverge: &mut impl FnMut(usize, &str, serde_json::Value, serde_json::Value),
+) {
+    let fields = [
+        (
+            "street",
+            serde_json::json!(recorded.street),
+            serde_json::json!(actual.street),
+        ),
+        (
+            "amount",
+            serde_json::json!(recorded.amount),
+            serde_json::json!(actual.amount),
+        ),
+        (
+            "total_bet",
+            serde_json::json!(recorded.total_bet),
+            serde_json::json!(actual.total_bet),
+        ),
+        (
+            "pot_after",
+            serde_json::json!(recorded.pot_after),
+            serde_json::json!(actual.pot_after),
+        ),
+    ];
+    for (field, expected, actual) in fields {
+        if expected != actual {
+            diverge(step, &format!("actions.{}", field), expected, actual);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn played_hand() -> HandHistory {
+        let players = (1..=3)
+            .map(|i| Player {
+                id: format!("player{}", i),
+                name: format!("Player {}", i),
+                chips: 1000,
+                hand: Vec::new(),
+                current_bet: 0,
+                is_folded: false,
+                is_all_in: false,
+            })
+            .collect();
+        let mut game = Game::new(players);
+        game.start_round().unwrap();
+
+        let mut raised = false;
+        while game.state != GameState::Finished {
+            let player_id = game.players[game.current_player_index].id.clone();
+            let action = if raised {
+                PlayerAction::Call
+            } else {
+                raised = true;
+                PlayerAction::Raise(20)
+            };
+            game.process_action(&player_id, action).unwrap();
+        }
+        game.hand_history.unwrap()
+    }
+
+    #[test]
+    fn test_replay_matches_recorded_hand() {
+        let hand = played_hand();
+        let replay = replay_hand(&hand).unwrap();
+
+        assert!(replay.consistent, "{:?}", replay.divergences);
+        let blinds = 2;
+        assert_eq!(replay.steps.len(), hand.actions.len() - blinds + 1);
+        assert_eq!(replay.steps[0].game_state["pot"], 15);
+
+        let last = replay.steps.last().unwrap();
+        assert_eq!(last.game_state["state"], "Finished");
+        assert_eq!(
+            last.game_state["community_cards"],
+            serde_json::json!(hand.board.cards())
+        );
+    }
+
+    #[test]
+    fn test_replay_steps_track_pot_after_each_action() {
+        let hand = played_hand();
+        let replay = replay_hand(&hand).unwrap();
+
+        for step in replay.steps.iter().skip(1) {
+            let action = step.action.as_ref().unwrap();
+            // O pot volta a zero quando a mão termina e o pot é distribuído
+            if step.game_state["state"] != "Finished" {
+                assert_eq!(step.game_state["pot"], action.pot_after);
+            }
+        }
+    }
+
+    #[test]
+    fn test_replay_flags_tampered_amounts() {
+        let mut hand = played_hand();
+        hand.actions[2].pot_after += 100;
+        hand.winnings[0].amount += 1;
+
+        let replay = replay_hand(&hand).unwrap();
+        assert!(!replay.consistent);
+        let fields: Vec<_> = replay
+            .divergences
+            .iter()
+            .map(|d| d.field.as_str())
+            .collect();
+        assert!(fields.contains(&"actions.pot_after"));
+        assert!(fields.contains(&"winnings"));
+        assert_eq!(replay.divergences[0].step, 1);
+    }
+
+    #[test]
+    fn test_replay_flags_different_deck() {
+        let mut hand = played_hand();
+        hand.deck.swap(0, 51);
+
+        let replay = replay_hand(&hand).unwrap();
+        assert!(!replay.consistent);
+        assert!(replay
+            .divergences
+            .iter()
+            .any(|d| d.field.ends_with("hole_cards") || d.field == "board"));
+    }
+
+    #[test]
+    fn test_replay_stops_on_rejected_action() {
+        let mut hand = played_hand();
+        // Check quando há aposta para pagar é rejeitado pelo motor
+        hand.actions[3].kind = ActionKind::Check;
+
+        let replay = replay_hand(&hand).unwrap();
+        assert!(!replay.consistent);
+        let rejected = replay
+            .divergences
+            .iter()
+            .find(|d| d.field == "actions")
+            .unwrap();
+        assert_eq!(rejected.actual, "cannot_check");
+        assert_eq!(replay.steps.len(), 2);
+    }
+
+    #[test]
+    fn test_imported_hand_without_deck_is_not_replayable() {
+        let mut hand = played_hand();
+        hand.deck.clear();
+        assert_eq!(
+            replay_hand(&hand).unwrap_err(),
+            GameError::HandNotReplayable
+        );
+    }
+}
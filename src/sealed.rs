@@ -0,0 +1,411 @@
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::error::GameError;
+use crate::models::*;
+
+/// Chave mestra do servidor. As chaves de cada mão são derivadas dela e de um
+/// salt aleatório, então um dump do banco ou do journal sem a chave não revela
+/// as cartas ainda não distribuídas.
+static MASTER_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+
+/// Define a chave mestra. Precisa ser chamada antes de qualquer deck ser
+/// selado; retorna `false` se a chave já estava definida.
+pub fn set_master_key(key: [u8; 32]) -> bool {
+    MASTER_KEY.set(key).is_ok()
+}
+
+/// Sem chave configurada, usa uma chave efêmera: os dados selados só podem
+/// ser abertos pelo mesmo processo.
+fn master_key() -> &'static [u8; 32] {
+    MASTER_KEY.get_or_init(|| {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        key
+    })
+}
+
+/// Lê a chave mestra (hex) do arquivo, criando uma chave nova se ele não
+/// existir.
+pub fn load_or_create_key(path: impl AsRef<Path>) -> std::io::Result<[u8; 32]> {
+    let path = path.as_ref();
+    if path.exists() {
+        let encoded = std::fs::read_to_string(path)?;
+        return hex::decode(encoded.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "a chave deve ter 32 bytes em hex",
+                )
+            });
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    std::io::Write::write_all(&mut options.open(path)?, hex::encode(key).as_bytes())?;
+    Ok(key)
+}
+
+/// Bloco cifrado com ChaCha20-Poly1305. Cada bloco tem um salt aleatório do
+/// qual sai a sua própria chave, que cifra uma única mensagem (por isso o
+/// nonce pode ser fixo).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Sealed {
+    salt: String,
+    ciphertext: String,
+}
+
+impl Sealed {
+    pub fn seal(plaintext: &[u8]) -> Self {
+        Self::seal_with_key(master_key(), plaintext)
+    }
+
+    fn seal_with_key(key: &[u8; 32], plaintext: &[u8]) -> Self {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let ciphertext = cipher(key, &salt)
+            .encrypt(&Nonce::default(), plaintext)
+            .expect("ChaCha20-Poly1305 não falha ao cifrar");
+
+        Self {
+            salt: hex::encode(salt),
+            ciphertext: hex::encode(ciphertext),
+        }
+    }
+
+    /// `None` se o bloco foi adulterado ou selado com outra chave mestra.
+    pub fn open(&self) -> Option<Vec<u8>> {
+        let salt = hex::decode(&self.salt).ok()?;
+        let ciphertext = hex::decode(&self.ciphertext).ok()?;
+        cipher(master_key(), &salt)
+            .decrypt(&Nonce::default(), ciphertext.as_slice())
+            .ok()
+    }
+}
+
+fn cipher(master_key: &[u8; 32], salt: &[u8]) -> ChaCha20Poly1305 {
+    let mut hasher = Sha256::new();
+    hasher.update(master_key);
+    hasher.update(salt);
+    let key: [u8; 32] = hasher.finalize().into();
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+/// Deck de uma mão, cifrado com uma chave própria. A ordem completa fica
+/// selada no snapshot e no journal, que expõem apenas quantas cartas restam.
+/// Em memória o deck é aberto uma única vez (ao ser selado ou na primeira
+/// carta depois de restaurado) e não é serializado nem aparece no `Debug`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(try_from = "StoredDeck")]
+pub struct SealedDeck {
+    sealed: Sealed,
+    remaining: usize,
+    #[serde(skip)]
+    opened: Option<Vec<Card>>,
+}
+
+impl std::fmt::Debug for SealedDeck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SealedDeck")
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Formatos aceitos na leitura: o deck selado ou a lista de cartas em claro
+/// gravada por versões anteriores, que é selada ao ser carregada.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredDeck {
+    Sealed { sealed: Sealed, remaining: usize },
+    Plain(Vec<Card>),
+}
+
+impl TryFrom<StoredDeck> for SealedDeck {
+    type Error = GameError;
+
+    fn try_from(stored: StoredDeck) -> Result<Self, GameError> {
+        match stored {
+            StoredDeck::Sealed { sealed, remaining } => Ok(Self {
+                sealed,
+                remaining,
+                opened: None,
+            }),
+            StoredDeck::Plain(cards) => Self::seal(&cards),
+        }
+    }
+}
+
+impl SealedDeck {
+    /// Sela o deck na ordem dada (as cartas saem do fim). Cartas repetidas ou
+    /// fora do baralho são rejeitadas com `InvalidDeck`.
+    pub fn seal(cards: &[Card]) -> Result<Self, GameError> {
+        let deck = Game::create_deck();
+        let mut bytes = Vec::with_capacity(cards.len());
+        for card in cards {
+            let index = deck
+                .iter()
+                .position(|c| c == card)
+                .ok_or(GameError::InvalidDeck)? as u8;
+            if bytes.contains(&index) {
+                return Err(GameError::InvalidDeck);
+            }
+            bytes.push(index);
+        }
+
+        Ok(Self {
+            sealed: Sealed::seal(&bytes),
+            remaining: cards.len(),
+            opened: Some(cards.to_vec()),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.remaining
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Abre o deck selado, se ainda não estiver aberto. Falha com
+    /// `DeckUnavailable` se o deck foi adulterado ou selado com outra chave
+    /// mestra.
+    pub fn open(&mut self) -> Result<&[Card], GameError> {
+        if self.opened.is_none() {
+            self.opened = Some(self.decrypt()?);
+        }
+        Ok(self.opened.as_deref().unwrap_or_default())
+    }
+
+    /// Distribui a próxima carta; `None` quando o deck acabou.
+    pub fn pop(&mut self) -> Result<Option<Card>, GameError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let index = self.remaining - 1;
+        let card = self
+            .open()?
+            .get(index)
+            .cloned()
+            .ok_or(GameError::DeckUnavailable)?;
+        self.remaining -= 1;
+        Ok(Some(card))
+    }
+
+    /// Ordem completa do deck da mão, inclusive as cartas já distribuídas. Só
+    /// deve sair do servidor depois que a mão termina.
+    pub fn reveal(&self) -> Result<Vec<Card>, GameError> {
+        match &self.opened {
+            Some(cards) => Ok(cards.clone()),
+            None => self.decrypt(),
+        }
+    }
+
+    fn decrypt(&self) -> Result<Vec<Card>, GameError> {
+        let deck = Game::create_deck();
+        self.sealed
+            .open()
+            .ok_or(GameError::DeckUnavailable)?
+            .into_iter()
+            .map(|index| deck.get(index as usize).cloned())
+            .collect::<Option<Vec<Card>>>()
+            .filter(|cards| cards.len() >= self.remaining)
+            .ok_or(GameError::DeckUnavailable)
+    }
+}
+
+/// Valor secreto lido de um snapshot. `Unavailable` quando o bloco selado não
+/// abre (gravado com outra chave mestra ou adulterado), para quem lê decidir o
+/// que fazer em vez de falhar a restauração inteira. Valores em claro gravados
+/// por versões anteriores continuam sendo lidos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unsealed<T> {
+    Value(T),
+    Unavailable,
+}
+
+impl<T: Default> Default for Unsealed<T> {
+    fn default() -> Self {
+        Unsealed::Value(T::default())
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Unsealed<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored<T> {
+            Sealed(Sealed),
+            Plain(T),
+        }
+
+        match Stored::<T>::deserialize(deserializer)? {
+            Stored::Sealed(sealed) => match sealed.open() {
+                Some(plaintext) => serde_json::from_slice(&plaintext)
+                    .map(Unsealed::Value)
+                    .map_err(D::Error::custom),
+                None => Ok(Unsealed::Unavailable),
+            },
+            Stored::Plain(value) => Ok(Unsealed::Value(value)),
+        }
+    }
+}
+
+/// Serializa um valor qualquer selado, para uso com `#[serde(with)]` em
+/// campos secretos (como as server seeds do commit-reveal). Na leitura, um
+/// bloco que não abre é um erro; use [`Unsealed`] para tratá-lo.
+pub mod secret {
+    use super::*;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let plaintext = serde_json::to_vec(value).map_err(S::Error::custom)?;
+        Sealed::seal(&plaintext).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: DeserializeOwned, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        match Unsealed::<T>::deserialize(deserializer)? {
+            Unsealed::Value(value) => Ok(value),
+            Unsealed::Unavailable => Err(D::Error::custom("não foi possível abrir o valor selado")),
+        }
+    }
+}
+
+/// Bloco selado com outra chave mestra, como o de um snapshot gravado antes de
+/// a chave ser trocada.
+#[cfg(test)]
+pub(crate) fn sealed_with_other_key() -> serde_json::Value {
+    serde_json::to_value(Sealed::seal_with_key(&[2; 32], b"\"outra chave\"")).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shuffle::DeckSource;
+
+    #[test]
+    fn test_sealed_deck_deals_in_order() {
+        let cards = DeckSource::Seeded { seed: 1 }.deck_for_hand(1, 2);
+        let mut deck = SealedDeck::seal(&cards).unwrap();
+        assert_eq!(deck.len(), 52);
+        assert_eq!(deck.reveal(), Ok(cards.clone()));
+
+        let mut expected = cards.clone();
+        for _ in 0..5 {
+            assert_eq!(deck.pop(), Ok(expected.pop()));
+        }
+        assert_eq!(deck.len(), 47);
+        // A ordem completa continua disponível para o histórico
+        assert_eq!(deck.reveal(), Ok(cards));
+        assert!(!format!("{:?}", deck).contains("rank"));
+    }
+
+    #[test]
+    fn test_invalid_cards_are_not_sealed() {
+        let mut cards = Game::create_deck();
+        cards[1] = cards[0].clone();
+        assert!(matches!(
+            SealedDeck::seal(&cards),
+            Err(GameError::InvalidDeck)
+        ));
+    }
+
+    #[test]
+    fn test_serialized_deck_has_no_cards() {
+        let cards = Game::create_deck();
+        let json = serde_json::to_string(&SealedDeck::seal(&cards).unwrap()).unwrap();
+
+        assert!(!json.contains("suit"));
+        assert!(!json.contains("Hearts"));
+        let mut restored: SealedDeck = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.reveal(), Ok(cards.clone()));
+        assert_eq!(restored.pop(), Ok(cards.last().cloned()));
+    }
+
+    #[test]
+    fn test_tampered_deck_is_an_error() {
+        let deck = SealedDeck::seal(&Game::create_deck()).unwrap();
+        let mut json = serde_json::to_value(&deck).unwrap();
+        let mut ciphertext = hex::decode(json["sealed"]["ciphertext"].as_str().unwrap()).unwrap();
+        ciphertext[0] ^= 1;
+        json["sealed"]["ciphertext"] = hex::encode(ciphertext).into();
+        let mut deck: SealedDeck = serde_json::from_value(json).unwrap();
+
+        assert_eq!(deck.pop(), Err(GameError::DeckUnavailable));
+        assert_eq!(deck.reveal(), Err(GameError::DeckUnavailable));
+        assert_eq!(deck.len(), 52);
+    }
+
+    #[test]
+    fn test_plain_deck_from_older_snapshots_is_sealed() {
+        let cards = Game::create_deck();
+        let json = serde_json::to_string(&cards).unwrap();
+        let mut deck: SealedDeck = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deck.len(), 52);
+        assert_eq!(deck.pop(), Ok(cards.last().cloned()));
+
+        let repeated = serde_json::to_string(&vec![cards[0].clone(); 2]).unwrap();
+        assert!(serde_json::from_str::<SealedDeck>(&repeated).is_err());
+    }
+
+    #[test]
+    fn test_secret_fields_are_sealed() {
+        #[derive(Serialize, Deserialize)]
+        struct Secret {
+            #[serde(with = "secret")]
+            seed: String,
+        }
+
+        let json = serde_json::to_string(&Secret {
+            seed: "seed-secreta".to_string(),
+        })
+        .unwrap();
+        assert!(!json.contains("seed-secreta"));
+        let restored: Secret = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.seed, "seed-secreta");
+
+        let plain: Secret = serde_json::from_str(r#"{"seed":"antiga"}"#).unwrap();
+        assert_eq!(plain.seed, "antiga");
+
+        let other_key = serde_json::json!({ "seed": sealed_with_other_key() });
+        assert!(serde_json::from_value::<Secret>(other_key.clone()).is_err());
+        assert_eq!(
+            serde_json::from_value::<Unsealed<String>>(other_key["seed"].clone()).unwrap(),
+            Unsealed::Unavailable
+        );
+    }
+
+    #[test]
+    fn test_key_file_is_created_and_reused() {
+        let path = std::env::temp_dir().join(format!("poker-{}.key", uuid::Uuid::new_v4()));
+        let key = load_or_create_key(&path).unwrap();
+        assert_eq!(load_or_create_key(&path).unwrap(), key);
+
+        std::fs::write(&path, "curta").unwrap();
+        assert!(load_or_create_key(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,292 @@
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use dashmap::DashMap;
+use serde::Serialize;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tower_http::cors::CorsLayer;
+
+use crate::history::HandHistory;
+use crate::journal::GameJournal;
+use crate::models::*;
+use crate::storage::{RoomSnapshot, RoomStore, StorageError};
+use crate::{auth, handlers, i18n, notation, sse, websocket};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub rooms: Arc<DashMap<String, Room>>,
+    pub store: Arc<dyn RoomStore>,
+    pub journal: Option<Arc<GameJournal>>,
+    /// Permite criar salas com seed fixa ou deck empilhado
+    pub test_mode: bool,
+    /// Prazo da votação de quantas vezes rodar o board
+    pub run_it_timeout: Duration,
+    /// SHA-256 do token de admin, que vê os históricos completos
+    admin_token: Option<String>,
+    writer: Arc<Writer>,
+}
+
+/// Prazo padrão para os jogadores all-in votarem quantas vezes rodar.
+const RUN_IT_TIMEOUT: Duration = Duration::from_secs(15);
+
+impl AppState {
+    pub fn new(store: Arc<dyn RoomStore>) -> Self {
+        Self {
+            rooms: Arc::new(DashMap::new()),
+            store,
+            journal: None,
+            test_mode: false,
+            run_it_timeout: RUN_IT_TIMEOUT,
+            admin_token: None,
+            writer: Arc::new(Writer::spawn()),
+        }
+    }
+
+    pub fn with_test_mode(mut self, enabled: bool) -> Self {
+        self.test_mode = enabled;
+        self
+    }
+
+    pub fn with_run_it_timeout(mut self, timeout: Duration) -> Self {
+        self.run_it_timeout = timeout;
+        self
+    }
+
+    /// Token que dá acesso aos históricos completos (deck e cartas do muck).
+    /// Sem token, ninguém os vê.
+    pub fn with_admin_token(mut self, token: Option<&str>) -> Self {
+        self.admin_token = token.map(auth::hash_token);
+        self
+    }
+
+    pub fn is_admin(&self, token: &str) -> bool {
+        self.admin_token.as_ref().is_some_and(|hash| {
+            auth::constant_time_eq(auth::hash_token(token).as_bytes(), hash.as_bytes())
+        })
+    }
+
+    /// Cria o estado carregando as salas salvas no armazenamento.
+    pub fn restore(store: Arc<dyn RoomStore>) -> Result<Self, StorageError> {
+        let state = Self::new(store);
+        for snapshot in state.store.load_rooms()? {
+            state
+                .rooms
+                .insert(snapshot.id.clone(), Room::from_snapshot(snapshot));
+        }
+        state.void_unreadable_hands();
+        Ok(state)
+    }
+
+    /// Retoma as mãos interrompidas a partir do journal. O snapshot do journal
+    /// prevalece sobre o do armazenamento, já que é gravado com `fsync` a cada
+    /// ação. Depois da restauração o journal é compactado.
+    pub fn with_journal(mut self, journal: GameJournal) -> Result<Self, StorageError> {
+        for snapshot in journal.replay()? {
+            match self.rooms.get_mut(&snapshot.id) {
+                Some(mut room) => room.game = snapshot.game,
+                None => {
+                    self.rooms
+                        .insert(snapshot.id.clone(), Room::from_snapshot(snapshot));
+                }
+            }
+        }
+        journal.compact()?;
+
+        self.journal = Some(Arc::new(journal));
+        self.void_unreadable_hands();
+        Ok(self)
+    }
+
+    /// Anula as mãos restauradas cujo deck selado não pode ser aberto (dados
+    /// gravados com outra chave mestra ou adulterados). As fichas voltam ao
+    /// início da mão e a sala anulada é gravada de novo.
+    fn void_unreadable_hands(&self) {
+        for mut room in self.rooms.iter_mut() {
+            let Some(error) = room
+                .game
+                .as_mut()
+                .and_then(Game::void_hand_if_deck_unavailable)
+            else {
+                continue;
+            };
+            eprintln!("Mão da sala {} anulada na restauração: {}", room.id, error);
+            let _ = self.persist(&room);
+        }
+    }
+
+    /// Agenda a gravação do histórico de uma mão finalizada. Ela sai antes de
+    /// qualquer `persist` feito depois, então esperar o `persist` seguinte
+    /// também garante a mão gravada.
+    pub fn record_hand(&self, room_id: &str, hand: &HandHistory) {
+        let _ = self.writer.send(
+            Write::Hand(room_id.to_string(), Box::new(hand.clone())),
+            self,
+        );
+    }
+
+    /// Agenda a gravação da sala no armazenamento e no journal. O snapshot é
+    /// tirado agora, com o lock da sala, e gravado por uma thread própria na
+    /// ordem das chamadas; a escrita e o `fsync` acontecem fora do lock. A
+    /// resposta ao cliente deve esperar o `PendingWrite` depois de soltar a
+    /// sala, para que uma ação confirmada esteja sempre gravada.
+    pub fn persist(&self, room: &Room) -> PendingWrite {
+        self.writer
+            .send(Write::Room(Box::new(room.snapshot())), self)
+    }
+
+    /// Situação das gravações: falhas seguidas e o último erro. Falhas não
+    /// derrubam a partida em andamento, mas aparecem em `/health`.
+    pub fn storage_health(&self) -> StorageHealth {
+        self.writer.health.lock().unwrap().clone()
+    }
+}
+
+/// Gravação agendada por `AppState::persist`.
+#[must_use = "a resposta deve esperar a gravação"]
+pub struct PendingWrite(oneshot::Receiver<()>);
+
+impl PendingWrite {
+    /// Espera a gravação terminar (com sucesso ou não).
+    pub async fn written(self) {
+        let _ = self.0.await;
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageHealth {
+    /// Gravações que falharam desde a última que deu certo
+    pub consecutive_failures: u64,
+    pub last_error: Option<String>,
+}
+
+enum Write {
+    Room(Box<RoomSnapshot>),
+    Hand(String, Box<HandHistory>),
+}
+
+struct Job {
+    write: Write,
+    store: Arc<dyn RoomStore>,
+    journal: Option<Arc<GameJournal>>,
+    done: oneshot::Sender<()>,
+}
+
+/// Thread que grava os snapshots e as mãos, uma de cada vez e na ordem em que
+/// foram agendados, para que um snapshot antigo nunca sobrescreva um novo.
+struct Writer {
+    jobs: mpsc::Sender<Job>,
+    health: Arc<Mutex<StorageHealth>>,
+}
+
+impl Writer {
+    fn spawn() -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let health = Arc::new(Mutex::new(StorageHealth::default()));
+        let status = health.clone();
+
+        std::thread::spawn(move || {
+            for job in receiver {
+                // O journal é gravado mesmo que o banco falhe (e vice-versa):
+                // ele é o que permite retomar a mão sem depender do banco
+                let results = match &job.write {
+                    Write::Room(snapshot) => {
+                        let mut results = vec![(
+                            format!("a sala {} no banco", snapshot.id),
+                            job.store.save_room(snapshot),
+                        )];
+                        if let Some(journal) = &job.journal {
+                            results.push((
+                                format!("a sala {} no journal", snapshot.id),
+                                journal.append(snapshot),
+                            ));
+                        }
+                        results
+                    }
+                    Write::Hand(room_id, hand) => vec![(
+                        format!("a mão {}", hand.hand_id),
+                        job.store.save_hand(room_id, hand),
+                    )],
+                };
+
+                let mut health = status.lock().unwrap();
+                let mut failed = false;
+                for (target, result) in results {
+                    if let Err(error) = result {
+                        eprintln!("Falha ao salvar {}: {}", target, error);
+                        health.consecutive_failures += 1;
+                        health.last_error = Some(error.to_string());
+                        failed = true;
+                    }
+                }
+                if !failed {
+                    health.consecutive_failures = 0;
+                }
+                drop(health);
+                let _ = job.done.send(());
+            }
+        });
+
+        Self { jobs, health }
+    }
+
+    fn send(&self, write: Write, state: &AppState) -> PendingWrite {
+        let (done, written) = oneshot::channel();
+        let job = Job {
+            write,
+            store: state.store.clone(),
+            journal: state.journal.clone(),
+            done,
+        };
+        // Se a thread parou o `PendingWrite` resolve na hora
+        if self.jobs.send(job).is_err() {
+            let mut health = self.health.lock().unwrap();
+            health.consecutive_failures += 1;
+            health.last_error = Some("a thread de gravação parou".to_string());
+        }
+        PendingWrite(written)
+    }
+}
+
+/// Rotas da API sobre o estado dado, com tradução de erros, formato das
+/// cartas por cliente e CORS.
+pub fn app(state: AppState) -> Router {
+    Router::new()
+        .route("/health", get(handlers::health_check))
+        .route("/room", post(handlers::create_room))
+        .route("/room/:room_id/join", post(handlers::join_room))
+        .route("/room/:room_id/ws", get(websocket::websocket_handler))
+        .route("/room/:room_id/events", get(sse::events_handler))
+        .route("/room/:room_id/action", post(handlers::submit_action))
+        .route("/room/:room_id/run-it", post(handlers::run_it))
+        .route("/room/:room_id/show", post(handlers::show_hand))
+        .route("/room/:room_id/auto-muck", post(handlers::auto_muck))
+        .route("/room/:room_id/rabbit-hunt", post(handlers::rabbit_hunt))
+        .route("/room/:room_id/start", post(handlers::start_game))
+        .route("/room/:room_id/result", get(handlers::get_hand_result))
+        .route("/room/:room_id/next", post(handlers::next_hand))
+        .route(
+            "/room/:room_id/client-seed",
+            post(handlers::set_client_seed),
+        )
+        .route("/room/:room_id/hands", get(handlers::list_hands))
+        .route("/room/:room_id/session", get(handlers::session_results))
+        .route(
+            "/room/:room_id/hands/pokerstars",
+            get(handlers::export_pokerstars),
+        )
+        .route("/hands/import", post(handlers::import_ohh))
+        .route("/hands/:hand_id", get(handlers::get_hand))
+        .route("/hands/:hand_id/ohh", get(handlers::export_ohh))
+        .route("/hands/:hand_id/replay", get(handlers::replay_hand))
+        .route("/hands/:hand_id/verify", get(handlers::verify_hand))
+        .route("/tools/equity", post(handlers::calculate_equity))
+        .with_state(state)
+        .layer(middleware::from_fn(i18n::localize_errors))
+        .layer(middleware::from_fn(i18n::localize_messages))
+        .layer(middleware::from_fn(notation::compact_cards))
+        .layer(CorsLayer::permissive())
+}
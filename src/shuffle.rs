@@ -0,0 +1,161 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::GameError;
+use crate::models::*;
+
+/// De onde vem a ordem do deck de cada mão.
+///
+/// Em produção o deck é embaralhado com um ChaCha20 semeado pela entropia do
+/// sistema. `Seeded` e `Stacked` existem para testes e cenários reproduzíveis e
+/// só podem ser escolhidos quando o servidor roda em modo de teste.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum DeckSource {
+    #[default]
+    Random,
+    /// Seed fixa: a mão `n` usa o stream `n` do ChaCha20 semeado com `seed`
+    Seeded { seed: u64 },
+    /// Cartas na ordem em que são distribuídas (hole cards, flop, turn, river),
+    /// sem contar as queimadas. O restante do deck é embaralhado.
+    Stacked { cards: Vec<Card> },
+}
+
+impl DeckSource {
+    /// Deck pronto para a mão `hand_number`, na ordem usada pelo motor (as
+    /// cartas saem do fim).
+    pub fn deck_for_hand(&self, hand_number: u64, player_count: usize) -> Vec<Card> {
+        match self {
+            DeckSource::Random => shuffled_deck(&mut ChaCha20Rng::from_entropy()),
+            DeckSource::Seeded { seed } => shuffled_deck(&mut seeded_rng(*seed, hand_number)),
+            DeckSource::Stacked { cards } => stacked_deck(cards, player_count),
+        }
+    }
+
+    /// Monta um deck empilhado a partir da notação curta (`Ah`, `Td`),
+    /// rejeitando cartas inválidas ou repetidas.
+    pub fn stacked(notation: &[String]) -> Result<DeckSource, GameError> {
+        let mut cards: Vec<Card> = Vec::with_capacity(notation.len());
+        for card in notation {
+            let card = Card::from_notation(card).ok_or(GameError::InvalidDeck)?;
+            if cards.contains(&card) {
+                return Err(GameError::InvalidDeck);
+            }
+            cards.push(card);
+        }
+        Ok(DeckSource::Stacked { cards })
+    }
+}
+
+/// RNG determinístico de uma mão em uma sala com seed fixa.
+pub fn seeded_rng(seed: u64, hand_number: u64) -> ChaCha20Rng {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    rng.set_stream(hand_number);
+    rng
+}
+
+/// Embaralha um deck novo com o RNG dado.
+pub fn shuffled_deck<R: Rng + ?Sized>(rng: &mut R) -> Vec<Card> {
+    let mut deck = Game::create_deck();
+    deck.shuffle(rng);
+    deck
+}
+
+fn stacked_deck(stacked: &[Card], player_count: usize) -> Vec<Card> {
+    let mut rest: Vec<Card> = shuffled_deck(&mut ChaCha20Rng::from_entropy())
+        .into_iter()
+        .filter(|card| !stacked.contains(card))
+        .collect();
+    let mut stacked = stacked.iter().cloned();
+    let mut next = |rest: &mut Vec<Card>| stacked.next().or_else(|| rest.pop());
+
+    let mut order = Vec::with_capacity(52);
+    for _ in 0..player_count * 2 {
+        order.extend(next(&mut rest));
+    }
+    // Flop, turn e river, cada um precedido de uma carta queimada
+    for street in [3, 1, 1] {
+        order.extend(rest.pop());
+        for _ in 0..street {
+            order.extend(next(&mut rest));
+        }
+    }
+    order.extend(stacked);
+    order.extend(rest);
+
+    order.reverse();
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::parse_cards;
+
+    fn cards(notation: &str) -> Vec<Card> {
+        parse_cards(notation).unwrap()
+    }
+
+    #[test]
+    fn test_seeded_decks_are_reproducible_per_hand() {
+        let source = DeckSource::Seeded { seed: 42 };
+        assert_eq!(source.deck_for_hand(1, 2), source.deck_for_hand(1, 2));
+        assert_ne!(source.deck_for_hand(1, 2), source.deck_for_hand(2, 2));
+        assert_ne!(
+            source.deck_for_hand(1, 2),
+            DeckSource::Seeded { seed: 43 }.deck_for_hand(1, 2)
+        );
+    }
+
+    #[test]
+    fn test_random_decks_are_complete() {
+        let deck = DeckSource::Random.deck_for_hand(1, 2);
+        assert_eq!(deck.len(), 52);
+        for card in Game::create_deck() {
+            assert!(deck.contains(&card));
+        }
+    }
+
+    #[test]
+    fn test_stacked_deck_deals_in_order() {
+        let stacked = "Ah Kh Ad Kd 2c 7s 9h Js Qc";
+        let source = DeckSource::stacked(
+            &stacked
+                .split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        let mut deck = source.deck_for_hand(1, 2);
+        assert_eq!(deck.len(), 52);
+        let mut deal = || deck.pop().unwrap();
+
+        // Hole cards: uma carta por jogador, duas voltas
+        let hole: Vec<_> = (0..4).map(|_| deal()).collect();
+        assert_eq!(hole, cards("Ah Kh Ad Kd"));
+        deal(); // queimada
+        let flop: Vec<_> = (0..3).map(|_| deal()).collect();
+        assert_eq!(flop, cards("2c 7s 9h"));
+        deal();
+        assert_eq!(deal(), cards("Js")[0]);
+        deal();
+        assert_eq!(deal(), cards("Qc")[0]);
+    }
+
+    #[test]
+    fn test_stacked_deck_rejects_invalid_cards() {
+        let invalid = ["Ah".to_string(), "Xx".to_string()];
+        assert_eq!(
+            DeckSource::stacked(&invalid).unwrap_err(),
+            GameError::InvalidDeck
+        );
+        let repeated = ["Ah".to_string(), "Ah".to_string()];
+        assert_eq!(
+            DeckSource::stacked(&repeated).unwrap_err(),
+            GameError::InvalidDeck
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::stream::{self, Stream};
+use std::convert::Infallible;
+use tokio::sync::mpsc;
+
+use crate::{
+    auth::{bearer_token, verify_player},
+    error::GameError,
+    i18n::{self, Locale},
+    models::*,
+    websocket::{forfeit_run_it, room_state_message},
+    AppState,
+};
+
+/// Stream de eventos (SSE) com as mesmas mensagens enviadas pelo WebSocket.
+/// Cada mensagem vira um evento cujo nome é o campo `type` e cujo `data` é a
+/// mensagem JSON completa. As ações são enviadas via `POST /room/:room_id/action`.
+/// Exige o token do jogador, em `?player_token=` ou no header `Authorization`.
+pub async fn events_handler(
+    Path(room_id): Path<String>,
+    Query(params): Query<EventStreamParams>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    locale: Locale,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, GameError> {
+    let (tx, rx) = mpsc::unbounded_channel::<serde_json::Value>();
+
+    {
+        let mut room = state
+            .rooms
+            .get_mut(&room_id)
+            .ok_or(GameError::RoomNotFound)?;
+        if !room.players.contains_key(&params.player_id) {
+            return Err(GameError::PlayerNotInRoom);
+        }
+        let token = params.player_token.as_deref().or(bearer_token(&headers));
+        verify_player(&room, &params.player_id, token)?;
+
+        room.websocket_senders
+            .insert(params.player_id.clone(), tx.clone());
+
+        // Enviar estado atual da sala
+        let _ = tx.send(room_state_message(&room, &params.player_id));
+    }
+
+    let cards = params.cards;
+    let locale = params.locale.unwrap_or(locale);
+    let guard = SenderGuard {
+        state,
+        room_id,
+        player_id: params.player_id,
+        tx: tx.downgrade(),
+    };
+
+    let stream = stream::unfold((rx, guard), move |(mut rx, guard)| async move {
+        let mut message = rx.recv().await?;
+        cards.apply(&mut message);
+        i18n::localize(locale, &mut message);
+        Some((Ok(to_event(&message)), (rx, guard)))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn to_event(message: &serde_json::Value) -> Event {
+    let event = Event::default().data(message.to_string());
+    match message.get("type").and_then(|t| t.as_str()) {
+        Some(event_type) => event.event(event_type),
+        None => event,
+    }
+}
+
+/// Remove o sender da sala quando o cliente SSE desconecta. Guarda só uma
+/// referência fraca ao canal: quando uma reconexão troca o sender registrado,
+/// a sala solta o antigo e este stream termina.
+struct SenderGuard {
+    state: AppState,
+    room_id: String,
+    player_id: String,
+    tx: mpsc::WeakUnboundedSender<serde_json::Value>,
+}
+
+impl Drop for SenderGuard {
+    fn drop(&mut self) {
+        if let Some(mut room) = self.state.rooms.get_mut(&self.room_id) {
+            // Só remove se o canal registrado ainda for o desta conexão
+            if room
+                .websocket_senders
+                .get(&self.player_id)
+                .is_some_and(|sender| self.tx.upgrade().is_some_and(|tx| sender.same_channel(&tx)))
+            {
+                room.websocket_senders.remove(&self.player_id);
+                // Desconectar no meio da votação de quantas vezes rodar conta
+                // como voto em 1
+                let (state, room_id, player_id) = (
+                    self.state.clone(),
+                    self.room_id.clone(),
+                    self.player_id.clone(),
+                );
+                drop(room);
+                tokio::spawn(async move {
+                    forfeit_run_it(&state, &room_id, &player_id).await;
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,692 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::auth;
+use crate::fairness::FairnessState;
+use crate::history::HandHistory;
+use crate::models::*;
+use crate::shuffle::DeckSource;
+
+/// Estado persistível de uma sala (tudo menos as conexões abertas).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomSnapshot {
+    pub id: String,
+    pub creator_id: String,
+    pub players: HashMap<String, Player>,
+    pub game: Option<Game>,
+    pub max_players: usize,
+    pub player_tokens: HashMap<String, String>,
+    #[serde(default)]
+    pub deck_source: DeckSource,
+    #[serde(default)]
+    pub fairness: FairnessState,
+    #[serde(default)]
+    pub rules: RoomRules,
+}
+
+impl Room {
+    pub fn snapshot(&self) -> RoomSnapshot {
+        RoomSnapshot {
+            id: self.id.clone(),
+            creator_id: self.creator_id.clone(),
+            players: self.players.clone(),
+            game: self.game.clone(),
+            max_players: self.max_players,
+            player_tokens: self.player_tokens.clone(),
+            deck_source: self.deck_source.clone(),
+            fairness: self.fairness.clone(),
+            rules: self.rules.clone(),
+        }
+    }
+
+    pub fn from_snapshot(snapshot: RoomSnapshot) -> Self {
+        Room {
+            id: snapshot.id,
+            creator_id: snapshot.creator_id,
+            players: snapshot.players,
+            game: snapshot.game,
+            max_players: snapshot.max_players,
+            websocket_senders: HashMap::new(),
+            player_tokens: snapshot
+                .player_tokens
+                .into_iter()
+                .map(|(player_id, token)| (player_id, auth::stored_token_hash(token)))
+                .collect(),
+            deck_source: snapshot.deck_source,
+            fairness: snapshot.fairness,
+            rules: snapshot.rules,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    Database(rusqlite::Error),
+    Serialization(serde_json::Error),
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Database(e) => write!(f, "erro no banco de dados: {}", e),
+            StorageError::Serialization(e) => write!(f, "erro de serialização: {}", e),
+            StorageError::Io(e) => write!(f, "erro de I/O: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(error: rusqlite::Error) -> Self {
+        StorageError::Database(error)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(error: serde_json::Error) -> Self {
+        StorageError::Serialization(error)
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(error: std::io::Error) -> Self {
+        StorageError::Io(error)
+    }
+}
+
+/// Armazenamento das salas. As implementações precisam ser seguras para uso
+/// concorrente, já que são chamadas de dentro dos handlers.
+pub trait RoomStore: Send + Sync {
+    fn save_room(&self, room: &RoomSnapshot) -> Result<(), StorageError>;
+    fn delete_room(&self, room_id: &str) -> Result<(), StorageError>;
+    fn load_rooms(&self) -> Result<Vec<RoomSnapshot>, StorageError>;
+    fn save_hand(&self, room_id: &str, hand: &HandHistory) -> Result<(), StorageError>;
+    /// Mãos da sala em ordem cronológica.
+    fn load_hands(&self, room_id: &str) -> Result<Vec<HandHistory>, StorageError>;
+    fn count_hands(&self, room_id: &str) -> Result<usize, StorageError> {
+        Ok(self.load_hands(room_id)?.len())
+    }
+    fn load_hand(&self, hand_id: &str) -> Result<Option<HandHistory>, StorageError>;
+}
+
+/// Armazenamento em memória, usado nos testes e com `DATABASE_PATH=:memory:`.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    rooms: Mutex<HashMap<String, RoomSnapshot>>,
+    hands: Mutex<Vec<(String, HandHistory)>>, // (room_id, mão)
+}
+
+impl RoomStore for MemoryStore {
+    fn save_room(&self, room: &RoomSnapshot) -> Result<(), StorageError> {
+        self.rooms
+            .lock()
+            .unwrap()
+            .insert(room.id.clone(), room.clone());
+        Ok(())
+    }
+
+    fn delete_room(&self, room_id: &str) -> Result<(), StorageError> {
+        self.rooms.lock().unwrap().remove(room_id);
+        Ok(())
+    }
+
+    fn load_rooms(&self) -> Result<Vec<RoomSnapshot>, StorageError> {
+        Ok(self.rooms.lock().unwrap().values().cloned().collect())
+    }
+
+    fn save_hand(&self, room_id: &str, hand: &HandHistory) -> Result<(), StorageError> {
+        let mut hands = self.hands.lock().unwrap();
+        hands.retain(|(_, stored)| stored.hand_id != hand.hand_id);
+        hands.push((room_id.to_string(), hand.clone()));
+        Ok(())
+    }
+
+    fn load_hands(&self, room_id: &str) -> Result<Vec<HandHistory>, StorageError> {
+        Ok(self
+            .hands
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(stored_room_id, _)| stored_room_id == room_id)
+            .map(|(_, hand)| hand.clone())
+            .collect())
+    }
+
+    fn load_hand(&self, hand_id: &str) -> Result<Option<HandHistory>, StorageError> {
+        Ok(self
+            .hands
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, hand)| hand.hand_id == hand_id)
+            .map(|(_, hand)| hand.clone()))
+    }
+}
+
+/// Armazenamento em SQLite. Salas e assentos (com o saldo de fichas) ficam em
+/// tabelas próprias; a mão em andamento é guardada como o `Game` em JSON.
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, StorageError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, StorageError> {
+        connection.execute_batch(
+            "PRAGMA foreign_keys = ON;
+             CREATE TABLE IF NOT EXISTS rooms (
+                 id TEXT PRIMARY KEY,
+                 creator_id TEXT NOT NULL,
+                 max_players INTEGER NOT NULL,
+                 game TEXT,
+                 updated_at TEXT NOT NULL,
+                 deck_source TEXT,
+                 fairness TEXT,
+                 rules TEXT
+             );
+             CREATE TABLE IF NOT EXISTS seats (
+                 room_id TEXT NOT NULL REFERENCES rooms(id) ON DELETE CASCADE,
+                 player_id TEXT NOT NULL,
+                 name TEXT NOT NULL,
+                 chips INTEGER NOT NULL,
+                 token TEXT NOT NULL,
+                 PRIMARY KEY (room_id, player_id)
+             );
+             CREATE TABLE IF NOT EXISTS hands (
+                 hand_id TEXT PRIMARY KEY,
+                 room_id TEXT NOT NULL,
+                 hand_number INTEGER NOT NULL,
+                 finished_at TEXT,
+                 data TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS hands_room ON hands (room_id, hand_number);",
+        )?;
+
+        // Colunas adicionadas depois da criação da tabela em bancos antigos
+        for column in ["deck_source", "fairness", "rules"] {
+            if connection
+                .prepare(&format!("SELECT {} FROM rooms LIMIT 0", column))
+                .is_err()
+            {
+                connection.execute(&format!("ALTER TABLE rooms ADD COLUMN {} TEXT", column), [])?;
+            }
+        }
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+impl RoomStore for SqliteStore {
+    fn save_room(&self, room: &RoomSnapshot) -> Result<(), StorageError> {
+        let game = room.game.as_ref().map(serde_json::to_string).transpose()?;
+        let deck_source = serde_json::to_string(&room.deck_source)?;
+        let fairness = serde_json::to_string(&room.fairness)?;
+        let rules = serde_json::to_string(&room.rules)?;
+
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO rooms (id, creator_id, max_players, game, updated_at, deck_source, fairness, rules)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(id) DO UPDATE SET
+                 max_players = excluded.max_players,
+                 game = excluded.game,
+                 updated_at = excluded.updated_at,
+                 deck_source = excluded.deck_source,
+                 fairness = excluded.fairness,
+                 rules = excluded.rules",
+            params![
+                room.id,
+                room.creator_id,
+                room.max_players as i64,
+                game,
+                chrono::Utc::now().to_rfc3339(),
+                deck_source,
+                fairness,
+                rules
+            ],
+        )?;
+
+        transaction.execute("DELETE FROM seats WHERE room_id = ?1", params![room.id])?;
+        for player in room.players.values() {
+            // Durante o jogo o saldo atualizado está nos jogadores do `Game`
+            let chips = room
+                .game
+                .as_ref()
+                .and_then(|game| game.players.iter().find(|p| p.id == player.id))
+                .map_or(player.chips, |p| p.chips);
+            let token = room
+                .player_tokens
+                .get(&player.id)
+                .cloned()
+                .unwrap_or_default();
+
+            transaction.execute(
+                "INSERT INTO seats (room_id, player_id, name, chips, token)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![room.id, player.id, player.name, chips, token],
+            )?;
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn delete_room(&self, room_id: &str) -> Result<(), StorageError> {
+        let connection = self.connection.lock().unwrap();
+        connection.execute("DELETE FROM rooms WHERE id = ?1", params![room_id])?;
+        Ok(())
+    }
+
+    fn load_rooms(&self) -> Result<Vec<RoomSnapshot>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+
+        let mut rooms_statement = connection.prepare(
+            "SELECT id, creator_id, max_players, game, deck_source, fairness, rules FROM rooms",
+        )?;
+        let rows = rooms_statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+            ))
+        })?;
+
+        let mut seats_statement = connection
+            .prepare("SELECT player_id, name, chips, token FROM seats WHERE room_id = ?1")?;
+
+        let mut rooms = Vec::new();
+        for row in rows {
+            let (id, creator_id, max_players, game, deck_source, fairness, rules) = row?;
+            let game: Option<Game> = game.as_deref().map(serde_json::from_str).transpose()?;
+            let deck_source: Option<DeckSource> = deck_source
+                .as_deref()
+                .map(serde_json::from_str)
+                .transpose()?;
+            let fairness: Option<FairnessState> =
+                fairness.as_deref().map(serde_json::from_str).transpose()?;
+            let rules: Option<RoomRules> =
+                rules.as_deref().map(serde_json::from_str).transpose()?;
+
+            let mut players = HashMap::new();
+            let mut player_tokens = HashMap::new();
+            let seats = seats_statement.query_map(params![id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, u32>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?;
+            for seat in seats {
+                let (player_id, name, chips, token) = seat?;
+                player_tokens.insert(player_id.clone(), token);
+                players.insert(
+                    player_id.clone(),
+                    Player {
+                        id: player_id,
+                        name,
+                        chips,
+                        hand: Vec::new(),
+                        current_bet: 0,
+                        is_folded: false,
+                        is_all_in: false,
+                    },
+                );
+            }
+
+            rooms.push(RoomSnapshot {
+                id,
+                creator_id,
+                players,
+                game,
+                max_players: max_players as usize,
+                player_tokens,
+                deck_source: deck_source.unwrap_or_default(),
+                fairness: fairness.unwrap_or_default(),
+                rules: rules.unwrap_or_default(),
+            });
+        }
+
+        Ok(rooms)
+    }
+
+    fn save_hand(&self, room_id: &str, hand: &HandHistory) -> Result<(), StorageError> {
+        let data = serde_json::to_string(hand)?;
+        let connection = self.connection.lock().unwrap();
+        connection.execute(
+            "INSERT OR REPLACE INTO hands (hand_id, room_id, hand_number, finished_at, data)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                hand.hand_id,
+                room_id,
+                hand.hand_number as i64,
+                hand.finished_at,
+                data
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn load_hands(&self, room_id: &str) -> Result<Vec<HandHistory>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement =
+            connection.prepare("SELECT data FROM hands WHERE room_id = ?1 ORDER BY rowid")?;
+        let rows = statement.query_map(params![room_id], |row| row.get::<_, String>(0))?;
+
+        let mut hands = Vec::new();
+        for data in rows {
+            hands.push(serde_json::from_str(&data?)?);
+        }
+        Ok(hands)
+    }
+
+    fn count_hands(&self, room_id: &str) -> Result<usize, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        let count = connection.query_row(
+            "SELECT COUNT(*) FROM hands WHERE room_id = ?1",
+            params![room_id],
+            |row| row.get::<_, i64>(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    fn load_hand(&self, hand_id: &str) -> Result<Option<HandHistory>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        let data = connection
+            .query_row(
+                "SELECT data FROM hands WHERE hand_id = ?1",
+                params![hand_id],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+
+        Ok(data.as_deref().map(serde_json::from_str).transpose()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_room() -> Room {
+        let players: Vec<Player> = ["Alice", "Bob"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Player {
+                id: format!("player{}", i + 1),
+                name: name.to_string(),
+                chips: 1000,
+                hand: Vec::new(),
+                current_bet: 0,
+                is_folded: false,
+                is_all_in: false,
+            })
+            .collect();
+
+        let mut game = Game::new(players.clone());
+        game.start_round().unwrap();
+
+        Room {
+            id: "sala1".to_string(),
+            creator_id: "player1".to_string(),
+            players: players.iter().map(|p| (p.id.clone(), p.clone())).collect(),
+            game: Some(game),
+            max_players: 6,
+            websocket_senders: HashMap::new(),
+            player_tokens: players
+                .iter()
+                .map(|p| (p.id.clone(), auth::hash_token(&format!("token-{}", p.id))))
+                .collect(),
+            deck_source: DeckSource::Seeded { seed: 7 },
+            fairness: FairnessState::default(),
+            rules: RoomRules { max_runs: 2 },
+        }
+    }
+
+    fn assert_round_trip(store: &dyn RoomStore) {
+        let room = test_room();
+        store.save_room(&room.snapshot()).unwrap();
+
+        let rooms = store.load_rooms().unwrap();
+        assert_eq!(rooms.len(), 1);
+        let restored = Room::from_snapshot(rooms[0].clone());
+
+        assert_eq!(restored.id, room.id);
+        assert_eq!(restored.creator_id, room.creator_id);
+        assert_eq!(restored.max_players, 6);
+        assert_eq!(restored.players.len(), 2);
+        assert_eq!(
+            restored.player_tokens["player2"],
+            auth::hash_token("token-player2")
+        );
+        assert_eq!(restored.deck_source, DeckSource::Seeded { seed: 7 });
+        assert_eq!(restored.rules.max_runs, 2);
+        assert_eq!(
+            restored.fairness.next_commitment(),
+            room.fairness.next_commitment()
+        );
+
+        let game = restored.game.unwrap();
+        let original = room.game.unwrap();
+        assert_eq!(game.id, original.id);
+        assert_eq!(game.pot, original.pot);
+        assert_eq!(game.deck.len(), original.deck.len());
+        assert_eq!(game.deck.reveal().unwrap(), original.deck.reveal().unwrap());
+        assert_eq!(game.current_player_index, original.current_player_index);
+        assert_eq!(game.round_bets, original.round_bets);
+        assert_eq!(game.players[0].hand.len(), 2);
+
+        store.delete_room(&room.id).unwrap();
+        assert!(store.load_rooms().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_memory_store_round_trip() {
+        assert_round_trip(&MemoryStore::default());
+    }
+
+    #[test]
+    fn test_sqlite_store_round_trip() {
+        assert_round_trip(&SqliteStore::open_in_memory().unwrap());
+    }
+
+    #[test]
+    fn test_sqlite_seats_keep_live_chip_balances() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let room = test_room();
+        store.save_room(&room.snapshot()).unwrap();
+
+        let game = room.game.as_ref().unwrap();
+        let restored = &store.load_rooms().unwrap()[0];
+        for player in &game.players {
+            // Fichas já descontadas pelos blinds
+            assert_eq!(restored.players[&player.id].chips, player.chips);
+        }
+    }
+
+    #[test]
+    fn test_sqlite_store_survives_reopen() {
+        let path = std::env::temp_dir().join(format!("poker-{}.db", uuid::Uuid::new_v4()));
+
+        {
+            let store = SqliteStore::open(&path).unwrap();
+            store.save_room(&test_room().snapshot()).unwrap();
+        }
+
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.load_rooms().unwrap().len(), 1);
+
+        drop(store);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    /// Falha se o texto contém alguma carta ainda não distribuída ou uma seed
+    /// do commit-reveal, que permitiria recalcular o deck.
+    fn assert_no_future_cards(text: &str, room: &Room) {
+        let game = room.game.as_ref().unwrap();
+        let mut undealt = game.deck.reveal().unwrap();
+        undealt.truncate(game.deck.len());
+        assert_eq!(undealt.len(), 48);
+        for card in undealt {
+            assert!(!text.contains(&serde_json::to_string(&card).unwrap()));
+        }
+
+        let current = game.fairness.current.as_ref().unwrap();
+        assert!(!text.contains(&current.server_seed));
+        assert!(!text.contains(&game.fairness.next_server_seed));
+        assert!(!text.contains(&room.fairness.next_server_seed));
+    }
+
+    #[test]
+    fn test_snapshot_does_not_reveal_undealt_cards() {
+        let room = test_room();
+        let json = serde_json::to_string(&room.snapshot()).unwrap();
+        assert_no_future_cards(&json, &room);
+    }
+
+    #[test]
+    fn test_sqlite_dump_does_not_reveal_undealt_cards() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let room = test_room();
+        store.save_room(&room.snapshot()).unwrap();
+
+        let (game, fairness): (String, String) = store
+            .connection
+            .lock()
+            .unwrap()
+            .query_row("SELECT game, fairness FROM rooms", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_no_future_cards(&game, &room);
+        assert_no_future_cards(&fairness, &room);
+
+        // O jogo restaurado continua distribuindo as mesmas cartas
+        let mut restored = Room::from_snapshot(store.load_rooms().unwrap().remove(0));
+        let mut original = room;
+        let restored_deck = &mut restored.game.as_mut().unwrap().deck;
+        let original_deck = &mut original.game.as_mut().unwrap().deck;
+        assert_eq!(restored_deck.pop(), original_deck.pop());
+    }
+
+    /// Troca cada bloco selado do JSON por um selado com outra chave mestra.
+    fn reseal_with_other_key(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) if map.contains_key("ciphertext") => {
+                *value = crate::sealed::sealed_with_other_key();
+            }
+            serde_json::Value::Object(map) => map.values_mut().for_each(reseal_with_other_key),
+            serde_json::Value::Array(items) => items.iter_mut().for_each(reseal_with_other_key),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_sqlite_restores_rooms_sealed_with_another_key() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let room = test_room();
+        store.save_room(&room.snapshot()).unwrap();
+
+        {
+            let connection = store.connection.lock().unwrap();
+            let (game, fairness): (String, String) = connection
+                .query_row("SELECT game, fairness FROM rooms", [], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .unwrap();
+            let mut game: serde_json::Value = serde_json::from_str(&game).unwrap();
+            let mut fairness: serde_json::Value = serde_json::from_str(&fairness).unwrap();
+            reseal_with_other_key(&mut game);
+            reseal_with_other_key(&mut fairness);
+            connection
+                .execute(
+                    "UPDATE rooms SET game = ?1, fairness = ?2",
+                    params![game.to_string(), fairness.to_string()],
+                )
+                .unwrap();
+        }
+
+        // As seeds que não abrem são trocadas e a mão em andamento é anulada
+        let mut restored = Room::from_snapshot(store.load_rooms().unwrap().remove(0));
+        assert_ne!(
+            restored.fairness.next_commitment(),
+            room.fairness.next_commitment()
+        );
+        let game = restored.game.as_mut().unwrap();
+        assert!(game.fairness.proof_unavailable);
+        assert_eq!(
+            game.void_hand_if_deck_unavailable(),
+            Some(crate::error::GameError::DeckUnavailable)
+        );
+        assert_eq!(game.state, GameState::Finished);
+        assert!(game.players.iter().all(|p| p.chips == 1000));
+        assert!(game.next_hand().is_ok());
+    }
+
+    fn finished_hand(room: &Room) -> HandHistory {
+        let mut game = room.game.clone().unwrap();
+        // Todos dão call/check até o showdown
+        while game.state != GameState::Finished {
+            let player_id = game.players[game.current_player_index].id.clone();
+            let action = if game.current_bet > game.players[game.current_player_index].current_bet {
+                PlayerAction::Call
+            } else {
+                PlayerAction::Check
+            };
+            game.process_action(&player_id, action).unwrap();
+        }
+        game.hand_history.unwrap()
+    }
+
+    fn assert_hands_round_trip(store: &dyn RoomStore) {
+        let room = test_room();
+        let hand = finished_hand(&room);
+        store.save_hand(&room.id, &hand).unwrap();
+        let mut other = finished_hand(&room);
+        other.hand_id = "mao-de-outra-sala".to_string();
+        store.save_hand("outra-sala", &other).unwrap();
+
+        let hands = store.load_hands(&room.id).unwrap();
+        assert_eq!(hands.len(), 1);
+        assert_eq!(hands[0].hand_id, hand.hand_id);
+        assert_eq!(hands[0].actions.len(), hand.actions.len());
+
+        let loaded = store.load_hand(&hand.hand_id).unwrap().unwrap();
+        assert_eq!(loaded.winnings.len(), hand.winnings.len());
+        assert!(store.load_hand("inexistente").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_memory_store_hands() {
+        assert_hands_round_trip(&MemoryStore::default());
+    }
+
+    #[test]
+    fn test_sqlite_store_hands() {
+        assert_hands_round_trip(&SqliteStore::open_in_memory().unwrap());
+    }
+}
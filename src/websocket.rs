@@ -1,108 +1,110 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        Path, State,
+        Path, Query, State,
     },
     response::Response,
 };
 use futures_util::{sink::SinkExt, stream::StreamExt};
-use tokio::sync::mpsc;
+use std::{collections::HashMap, future::Future, pin::Pin};
+use tokio::sync::{mpsc, watch};
 
-use crate::{models::*, AppState};
+use crate::{
+    auth::verify_player,
+    error::GameError,
+    i18n::{self, Locale},
+    models::*,
+    notation::CardFormat,
+    AppState,
+};
 
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     Path(room_id): Path<String>,
+    Query(params): Query<WebSocketParams>,
     State(state): State<AppState>,
+    locale: Locale,
 ) -> Response {
-    ws.on_upgrade(move |socket| handle_socket(socket, room_id, state))
+    let encoding = params.encoding.unwrap_or_default();
+    let cards = params.cards;
+    ws.on_upgrade(move |socket| handle_socket(socket, room_id, state, encoding, cards, locale))
 }
 
-async fn handle_socket(socket: WebSocket, room_id: String, state: AppState) {
+impl WireEncoding {
+    /// Serializa uma mensagem do servidor no formato negociado para a conexão.
+    pub fn encode(self, message: &serde_json::Value) -> Option<Message> {
+        match self {
+            WireEncoding::Json => Some(Message::Text(message.to_string())),
+            WireEncoding::Msgpack => rmp_serde::to_vec_named(message).ok().map(Message::Binary),
+        }
+    }
+
+    /// Decodifica uma mensagem do cliente. Frames de texto são sempre aceitos como
+    /// JSON; frames binários só são aceitos em conexões MessagePack.
+    pub fn decode(self, message: &Message) -> Option<WebSocketMessage> {
+        match (self, message) {
+            (_, Message::Text(text)) => serde_json::from_str(text).ok(),
+            (WireEncoding::Msgpack, Message::Binary(bytes)) => rmp_serde::from_slice(bytes).ok(),
+            _ => None,
+        }
+    }
+}
+
+async fn handle_socket(
+    socket: WebSocket,
+    room_id: String,
+    state: AppState,
+    encoding: WireEncoding,
+    cards: CardFormat,
+    locale: Locale,
+) {
     let (mut sender, mut receiver) = socket.split();
-    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (tx, mut rx) = mpsc::unbounded_channel::<serde_json::Value>();
+    // Começa no Accept-Language do handshake; o `join` pode trocar
+    let (locale_tx, locale_rx) = watch::channel(locale);
 
     // Enviar mensagens do canal para o WebSocket
     let send_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            if sender.send(Message::Text(msg)).await.is_err() {
+        while let Some(mut msg) = rx.recv().await {
+            cards.apply(&mut msg);
+            i18n::localize(*locale_rx.borrow(), &mut msg);
+            let Some(frame) = encoding.encode(&msg) else {
+                continue;
+            };
+            if sender.send(frame).await.is_err() {
                 break;
             }
         }
     });
 
     // Processar mensagens recebidas do WebSocket
-    let room_id_clone = room_id.clone();
-    let state_clone = state.clone();
-    let tx_clone = tx.clone();
-
     let recv_task = tokio::spawn(async move {
-        let mut player_id: Option<String> = None;
+        let mut session = Session::new(tx, locale_tx);
 
         while let Some(msg) = receiver.next().await {
-            if let Ok(msg) = msg {
-                if let Message::Text(text) = msg {
-                    if let Ok(ws_message) = serde_json::from_str::<WebSocketMessage>(&text) {
-                        match ws_message.message_type.as_str() {
-                            "join" => {
-                                if let Ok(join_data) =
-                                    serde_json::from_value::<serde_json::Value>(ws_message.data)
-                                {
-                                    if let Some(pid) =
-                                        join_data.get("player_id").and_then(|v| v.as_str())
-                                    {
-                                        player_id = Some(pid.to_string());
-
-                                        // Adicionar sender do WebSocket à sala
-                                        if let Some(mut room) =
-                                            state_clone.rooms.get_mut(&room_id_clone)
-                                        {
-                                            room.websocket_senders
-                                                .insert(pid.to_string(), tx_clone.clone());
-
-                                            // Enviar estado atual da sala
-                                            let room_state = serde_json::json!({
-                                                "type": "room_state",
-                                                "data": {
-                                                    "room_id": room.id,
-                                                    "players": room.players.values().collect::<Vec<_>>(),
-                                                    "game": room.game.as_ref().map(|g| g.get_game_state())
-                                                }
-                                            });
-
-                                            let _ = tx_clone.send(room_state.to_string());
-                                        }
-                                    }
-                                }
-                            }
-                            "game_action" => {
-                                if let Some(ref pid) = player_id {
-                                    if let Ok(action_data) =
-                                        serde_json::from_value::<GameActionMessage>(ws_message.data)
-                                    {
-                                        handle_game_action(
-                                            &state_clone,
-                                            &room_id_clone,
-                                            pid,
-                                            action_data.action,
-                                        )
-                                        .await;
-                                    }
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            } else {
+            let Ok(msg) = msg else {
                 break;
+            };
+            let Some(ws_message) = encoding.decode(&msg) else {
+                continue;
+            };
+            if let Err(error) =
+                handle_client_message(&state, &room_id, &mut session, ws_message).await
+            {
+                // Enviar erro para o jogador específico
+                let error_message = serde_json::json!({
+                    "type": "error",
+                    "data": error.to_json(*session.locale.borrow())
+                });
+                let _ = session.tx.send(error_message);
             }
         }
 
-        // Remove o jogador quando desconectar
-        if let Some(pid) = player_id {
-            if let Some(mut room) = state_clone.rooms.get_mut(&room_id_clone) {
-                room.websocket_senders.remove(&pid);
+        // Remove o jogador quando desconectar; se ele estava numa votação de
+        // quantas vezes rodar sem votar, a saída conta como 1
+        if session.leave(&state, &room_id) {
+            if let Some(pid) = &session.player_id {
+                forfeit_run_it(&state, &room_id, pid).await;
             }
         }
     });
@@ -114,77 +116,820 @@ async fn handle_socket(socket: WebSocket, room_id: String, state: AppState) {
     }
 }
 
-async fn handle_game_action(
+/// Conexão de um cliente com a sala. O jogador só é definido por um `join`
+/// com o `player_token` dele, e todas as mensagens seguintes valem para esse
+/// jogador.
+struct Session {
+    player_id: Option<String>,
+    /// Idioma desta conexão, lido também por quem envia as mensagens
+    locale: watch::Sender<Locale>,
+    tx: mpsc::UnboundedSender<serde_json::Value>,
+}
+
+impl Session {
+    fn new(tx: mpsc::UnboundedSender<serde_json::Value>, locale: watch::Sender<Locale>) -> Self {
+        Self {
+            player_id: None,
+            locale,
+            tx,
+        }
+    }
+
+    fn player_id(&self) -> Result<&str, GameError> {
+        self.player_id.as_deref().ok_or(GameError::Unauthorized)
+    }
+
+    /// Tira o canal desta conexão da sala, se ele ainda for o registrado.
+    /// Retorna se o jogador saiu (e não apenas trocou de conexão).
+    fn leave(&self, state: &AppState, room_id: &str) -> bool {
+        let Some(pid) = &self.player_id else {
+            return false;
+        };
+        let Some(mut room) = state.rooms.get_mut(room_id) else {
+            return false;
+        };
+        if !room
+            .websocket_senders
+            .get(pid)
+            .is_some_and(|sender| sender.same_channel(&self.tx))
+        {
+            return false;
+        }
+        room.websocket_senders.remove(pid);
+        true
+    }
+}
+
+async fn handle_client_message(
     state: &AppState,
     room_id: &str,
-    player_id: &str,
-    action: PlayerAction,
+    session: &mut Session,
+    message: WebSocketMessage,
+) -> Result<(), GameError> {
+    match message.message_type.as_str() {
+        "join" => join(state, room_id, session, &message.data),
+        "game_action" => {
+            let pid = session.player_id()?;
+            let Ok(action_data) = serde_json::from_value::<GameActionMessage>(message.data) else {
+                return Ok(());
+            };
+            if action_data.player_id != pid {
+                return Err(GameError::Unauthorized);
+            }
+            handle_game_action(state, room_id, pid, &action_data)
+                .await
+                .map(drop)
+        }
+        "show_hand" => handle_show_hand(state, room_id, session.player_id()?)
+            .await
+            .map(drop),
+        "rabbit_hunt" => handle_rabbit_hunt(state, room_id, session.player_id()?)
+            .await
+            .map(drop),
+        "auto_muck" => {
+            let pid = session.player_id()?;
+            if message
+                .data
+                .get("player_id")
+                .and_then(|v| v.as_str())
+                .is_some_and(|id| id != pid)
+            {
+                return Err(GameError::Unauthorized);
+            }
+            let Some(enabled) = message.data.get("enabled").and_then(|v| v.as_bool()) else {
+                return Ok(());
+            };
+            let message = serde_json::json!({
+                "type": "auto_muck",
+                "data": handle_auto_muck(state, room_id, pid, enabled).await?
+            });
+            let _ = session.tx.send(message);
+            Ok(())
+        }
+        "run_it" => {
+            let pid = session.player_id()?;
+            // O voto vale só para o jogador autenticado na conexão
+            if message
+                .data
+                .get("player_id")
+                .and_then(|v| v.as_str())
+                .is_some_and(|id| id != pid)
+            {
+                return Err(GameError::Unauthorized);
+            }
+            let runs = message
+                .data
+                .get("runs")
+                .and_then(|v| v.as_u64())
+                .map_or(0, |runs| runs.min(u8::MAX as u64) as u8);
+            handle_run_it(state, room_id, pid, runs).await.map(drop)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Autentica a conexão com o `player_token` do jogador e passa a enviar a ela
+/// as mensagens da sala.
+fn join(
+    state: &AppState,
+    room_id: &str,
+    session: &mut Session,
+    data: &serde_json::Value,
+) -> Result<(), GameError> {
+    // Idioma opcional das mensagens desta conexão
+    if let Some(requested) = data
+        .get("locale")
+        .and_then(|v| v.as_str())
+        .and_then(Locale::from_tag)
+    {
+        session.locale.send_replace(requested);
+    }
+
+    let pid = data
+        .get("player_id")
+        .and_then(|v| v.as_str())
+        .ok_or(GameError::Unauthorized)?;
+    let token = data.get("player_token").and_then(|v| v.as_str());
+    {
+        let room = state.rooms.get(room_id).ok_or(GameError::RoomNotFound)?;
+        verify_player(&room, pid, token)?;
+    }
+
+    // Uma conexão representa um jogador por vez
+    session.leave(state, room_id);
+    session.player_id = Some(pid.to_string());
+
+    // Adicionar sender do WebSocket à sala e enviar o estado atual
+    let mut room = state
+        .rooms
+        .get_mut(room_id)
+        .ok_or(GameError::RoomNotFound)?;
+    room.websocket_senders
+        .insert(pid.to_string(), session.tx.clone());
+    let _ = session.tx.send(room_state_message(&room, pid));
+    Ok(())
+}
+
+/// Mensagem `room_state` enviada a cada conexão que entra na sala, com as
+/// hole cards do jogador da conexão.
+pub fn room_state_message(room: &Room, player_id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "type": "room_state",
+        "data": {
+            "room_id": room.id,
+            "players": room.players.values().collect::<Vec<_>>(),
+            "game": room.game.as_ref().map(|g| g.game_state_for(Some(player_id)))
+        }
+    })
+}
+
+/// Envia o estado do jogo a cada conexão da sala. Cada jogador recebe só as
+/// próprias hole cards (e as mostradas depois do fim da mão).
+pub fn broadcast_game_state(
+    senders: &HashMap<String, mpsc::UnboundedSender<serde_json::Value>>,
+    message_type: &str,
+    game: &Game,
 ) {
-    if let Some(mut room) = state.rooms.get_mut(room_id) {
+    for (player_id, sender) in senders {
+        let _ = sender.send(serde_json::json!({
+            "type": message_type,
+            "data": game.game_state_for(Some(player_id))
+        }));
+    }
+}
+
+/// Processa uma ação de jogo e faz o broadcast do resultado para a sala,
+/// retornando o estado do jogo após a ação, visto pelo jogador. Compartilhado
+/// entre o WebSocket e o endpoint REST de ações.
+pub async fn handle_game_action(
+    state: &AppState,
+    room_id: &str,
+    player_id: &str,
+    message: &GameActionMessage,
+) -> Result<serde_json::Value, GameError> {
+    update_game(state, room_id, player_id, |game| {
+        game.process_client_action(player_id, message)
+    })
+    .await
+}
+
+/// Registra o voto de um jogador all-in em quantas vezes distribuir o board.
+/// Compartilhado entre o WebSocket e o endpoint REST.
+pub async fn handle_run_it(
+    state: &AppState,
+    room_id: &str,
+    player_id: &str,
+    runs: u8,
+) -> Result<serde_json::Value, GameError> {
+    update_game(state, room_id, player_id, |game| {
+        game.choose_runs(player_id, runs)
+    })
+    .await
+}
+
+/// Liga ou desliga o auto-muck do jogador. A escolha só aparece no estado do
+/// jogo, sem broadcast próprio.
+pub async fn handle_auto_muck(
+    state: &AppState,
+    room_id: &str,
+    player_id: &str,
+    enabled: bool,
+) -> Result<serde_json::Value, GameError> {
+    let mut room = state
+        .rooms
+        .get_mut(room_id)
+        .ok_or(GameError::RoomNotFound)?;
+    let game = room.game.as_mut().ok_or(GameError::GameNotStarted)?;
+    game.set_auto_muck(player_id, enabled)?;
+    let written = state.persist(&room);
+    drop(room);
+    written.written().await;
+    Ok(serde_json::json!({
+        "player_id": player_id,
+        "auto_muck": enabled
+    }))
+}
+
+/// Abre as cartas do jogador depois do fim da mão e avisa a sala com uma
+/// mensagem `hand_shown`, que também é o retorno.
+pub async fn handle_show_hand(
+    state: &AppState,
+    room_id: &str,
+    player_id: &str,
+) -> Result<serde_json::Value, GameError> {
+    reveal(state, room_id, |game| {
+        Ok(serde_json::json!({
+            "type": "hand_shown",
+            "data": game.show_hand(player_id)?
+        }))
+    })
+    .await
+}
+
+/// Revela o board que teria saído numa mão que acabou antes do river e avisa
+/// a sala com uma mensagem `rabbit_hunt`, que também é o retorno.
+pub async fn handle_rabbit_hunt(
+    state: &AppState,
+    room_id: &str,
+    player_id: &str,
+) -> Result<serde_json::Value, GameError> {
+    reveal(state, room_id, |game| {
+        Ok(serde_json::json!({
+            "type": "rabbit_hunt",
+            "data": {
+                "player_id": player_id,
+                "board": game.rabbit_hunt(player_id)?
+            }
+        }))
+    })
+    .await
+}
+
+async fn reveal(
+    state: &AppState,
+    room_id: &str,
+    apply: impl FnOnce(&mut Game) -> Result<serde_json::Value, GameError>,
+) -> Result<serde_json::Value, GameError> {
+    let mut room = state
+        .rooms
+        .get_mut(room_id)
+        .ok_or(GameError::RoomNotFound)?;
+    let senders: Vec<_> = room.websocket_senders.values().cloned().collect();
+    let game = room.game.as_mut().ok_or(GameError::GameNotStarted)?;
+
+    let message = apply(game)?;
+    for sender in &senders {
+        let _ = sender.send(message.clone());
+    }
+    // O histórico gravado passa a incluir as cartas abertas depois do fim
+    if let Some(history) = &game.hand_history {
+        state.record_hand(room_id, history);
+    }
+    let written = state.persist(&room);
+    drop(room);
+    written.written().await;
+    Ok(message)
+}
+
+/// Aplica uma alteração ao jogo da sala e faz o broadcast do resultado. Se a
+/// alteração fechou um all-in, a equity é calculada numa thread de bloqueio
+/// com a sala solta (no pré-flop ela é um Monte Carlo) e gravada no jogo
+/// antes do broadcast e da gravação. Retorna o estado visto por `player_id`.
+async fn update_game(
+    state: &AppState,
+    room_id: &str,
+    player_id: &str,
+    apply: impl FnOnce(&mut Game) -> Result<Option<serde_json::Value>, GameError>,
+) -> Result<serde_json::Value, GameError> {
+    let (had_all_in, had_vote, round_result, mut finished_hand, equity_request) = {
+        let mut room = state
+            .rooms
+            .get_mut(room_id)
+            .ok_or(GameError::RoomNotFound)?;
+        let game = room.game.as_mut().ok_or(GameError::GameNotStarted)?;
+        let had_all_in = game
+            .hand_history
+            .as_ref()
+            .is_some_and(|h| h.all_in.is_some());
+        let had_vote = game.run_it.is_some();
+
+        let round_result = match apply(game) {
+            // Reenvio de uma ação já aplicada: responde com o estado atual sem
+            // aplicar de novo nem refazer o broadcast
+            Err(GameError::DuplicateAction) => return Ok(game.game_state_for(Some(player_id))),
+            result => result?,
+        };
+        // A mão terminada é guardada agora: outra requisição pode iniciar a
+        // próxima enquanto a equity é calculada
+        let finished_hand = round_result
+            .as_ref()
+            .and_then(|_| game.hand_history.clone());
+        (
+            had_all_in,
+            had_vote,
+            round_result,
+            finished_hand,
+            game.all_in_equity_request(),
+        )
+    };
+
+    let equity = match equity_request {
+        Some(request) => {
+            let players = tokio::task::spawn_blocking({
+                let request = request.clone();
+                move || request.calculate()
+            })
+            .await
+            .unwrap_or_default();
+            Some((request, players))
+        }
+        None => None,
+    };
+
+    let (game_state, round_finished, opened_vote, written) = {
+        let mut room = state
+            .rooms
+            .get_mut(room_id)
+            .ok_or(GameError::RoomNotFound)?;
         // Primeiro, coletar todos os senders
-        let senders: Vec<_> = room.websocket_senders.values().cloned().collect();
-        let player_sender = room.websocket_senders.get(player_id).cloned();
-        if let Some(ref mut game) = room.game {
-            match game.process_action(player_id, action) {
-                Ok(round_result) => {
-                    // Enviar estado atualizado do jogo para todos os jogadores
-                    let game_state = game.get_game_state();
-                    let message = serde_json::json!({
-                        "type": "game_update",
-                        "data": game_state
-                    });
-
-                    for sender in &senders {
-                        let _ = sender.send(message.to_string());
-                    }
-
-                    // Se há um resultado da rodada (jogo terminou), enviar o resultado
-                    if let Some(result) = round_result {
-                        let message = serde_json::json!({
-                            "type": "round_finished",
-                            "data": result
-                        });
-
-                        for sender in &senders {
-                            let _ = sender.send(message.to_string());
-                        }
-
-                        // Aguardar 5 segundos e iniciar nova rodada
-                        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-
-                        // Verificar se ainda há jogadores suficientes
-                        let active_players_count =
-                            game.players.iter().filter(|p| p.chips > 0).count();
-
-                        if active_players_count >= 2 {
-                            game.dealer_index = (game.dealer_index + 1) % game.players.len();
-                            game.start_round();
-
-                            let game_state = game.get_game_state();
-                            let message = serde_json::json!({
-                                "type": "new_round",
-                                "data": game_state
-                            });
-
-                            for sender in &senders {
-                                let _ = sender.send(message.to_string());
-                            }
-                        }
-                    }
+        let senders = room.websocket_senders.clone();
+        let game = room.game.as_mut().ok_or(GameError::GameNotStarted)?;
+        if let Some((request, players)) = equity {
+            if let Some(all_in) = finished_hand.as_mut().and_then(|h| h.all_in.as_mut()) {
+                all_in.players = players.clone();
+            }
+            game.set_all_in_equity(&request, players);
+        }
+
+        // Mão decidida em all-in: a equity de cada jogador vai antes do board
+        if !had_all_in {
+            if let Some(all_in) = game.hand_history.as_ref().and_then(|h| h.all_in.as_ref()) {
+                let message = serde_json::json!({
+                    "type": "all_in",
+                    "data": all_in
+                });
+                for sender in senders.values() {
+                    let _ = sender.send(message.clone());
                 }
-                Err(error) => {
-                    // Enviar erro para o jogador específico
-                    if let Some(sender) = player_sender {
-                        let error_message = serde_json::json!({
-                            "type": "error",
-                            "data": {
-                                "message": error
-                            }
-                        });
-                        let _ = sender.send(error_message.to_string());
-                    }
+            }
+        }
+
+        // Votação aberta: os jogadores all-in escolhem quantas vezes rodar
+        let mut opened_vote = None;
+        if !had_vote {
+            if let Some(vote) = &game.run_it {
+                let message = serde_json::json!({
+                    "type": "run_it_offer",
+                    "data": vote
+                });
+                for sender in senders.values() {
+                    let _ = sender.send(message.clone());
                 }
+                opened_vote = Some(game.hand_number);
+            }
+        }
+
+        // Enviar estado atualizado do jogo para todos os jogadores
+        broadcast_game_state(&senders, "game_update", game);
+        let game_state = game.game_state_for(Some(player_id));
+
+        // Se há um resultado da rodada (jogo terminou), enviar o resultado
+        if let Some(result) = &round_result {
+            let message = serde_json::json!({
+                "type": "round_finished",
+                "data": result
+            });
+
+            for sender in senders.values() {
+                let _ = sender.send(message.clone());
+            }
+        }
+
+        let round_finished = round_result.is_some();
+        if let Some(history) = &finished_hand {
+            state.record_hand(room_id, history);
+        }
+        let written = state.persist(&room);
+        (game_state, round_finished, opened_vote, written)
+    };
+    written.written().await;
+
+    if let Some(hand_number) = opened_vote {
+        tokio::spawn(expire_run_it_vote(
+            state.clone(),
+            room_id.to_string(),
+            hand_number,
+        ));
+    }
+
+    // A nova rodada é agendada fora do lock da sala para não bloquear as
+    // demais requisições durante a espera
+    if round_finished {
+        tokio::spawn(start_next_round(state.clone(), room_id.to_string()));
+    }
+
+    Ok(game_state)
+}
+
+/// Encerra a votação da mão `hand_number` no fim do prazo, se ela ainda
+/// estiver aberta: quem não votou conta como 1. O future é boxed porque passa
+/// de novo por `update_game`, que é quem agenda o prazo.
+fn expire_run_it_vote(
+    state: AppState,
+    room_id: String,
+    hand_number: u64,
+) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    Box::pin(async move {
+        tokio::time::sleep(state.run_it_timeout).await;
+        let _ = update_game(&state, &room_id, "", |game| {
+            if game.hand_number != hand_number {
+                return Err(GameError::RunItNotAvailable);
             }
+            game.expire_run_it_vote()
+        })
+        .await;
+    })
+}
+
+/// Agenda o prazo das votações que estavam abertas quando o servidor parou.
+pub fn resume_run_it_votes(state: &AppState) {
+    for room in state.rooms.iter() {
+        if let Some(game) = room.game.as_ref().filter(|game| game.run_it.is_some()) {
+            tokio::spawn(expire_run_it_vote(
+                state.clone(),
+                room.id.clone(),
+                game.hand_number,
+            ));
+        }
+    }
+}
+
+/// Conta a saída de um jogador como voto em rodar uma vez, se ele estiver
+/// numa votação aberta sem ter votado.
+pub async fn forfeit_run_it(state: &AppState, room_id: &str, player_id: &str) {
+    let _ = update_game(state, room_id, player_id, |game| {
+        game.forfeit_run_it(player_id)
+    })
+    .await;
+}
+
+async fn start_next_round(state: AppState, room_id: String) {
+    // Aguardar 5 segundos e iniciar nova rodada
+    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+
+    let Some(mut room) = state.rooms.get_mut(&room_id) else {
+        return;
+    };
+    let senders = room.websocket_senders.clone();
+    let Some(game) = room.game.as_mut() else {
+        return;
+    };
+
+    // A mão pode já ter sido reiniciada via POST /room/:room_id/next
+    if !matches!(game.state, GameState::Finished) {
+        return;
+    }
+
+    // Verificar se ainda há jogadores suficientes
+    let active_players_count = game.players.iter().filter(|p| p.chips > 0).count();
+
+    if active_players_count >= 2 {
+        game.dealer_index = (game.dealer_index + 1) % game.players.len();
+        if let Err(error) = game.start_round() {
+            eprintln!("Falha ao iniciar a mão na sala {}: {}", room_id, error);
+            return;
         }
+
+        broadcast_game_state(&senders, "new_round", game);
+
+        let written = state.persist(&room);
+        drop(room);
+        written.written().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{auth::hash_token, storage::MemoryStore};
+    use axum::extract::ws::Message;
+    use std::{collections::HashMap, sync::Arc, time::Duration};
+
+    /// Sala com Alice e Bob em jogo; o token de cada um é `token-<id>`.
+    fn state_with_game() -> AppState {
+        let players: Vec<Player> = ["alice", "bob"]
+            .iter()
+            .map(|id| Player {
+                id: id.to_string(),
+                name: id.to_string(),
+                chips: 1000,
+                hand: Vec::new(),
+                current_bet: 0,
+                is_folded: false,
+                is_all_in: false,
+            })
+            .collect();
+        let mut game = Game::new(players.clone());
+        game.start_round().unwrap();
+
+        let state = AppState::new(Arc::new(MemoryStore::default()));
+        state.rooms.insert(
+            "sala".to_string(),
+            Room {
+                id: "sala".to_string(),
+                creator_id: "alice".to_string(),
+                players: players.iter().map(|p| (p.id.clone(), p.clone())).collect(),
+                game: Some(game),
+                max_players: 6,
+                websocket_senders: HashMap::new(),
+                player_tokens: players
+                    .iter()
+                    .map(|p| (p.id.clone(), hash_token(&format!("token-{}", p.id))))
+                    .collect(),
+                deck_source: Default::default(),
+                fairness: Default::default(),
+                rules: Default::default(),
+            },
+        );
+        state
+    }
+
+    fn client_message(message_type: &str, data: serde_json::Value) -> WebSocketMessage {
+        WebSocketMessage {
+            message_type: message_type.to_string(),
+            data,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_join_requires_player_token() {
+        let state = state_with_game();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut session = Session::new(tx, watch::channel(Locale::default()).0);
+
+        for data in [
+            serde_json::json!({"player_id": "alice"}),
+            serde_json::json!({"player_id": "alice", "player_token": "token-bob"}),
+            serde_json::json!({"player_id": "alice", "player_token": hash_token("token-alice")}),
+        ] {
+            let join = client_message("join", data);
+            let result = handle_client_message(&state, "sala", &mut session, join).await;
+            assert_eq!(result, Err(GameError::Unauthorized));
+        }
+        assert!(session.player_id.is_none());
+        assert!(state
+            .rooms
+            .get("sala")
+            .unwrap()
+            .websocket_senders
+            .is_empty());
+
+        let join = client_message(
+            "join",
+            serde_json::json!({"player_id": "alice", "player_token": "token-alice"}),
+        );
+        handle_client_message(&state, "sala", &mut session, join)
+            .await
+            .unwrap();
+        assert_eq!(session.player_id.as_deref(), Some("alice"));
+        assert!(state
+            .rooms
+            .get("sala")
+            .unwrap()
+            .websocket_senders
+            .contains_key("alice"));
+        assert_eq!(rx.recv().await.unwrap()["type"], "room_state");
+    }
+
+    #[tokio::test]
+    async fn test_actions_are_bound_to_the_joined_player() {
+        let state = state_with_game();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut session = Session::new(tx, watch::channel(Locale::default()).0);
+        let action = |player_id: &str| {
+            client_message(
+                "game_action",
+                serde_json::json!({"player_id": player_id, "action": "Call"}),
+            )
+        };
+
+        // Sem join nenhuma ação é aceita
+        let result = handle_client_message(&state, "sala", &mut session, action("alice")).await;
+        assert_eq!(result, Err(GameError::Unauthorized));
+
+        let join = client_message(
+            "join",
+            serde_json::json!({"player_id": "bob", "player_token": "token-bob"}),
+        );
+        handle_client_message(&state, "sala", &mut session, join)
+            .await
+            .unwrap();
+
+        // A conexão do Bob não age em nome da Alice, só no dele (Bob abre a mão)
+        let result = handle_client_message(&state, "sala", &mut session, action("alice")).await;
+        assert_eq!(result, Err(GameError::Unauthorized));
+        handle_client_message(&state, "sala", &mut session, action("bob"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_it_vote_expires_when_a_player_never_votes() {
+        let state = state_with_game().with_run_it_timeout(Duration::from_millis(50));
+        state
+            .rooms
+            .get_mut("sala")
+            .unwrap()
+            .game
+            .as_mut()
+            .unwrap()
+            .rules
+            .max_runs = 2;
+        update_game(&state, "sala", "bob", |game| {
+            game.process_action("bob", PlayerAction::AllIn)
+        })
+        .await
+        .unwrap();
+        update_game(&state, "sala", "alice", |game| {
+            game.process_action("alice", PlayerAction::Call)
+        })
+        .await
+        .unwrap();
+        handle_run_it(&state, "sala", "alice", 2).await.unwrap();
+
+        // Bob nunca vota: no fim do prazo o board sai uma vez e a mão termina
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let room = state.rooms.get("sala").unwrap();
+        let game = room.game.as_ref().unwrap();
+        assert!(game.run_it.is_none());
+        assert_eq!(game.state, GameState::Finished);
+        assert!(game.hand_history.as_ref().unwrap().runs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_it_vote_comes_only_from_the_joined_player() {
+        let state = state_with_game();
+        {
+            let mut room = state.rooms.get_mut("sala").unwrap();
+            let game = room.game.as_mut().unwrap();
+            game.rules.max_runs = 2;
+            game.process_action("bob", PlayerAction::AllIn).unwrap();
+            game.process_action("alice", PlayerAction::Call).unwrap();
+            assert!(game.run_it.is_some());
+        }
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut session = Session::new(tx, watch::channel(Locale::default()).0);
+        let vote = |data: serde_json::Value| client_message("run_it", data);
+        let votes = |state: &AppState| {
+            let room = state.rooms.get("sala").unwrap();
+            room.game
+                .as_ref()
+                .unwrap()
+                .run_it
+                .as_ref()
+                .unwrap()
+                .votes
+                .clone()
+        };
+
+        // Sem join o voto é recusado
+        let result = handle_client_message(
+            &state,
+            "sala",
+            &mut session,
+            vote(serde_json::json!({"runs": 2})),
+        )
+        .await;
+        assert_eq!(result, Err(GameError::Unauthorized));
+        assert!(votes(&state).is_empty());
+
+        let join = client_message(
+            "join",
+            serde_json::json!({"player_id": "bob", "player_token": "token-bob"}),
+        );
+        handle_client_message(&state, "sala", &mut session, join)
+            .await
+            .unwrap();
+
+        // A conexão do Bob não vota pela Alice
+        let result = handle_client_message(
+            &state,
+            "sala",
+            &mut session,
+            vote(serde_json::json!({"player_id": "alice", "runs": 2})),
+        )
+        .await;
+        assert_eq!(result, Err(GameError::Unauthorized));
+        assert!(votes(&state).is_empty());
+
+        handle_client_message(
+            &state,
+            "sala",
+            &mut session,
+            vote(serde_json::json!({"runs": 2})),
+        )
+        .await
+        .unwrap();
+        assert_eq!(votes(&state), HashMap::from([("bob".to_string(), 2)]));
+    }
+
+    fn sample_message() -> serde_json::Value {
+        serde_json::json!({
+            "message_type": "game_action",
+            "data": {
+                "player_id": "player1",
+                "action": {"Raise": 20}
+            }
+        })
+    }
+
+    fn decode_server_message(encoding: WireEncoding, frame: &Message) -> serde_json::Value {
+        match (encoding, frame) {
+            (WireEncoding::Json, Message::Text(text)) => serde_json::from_str(text).unwrap(),
+            (WireEncoding::Msgpack, Message::Binary(bytes)) => {
+                rmp_serde::from_slice(bytes).unwrap()
+            }
+            _ => panic!("Frame inesperado para {:?}: {:?}", encoding, frame),
+        }
+    }
+
+    #[test]
+    fn test_encoding_round_trip() {
+        let message = serde_json::json!({
+            "type": "game_update",
+            "data": {"pot": 15, "community_cards": [{"suit": "Hearts", "rank": "Ace"}]}
+        });
+
+        for encoding in [WireEncoding::Json, WireEncoding::Msgpack] {
+            let frame = encoding.encode(&message).unwrap();
+            assert_eq!(decode_server_message(encoding, &frame), message);
+        }
+    }
+
+    #[test]
+    fn test_decode_client_message_in_both_encodings() {
+        let message = sample_message();
+
+        for encoding in [WireEncoding::Json, WireEncoding::Msgpack] {
+            let frame = encoding.encode(&message).unwrap();
+            let decoded = encoding.decode(&frame).unwrap();
+            assert_eq!(decoded.message_type, "game_action");
+
+            let action: GameActionMessage = serde_json::from_value(decoded.data).unwrap();
+            assert_eq!(action.player_id, "player1");
+            assert!(matches!(action.action, PlayerAction::Raise(20)));
+        }
+    }
+
+    #[test]
+    fn test_msgpack_is_smaller_than_json() {
+        let message = sample_message();
+        let Some(Message::Text(json)) = WireEncoding::Json.encode(&message) else {
+            panic!("JSON deve gerar frame de texto");
+        };
+        let Some(Message::Binary(msgpack)) = WireEncoding::Msgpack.encode(&message) else {
+            panic!("MessagePack deve gerar frame binário");
+        };
+        assert!(msgpack.len() < json.len());
+    }
+
+    #[test]
+    fn test_json_connection_rejects_binary_frames() {
+        let frame = WireEncoding::Msgpack.encode(&sample_message()).unwrap();
+        assert!(WireEncoding::Json.decode(&frame).is_none());
+        // Clientes MessagePack ainda podem mandar texto JSON
+        let text = WireEncoding::Json.encode(&sample_message()).unwrap();
+        assert!(WireEncoding::Msgpack.decode(&text).is_some());
+    }
+
+    #[test]
+    fn test_encoding_query_param() {
+        let params: WebSocketParams = serde_json::from_value(serde_json::json!({
+            "encoding": "msgpack"
+        }))
+        .unwrap();
+        assert_eq!(params.encoding, Some(WireEncoding::Msgpack));
+        assert_eq!(params.cards, CardFormat::Verbose);
+        assert_eq!(WireEncoding::default(), WireEncoding::Json);
     }
 }
@@ -0,0 +1,1602 @@
+//! Testes da API HTTP, usando a biblioteca como um consumidor externo.
+#![cfg(feature = "server")]
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use futures_util::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use poker_api::history::HandHistory;
+use poker_api::journal::GameJournal;
+use poker_api::models::Card;
+use poker_api::server::{app, AppState};
+use poker_api::storage::{MemoryStore, RoomSnapshot, RoomStore, StorageError};
+
+async fn create_test_app() -> Router {
+    create_app_with_store(Arc::new(MemoryStore::default()))
+}
+
+/// App com seeds fixas e decks empilhados liberados
+fn create_test_mode_app() -> Router {
+    create_app_with_state(AppState::new(Arc::new(MemoryStore::default())).with_test_mode(true))
+}
+
+fn create_app_with_store(store: Arc<dyn RoomStore>) -> Router {
+    create_app_with_state(AppState::restore(store).unwrap())
+}
+
+fn create_app_with_state(state: AppState) -> Router {
+    app(state)
+}
+
+#[tokio::test]
+async fn test_health_check() {
+    let app = create_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+/// Armazenamento cujas gravações sempre falham, como um disco cheio.
+struct FailingStore;
+
+impl RoomStore for FailingStore {
+    fn save_room(&self, _: &RoomSnapshot) -> Result<(), StorageError> {
+        Err(StorageError::Io(std::io::Error::other("disco cheio")))
+    }
+    fn delete_room(&self, _: &str) -> Result<(), StorageError> {
+        Ok(())
+    }
+    fn load_rooms(&self) -> Result<Vec<RoomSnapshot>, StorageError> {
+        Ok(Vec::new())
+    }
+    fn save_hand(&self, _: &str, _: &HandHistory) -> Result<(), StorageError> {
+        Err(StorageError::Io(std::io::Error::other("disco cheio")))
+    }
+    fn load_hands(&self, _: &str) -> Result<Vec<HandHistory>, StorageError> {
+        Ok(Vec::new())
+    }
+    fn load_hand(&self, _: &str) -> Result<Option<HandHistory>, StorageError> {
+        Ok(None)
+    }
+}
+
+#[tokio::test]
+async fn test_health_reports_failing_storage() {
+    let app = create_app_with_store(Arc::new(FailingStore));
+
+    // A sala continua funcionando em memória, mas a falha aparece no /health
+    let (room_id, _) = setup_room(&app).await;
+    assert!(!room_id.is_empty());
+
+    let (status, health) = get_json(&app, "/health").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(health["status"], "degraded");
+    assert_eq!(health["storage"]["consecutive_failures"], 2);
+    assert!(health["storage"]["last_error"]
+        .as_str()
+        .unwrap()
+        .contains("disco cheio"));
+}
+
+#[tokio::test]
+async fn test_create_room() {
+    let app = create_test_app().await;
+
+    let request_body = json!({
+        "creator_name": "TestPlayer"
+    });
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/room")
+                .header("content-type", "application/json")
+                .body(Body::from(request_body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_join_nonexistent_room() {
+    let app = create_test_app().await;
+
+    let request_body = json!({
+        "player_name": "TestPlayer"
+    });
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/room/nonexistent/join")
+                .header("content-type", "application/json")
+                .body(Body::from(request_body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+async fn send_json(
+    app: &Router,
+    uri: &str,
+    body: serde_json::Value,
+    token: Option<&str>,
+) -> (StatusCode, serde_json::Value) {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        request = request.header("authorization", format!("Bearer {}", token));
+    }
+
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+async fn post_json(app: &Router, uri: &str, body: serde_json::Value) -> serde_json::Value {
+    let (status, body) = send_json(app, uri, body, None).await;
+    assert_eq!(status, StatusCode::OK);
+    body
+}
+
+struct TestPlayer {
+    id: String,
+    token: String,
+}
+
+impl TestPlayer {
+    fn from_response(response: &serde_json::Value) -> Self {
+        Self {
+            id: response["player_id"].as_str().unwrap().to_string(),
+            token: response["player_token"].as_str().unwrap().to_string(),
+        }
+    }
+}
+
+/// Cria uma sala com dois jogadores e retorna o id da sala e os jogadores
+async fn setup_room(app: &Router) -> (String, Vec<TestPlayer>) {
+    let created = post_json(app, "/room", json!({"creator_name": "Alice"})).await;
+    let room_id = created["room_id"].as_str().unwrap().to_string();
+    let joined = post_json(
+        app,
+        &format!("/room/{}/join", room_id),
+        json!({"player_name": "Bob"}),
+    )
+    .await;
+
+    let players = vec![
+        TestPlayer::from_response(&created),
+        TestPlayer::from_response(&joined),
+    ];
+    (room_id, players)
+}
+
+async fn submit_action(
+    app: &Router,
+    room_id: &str,
+    player: &TestPlayer,
+    token: &str,
+    action: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    send_json(
+        app,
+        &format!("/room/{}/action", room_id),
+        json!({"player_id": player.id, "action": action}),
+        Some(token),
+    )
+    .await
+}
+
+/// Inicia o jogo e retorna (jogador da vez, outro jogador)
+async fn start_game<'a>(
+    app: &Router,
+    room_id: &str,
+    players: &'a [TestPlayer],
+) -> (&'a TestPlayer, &'a TestPlayer) {
+    let started = post_json(app, &format!("/room/{}/start", room_id), json!({})).await;
+    assert_eq!(started["success"], true);
+    // Sem token, o estado não traz as cartas de ninguém
+    for player in started["game_state"]["players"].as_array().unwrap() {
+        assert_eq!(player["hand"], json!([]));
+    }
+    let current_player = started["game_state"]["current_player"].as_str().unwrap();
+
+    if players[0].id == current_player {
+        (&players[0], &players[1])
+    } else {
+        (&players[1], &players[0])
+    }
+}
+
+async fn next_event(body: &mut axum::body::BodyDataStream) -> String {
+    let chunk = body.next().await.unwrap().unwrap();
+    String::from_utf8(chunk.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_event_stream_delivers_room_state_and_updates() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/room/{}/events?player_id={}&player_token={}",
+                    room_id, players[0].id, players[0].token
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    let mut body = response.into_body().into_data_stream();
+    assert!(next_event(&mut body).await.contains("event: room_state\n"));
+
+    let (current, _) = start_game(&app, &room_id, &players).await;
+    assert!(next_event(&mut body)
+        .await
+        .contains("event: game_started\n"));
+
+    let (status, result) =
+        submit_action(&app, &room_id, current, &current.token, json!("Call")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["success"], true);
+    assert!(next_event(&mut body).await.contains("event: game_update\n"));
+}
+
+#[tokio::test]
+async fn test_event_stream_ends_when_the_player_reconnects() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let uri = format!(
+        "/room/{}/events?player_id={}&player_token={}",
+        room_id, players[0].id, players[0].token
+    );
+    let open = || async {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut body = response.into_body().into_data_stream();
+        assert!(next_event(&mut body).await.contains("event: room_state\n"));
+        body
+    };
+
+    let mut old = open().await;
+    let mut new = open().await;
+
+    // O stream antigo termina assim que a reconexão toma o lugar dele
+    let ended = tokio::time::timeout(std::time::Duration::from_secs(1), old.next()).await;
+    assert!(matches!(ended, Ok(None)));
+
+    start_game(&app, &room_id, &players).await;
+    assert!(next_event(&mut new).await.contains("event: game_started\n"));
+}
+
+#[tokio::test]
+async fn test_event_stream_accepts_any_regional_locale() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+
+    for locale in ["en-GB", "pt_PT", "EN"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!(
+                        "/room/{}/events?player_id={}&player_token={}&locale={}",
+                        room_id, players[0].id, players[0].token, locale
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "locale {locale}");
+        let mut body = response.into_body().into_data_stream();
+        assert!(next_event(&mut body).await.contains("event: room_state\n"));
+    }
+}
+
+#[tokio::test]
+async fn test_event_stream_rejects_unknown_player() {
+    let app = create_test_app().await;
+    let (room_id, _) = setup_room(&app).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/room/{}/events?player_id=intruso", room_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_event_stream_requires_player_token() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+
+    let subscribe = |query: String, token: Option<&str>| {
+        let mut request = Request::builder().uri(format!("/room/{}/events?{}", room_id, query));
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+    let player = &players[0];
+
+    // Sem token ou com o token de outro jogador
+    for (query, header) in [
+        (format!("player_id={}", player.id), None),
+        (
+            format!("player_id={}&player_token={}", player.id, players[1].token),
+            None,
+        ),
+        (format!("player_id={}", player.id), Some(&players[1].token)),
+    ] {
+        let response = subscribe(query, header.map(String::as_str)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = subscribe(format!("player_id={}", player.id), Some(&player.token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_submit_action_out_of_turn() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let (_, other) = start_game(&app, &room_id, &players).await;
+
+    let (status, result) = submit_action(&app, &room_id, other, &other.token, json!("Call")).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(result["success"], false);
+    assert_eq!(result["code"], "not_your_turn");
+    assert_eq!(result["message"], "Não é sua vez de jogar");
+}
+
+#[tokio::test]
+async fn test_submit_action_returns_game_state() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let (current, _) = start_game(&app, &room_id, &players).await;
+
+    let (status, result) =
+        submit_action(&app, &room_id, current, &current.token, json!("Call")).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["success"], true);
+    // Small blind completou o big blind: 10 + 10 no pot
+    assert_eq!(result["game_state"]["pot"], 20);
+}
+
+#[tokio::test]
+async fn test_submit_action_rejects_invalid_check() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let (current, _) = start_game(&app, &room_id, &players).await;
+
+    // Há um big blind a ser igualado, então check não é permitido
+    let (status, result) =
+        submit_action(&app, &room_id, current, &current.token, json!("Check")).await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(result["code"], "cannot_check");
+}
+
+#[tokio::test]
+async fn test_submit_action_requires_player_token() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let (current, other) = start_game(&app, &room_id, &players).await;
+
+    // Token de outro jogador não autoriza a ação
+    let (status, result) =
+        submit_action(&app, &room_id, current, &other.token, json!("Call")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(result["code"], "unauthorized");
+
+    // Sem header de autorização
+    let (status, _) = send_json(
+        &app,
+        &format!("/room/{}/action", room_id),
+        json!({"player_id": current.id, "action": "Call"}),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_submit_action_retry_is_idempotent() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let (current, _) = start_game(&app, &room_id, &players).await;
+
+    let body = json!({
+        "player_id": current.id,
+        "action": "Call",
+        "action_id": "c0ffee",
+        "hand_number": 1,
+        "sequence": 0
+    });
+    let uri = format!("/room/{}/action", room_id);
+
+    let (status, first) = send_json(&app, &uri, body.clone(), Some(&current.token)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, retry) = send_json(&app, &uri, body, Some(&current.token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(retry["game_state"]["pot"], first["game_state"]["pot"]);
+    assert_eq!(retry["game_state"]["action_sequence"], 1);
+}
+
+#[tokio::test]
+async fn test_submit_stale_action_conflicts() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let (current, _) = start_game(&app, &room_id, &players).await;
+
+    let (status, result) = send_json(
+        &app,
+        &format!("/room/{}/action", room_id),
+        json!({"player_id": current.id, "action": "Call", "hand_number": 7}),
+        Some(&current.token),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(result["code"], "stale_action");
+}
+
+#[tokio::test]
+async fn test_join_full_room_returns_error_code() {
+    let app = create_test_app().await;
+    let created = post_json(
+        &app,
+        "/room",
+        json!({"creator_name": "Alice", "max_players": 1}),
+    )
+    .await;
+    let room_id = created["room_id"].as_str().unwrap();
+
+    let (status, result) = send_json(
+        &app,
+        &format!("/room/{}/join", room_id),
+        json!({"player_name": "Bob"}),
+        None,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(result["success"], false);
+    assert_eq!(result["code"], "room_full");
+    assert_eq!(result["message"], "Sala lotada");
+}
+
+#[tokio::test]
+async fn test_start_game_without_enough_players() {
+    let app = create_test_app().await;
+    let created = post_json(&app, "/room", json!({"creator_name": "Alice"})).await;
+    let room_id = created["room_id"].as_str().unwrap();
+
+    let (status, result) =
+        send_json(&app, &format!("/room/{}/start", room_id), json!({}), None).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(result["code"], "not_enough_players");
+}
+
+#[tokio::test]
+async fn test_errors_follow_accept_language() {
+    let app = create_test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/room/nonexistent/start")
+                .header("accept-language", "en-US,en;q=0.9,pt-BR;q=0.8")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["code"], "room_not_found");
+    assert_eq!(body["message"], "Room not found");
+}
+
+#[tokio::test]
+async fn test_rooms_are_restored_from_store() {
+    let store: Arc<dyn RoomStore> = Arc::new(MemoryStore::default());
+    let app = create_app_with_store(store.clone());
+    let (room_id, players) = setup_room(&app).await;
+    let (current, _) = start_game(&app, &room_id, &players).await;
+    let (status, before_restart) =
+        submit_action(&app, &room_id, current, &current.token, json!("Call")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Simula um restart do servidor reaproveitando o mesmo armazenamento
+    let restarted = create_app_with_store(store);
+
+    let next_player_id = before_restart["game_state"]["current_player"]
+        .as_str()
+        .unwrap();
+    let next_player = players.iter().find(|p| p.id == next_player_id).unwrap();
+    let (status, result) = send_json(
+        &restarted,
+        &format!("/room/{}/action", room_id),
+        json!({
+            "player_id": next_player.id,
+            "action": "Check",
+            "hand_number": 1,
+            "sequence": 1
+        }),
+        Some(&next_player.token),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK, "{}", result);
+    assert_eq!(
+        result["game_state"]["pot"],
+        before_restart["game_state"]["pot"]
+    );
+}
+
+#[tokio::test]
+async fn test_hand_with_unreadable_deck_is_voided_on_restore() {
+    let store: Arc<dyn RoomStore> = Arc::new(MemoryStore::default());
+    let app = create_app_with_store(store.clone());
+    let (room_id, players) = setup_room(&app).await;
+    let (current, _) = start_game(&app, &room_id, &players).await;
+    submit_action(&app, &room_id, current, &current.token, json!("Call")).await;
+
+    // Deck gravado com outra chave mestra (ou adulterado)
+    let snapshot = store.load_rooms().unwrap().remove(0);
+    let mut snapshot = serde_json::to_value(snapshot).unwrap();
+    snapshot["game"]["deck"]["sealed"]["ciphertext"] = json!("00");
+    store
+        .save_room(&serde_json::from_value::<RoomSnapshot>(snapshot).unwrap())
+        .unwrap();
+
+    let restarted = create_app_with_store(store);
+    let (status, body) = get_json(&restarted, &format!("/room/{}/result", room_id)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["result"]["type"], "voided");
+    assert_eq!(body["result"]["reason"], "deck_unavailable");
+
+    // As fichas voltaram ao início da mão e a próxima mão sai normalmente
+    let next = post_json(&restarted, &format!("/room/{}/next", room_id), json!({})).await;
+    assert_eq!(next["success"], true, "{}", next);
+    for player in next["game_state"]["players"].as_array().unwrap() {
+        let stack = player["chips"].as_u64().unwrap() + player["current_bet"].as_u64().unwrap();
+        assert_eq!(stack, 1000);
+    }
+}
+
+#[tokio::test]
+async fn test_interrupted_hand_is_resumed_from_journal() {
+    let path = std::env::temp_dir().join(format!("poker-{}.journal", uuid::Uuid::new_v4()));
+    let state = AppState::new(Arc::new(MemoryStore::default()))
+        .with_journal(GameJournal::open(&path).unwrap())
+        .unwrap();
+    let app = create_app_with_state(state);
+
+    let (room_id, players) = setup_room(&app).await;
+    let (current, _) = start_game(&app, &room_id, &players).await;
+    let (_, before_crash) =
+        submit_action(&app, &room_id, current, &current.token, json!("Call")).await;
+
+    // Sem banco de dados: só o journal sobrevive ao "crash"
+    let state = AppState::new(Arc::new(MemoryStore::default()))
+        .with_journal(GameJournal::open(&path).unwrap())
+        .unwrap();
+    let restarted = create_app_with_state(state);
+
+    let next_player_id = before_crash["game_state"]["current_player"]
+        .as_str()
+        .unwrap();
+    let next_player = players.iter().find(|p| p.id == next_player_id).unwrap();
+    let (status, result) = send_json(
+        &restarted,
+        &format!("/room/{}/action", room_id),
+        json!({"player_id": next_player.id, "action": "Check", "sequence": 1}),
+        Some(&next_player.token),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK, "{}", result);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_journal_is_written_when_the_database_fails() {
+    let path = std::env::temp_dir().join(format!("poker-{}.journal", uuid::Uuid::new_v4()));
+    let state = AppState::new(Arc::new(FailingStore))
+        .with_journal(GameJournal::open(&path).unwrap())
+        .unwrap();
+    let app = create_app_with_state(state);
+
+    let (room_id, players) = setup_room(&app).await;
+    let (current, _) = start_game(&app, &room_id, &players).await;
+    submit_action(&app, &room_id, current, &current.token, json!("Call")).await;
+    let (_, health) = get_json(&app, "/health").await;
+    assert_eq!(health["status"], "degraded");
+
+    // O banco nunca gravou nada, mas a mão continua no journal
+    let journal = GameJournal::open(&path).unwrap();
+    let rooms = journal.replay().unwrap();
+    let game = rooms[0].game.as_ref().unwrap();
+    assert_eq!(rooms[0].id, room_id);
+    assert_eq!(game.action_sequence, 1);
+    let _ = std::fs::remove_file(&path);
+}
+
+async fn get_json(app: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+    get_json_as(app, uri, None).await
+}
+
+/// GET com o token `Authorization: Bearer`, quando informado
+async fn get_json_as(
+    app: &Router,
+    uri: &str,
+    token: Option<&str>,
+) -> (StatusCode, serde_json::Value) {
+    let mut request = Request::builder().uri(uri);
+    if let Some(token) = token {
+        request = request.header("authorization", format!("Bearer {}", token));
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+/// Joga call até o fim da mão (call sem aposta pendente equivale a check)
+async fn play_hand(app: &Router, room_id: &str, players: &[TestPlayer]) {
+    let (mut current, _) = start_game(app, room_id, players).await;
+    loop {
+        let (status, result) =
+            submit_action(app, room_id, current, &current.token, json!("Call")).await;
+        assert_eq!(status, StatusCode::OK, "{}", result);
+
+        let game_state = &result["game_state"];
+        if game_state["state"] == "Finished" {
+            break;
+        }
+        let next = game_state["current_player"].as_str().unwrap();
+        current = players.iter().find(|p| p.id == next).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_hand_result_is_localized() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    play_hand(&app, &room_id, &players).await;
+    let uri = format!("/room/{}/result", room_id);
+
+    let (_, result) = get_json(&app, &uri).await;
+    let winner = &result["result"]["winners"][0];
+    let summary = &winner["explanation"]["summary"];
+    let prefix = match summary["key"].as_str().unwrap() {
+        "showdown.won" => "Ganha com",
+        _ => "Divide o pot com",
+    };
+    assert_eq!(
+        summary["text"],
+        format!(
+            "{} {}",
+            prefix,
+            winner["description"]["text"].as_str().unwrap()
+        )
+    );
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&uri)
+                .header("accept-language", "en")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let english: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    let english = &english["result"]["winners"][0]["explanation"]["summary"];
+    assert_eq!(english["key"], summary["key"]);
+    assert_eq!(english["params"], summary["params"]);
+    assert!(english["text"]
+        .as_str()
+        .is_some_and(|text| text.starts_with("Wins with") || text.starts_with("Splits the pot")));
+}
+
+#[tokio::test]
+async fn test_finished_hands_are_listed_and_fetchable() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+
+    let (status, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["hands"].as_array().unwrap().len(), 0);
+
+    play_hand(&app, &room_id, &players).await;
+
+    let (status, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    let hands = result["hands"].as_array().unwrap();
+    assert_eq!(hands.len(), 1);
+    assert_eq!(hands[0]["seats"].as_array().unwrap().len(), 2);
+    assert!(hands[0]["finished_at"].is_string());
+
+    let hand_id = hands[0]["hand_id"].as_str().unwrap();
+    let (status, result) = get_json(&app, &format!("/hands/{}", hand_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["hand"]["hand_id"], hand_id);
+    assert_eq!(result["hand"]["board"]["flop"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_compact_card_format_per_client() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    play_hand(&app, &room_id, &players).await;
+
+    let (_, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    let hand_id = result["hands"][0]["hand_id"].as_str().unwrap().to_string();
+    let (_, verbose) = get_json(&app, &format!("/hands/{}", hand_id)).await;
+    assert!(verbose["hand"]["board"]["flop"][0]["rank"].is_string());
+
+    let (status, compact) = get_json(&app, &format!("/hands/{}?cards=compact", hand_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    let flop = &compact["hand"]["board"]["flop"];
+    assert_eq!(flop.as_array().unwrap().len(), 3);
+    for (card, original) in flop
+        .as_array()
+        .unwrap()
+        .iter()
+        .zip(verbose["hand"]["board"]["flop"].as_array().unwrap())
+    {
+        let parsed: Card = serde_json::from_value(card.clone()).unwrap();
+        let expected: Card = serde_json::from_value(original.clone()).unwrap();
+        assert_eq!(parsed, expected);
+    }
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/hands/{}", hand_id))
+                .header("x-card-format", "compact")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["hand"]["board"]["flop"], *flop);
+}
+
+#[tokio::test]
+async fn test_equity_tool() {
+    let app = create_test_app().await;
+
+    let result = post_json(
+        &app,
+        "/tools/equity",
+        json!({"hands": ["AhAd", "Kc Ks"], "board": "2c 7d 9h Js"}),
+    )
+    .await;
+    assert_eq!(result["success"], true);
+    let equity = &result["equity"];
+    assert_eq!(equity["method"], "exhaustive");
+    assert_eq!(equity["samples"], 44);
+    assert_eq!(equity["players"][1]["win"], 2.0 / 44.0);
+
+    let result = post_json(
+        &app,
+        "/tools/equity",
+        json!({"hands": ["AhAd", "KcKs"], "samples": 2000, "seed": 1}),
+    )
+    .await;
+    assert_eq!(result["equity"]["method"], "monte_carlo");
+
+    let result = post_json(
+        &app,
+        "/tools/equity",
+        json!({"hands": ["QQ+, AKs", "76s-54s"], "board": "Ac 7d 2h"}),
+    )
+    .await;
+    let players = result["equity"]["players"].as_array().unwrap();
+    assert_eq!(players[0]["combos"], 15 + 3);
+    assert!(players[0].get("hand").is_none());
+    // 76s perde o 7d do board
+    assert_eq!(players[1]["combos"], 3 + 4 + 4);
+
+    let (status, result) = send_json(
+        &app,
+        "/tools/equity",
+        json!({"hands": ["AhAd", "AhKs"]}),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(result["code"], "invalid_equity_request");
+}
+
+#[tokio::test]
+async fn test_all_in_hand_records_ev() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let (first, second) = start_game(&app, &room_id, &players).await;
+
+    let (status, _) = submit_action(&app, &room_id, first, &first.token, json!("AllIn")).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, result) =
+        submit_action(&app, &room_id, second, &second.token, json!("Call")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["game_state"]["state"], "Finished");
+    assert_eq!(
+        result["game_state"]["community_cards"]
+            .as_array()
+            .unwrap()
+            .len(),
+        5
+    );
+
+    let (_, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    let all_in = &result["hands"][0]["all_in"];
+    assert_eq!(all_in["street"], "PreFlop");
+    assert_eq!(all_in["players"].as_array().unwrap().len(), 2);
+
+    let (status, result) = get_json(&app, &format!("/room/{}/session", room_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    let results = result["players"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    let net: i64 = results.iter().map(|p| p["net"].as_i64().unwrap()).sum();
+    let ev_net: f64 = results.iter().map(|p| p["ev_net"].as_f64().unwrap()).sum();
+    assert_eq!(net, 0);
+    assert!(ev_net.abs() < 1e-6);
+    for player in results {
+        assert_eq!(player["hands"], 1);
+        assert_eq!(player["all_ins"], 1);
+    }
+}
+
+#[tokio::test]
+async fn test_all_in_players_choose_to_run_it_twice() {
+    let app = create_test_app().await;
+    let (status, result) = send_json(
+        &app,
+        "/room",
+        json!({"creator_name": "Alice", "max_runs": 9}),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(result["code"], "invalid_run_count");
+
+    let created = post_json(
+        &app,
+        "/room",
+        json!({"creator_name": "Alice", "max_runs": 2}),
+    )
+    .await;
+    let room_id = created["room_id"].as_str().unwrap().to_string();
+    let joined = post_json(
+        &app,
+        &format!("/room/{}/join", room_id),
+        json!({"player_name": "Bob"}),
+    )
+    .await;
+    let players = vec![
+        TestPlayer::from_response(&created),
+        TestPlayer::from_response(&joined),
+    ];
+    let (first, second) = start_game(&app, &room_id, &players).await;
+
+    submit_action(&app, &room_id, first, &first.token, json!("AllIn")).await;
+    let (status, result) =
+        submit_action(&app, &room_id, second, &second.token, json!("Call")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["game_state"]["state"], "PreFlop");
+    assert_eq!(result["game_state"]["run_it"]["max_runs"], 2);
+
+    // Enquanto a votação está aberta não há mais ações
+    let (status, result) = submit_action(&app, &room_id, first, &first.token, json!("Check")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(result["code"], "run_it_pending");
+
+    let run_it = format!("/room/{}/run-it", room_id);
+    let (status, _) = send_json(
+        &app,
+        &run_it,
+        json!({"player_id": first.id, "runs": 2}),
+        Some(&second.token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, result) = send_json(
+        &app,
+        &run_it,
+        json!({"player_id": first.id, "runs": 3}),
+        Some(&first.token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(result["code"], "invalid_run_count");
+
+    for player in [first, second] {
+        let (status, _) = send_json(
+            &app,
+            &run_it,
+            json!({"player_id": player.id, "runs": 2}),
+            Some(&player.token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (_, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    let hand = &result["hands"][0];
+    let runs = hand["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_ne!(runs[0]["board"], runs[1]["board"]);
+    let won: u64 = hand["winnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["amount"].as_u64().unwrap())
+        .sum();
+    assert_eq!(won, 2000);
+}
+
+#[tokio::test]
+async fn test_winner_shows_and_players_rabbit_hunt_after_fold() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let (first, second) = start_game(&app, &room_id, &players).await;
+    let show = format!("/room/{}/show", room_id);
+    let rabbit_hunt = format!("/room/{}/rabbit-hunt", room_id);
+
+    let (status, result) = send_json(
+        &app,
+        &rabbit_hunt,
+        json!({"player_id": first.id}),
+        Some(&first.token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(result["code"], "hand_not_finished");
+
+    let (_, result) = submit_action(&app, &room_id, first, &first.token, json!("Fold")).await;
+    assert_eq!(result["game_state"]["state"], "Finished");
+    assert_eq!(result["game_state"]["community_cards"], json!([]));
+
+    let (_, result) = get_json(&app, &format!("/room/{}/result", room_id)).await;
+    assert_eq!(result["result"]["winner"]["id"], second.id.as_str());
+    assert_eq!(result["result"]["winner"]["shown"], false);
+
+    let (status, _) = send_json(
+        &app,
+        &show,
+        json!({"player_id": second.id}),
+        Some(&first.token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, result) = send_json(
+        &app,
+        &show,
+        json!({"player_id": second.id}),
+        Some(&second.token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["hand"].as_array().unwrap().len(), 2);
+
+    let (status, result) = send_json(
+        &app,
+        &rabbit_hunt,
+        json!({"player_id": first.id}),
+        Some(&first.token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["board"].as_array().unwrap().len(), 5);
+
+    let (_, result) = get_json(&app, &format!("/room/{}/result", room_id)).await;
+    assert_eq!(result["result"]["winner"]["shown"], true);
+    assert_eq!(result["result"]["rabbit_hunt"].as_array().unwrap().len(), 5);
+}
+
+#[tokio::test]
+async fn test_player_turns_off_auto_muck() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    let (first, _) = start_game(&app, &room_id, &players).await;
+    let uri = format!("/room/{}/auto-muck", room_id);
+    let bob = &players[1];
+
+    let (status, _) = send_json(
+        &app,
+        &uri,
+        json!({"player_id": bob.id, "enabled": false}),
+        Some(&players[0].token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, result) = send_json(
+        &app,
+        &uri,
+        json!({"player_id": bob.id, "enabled": false}),
+        Some(&bob.token),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["auto_muck"], false);
+
+    let (_, result) = submit_action(&app, &room_id, first, &first.token, json!("Call")).await;
+    let seats = result["game_state"]["players"].as_array().unwrap();
+    let seat = |id: &str| seats.iter().find(|p| p["id"] == id).unwrap()["auto_muck"].clone();
+    assert_eq!(seat(&bob.id), false);
+    assert_eq!(seat(&players[0].id), true);
+}
+
+#[tokio::test]
+async fn test_hand_history_shows_only_revealed_cards() {
+    let state = AppState::new(Arc::new(MemoryStore::default())).with_admin_token(Some("admin"));
+    let app = create_app_with_state(state);
+    let (room_id, players) = setup_room(&app).await;
+    let (first, second) = start_game(&app, &room_id, &players).await;
+    submit_action(&app, &room_id, first, &first.token, json!("Fold")).await;
+
+    let (_, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    let hand_id = result["hands"][0]["hand_id"].as_str().unwrap().to_string();
+    let uri = format!("/hands/{}", hand_id);
+    let hole_cards = |hand: &serde_json::Value, player: &TestPlayer| {
+        let seats = hand["seats"].as_array().unwrap();
+        let seat = seats.iter().find(|s| s["player_id"] == player.id.as_str());
+        seat.unwrap()["hole_cards"].as_array().unwrap().len()
+    };
+
+    // Ninguém abriu as cartas: o público não vê hole cards nem o deck
+    let (status, result) = get_json(&app, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(hole_cards(&result["hand"], first), 0);
+    assert_eq!(hole_cards(&result["hand"], second), 0);
+    assert_eq!(result["hand"]["deck"], json!([]));
+    assert!(result["hand"]["fairness"].get("server_seed").is_none());
+    let (_, ohh) = get_json(&app, &format!("/hands/{}/ohh", hand_id)).await;
+    let dealt = &ohh["ohh"]["rounds"][0]["actions"][0];
+    assert_eq!(dealt["action"], "Dealt Cards");
+    assert!(dealt.get("cards").is_none());
+
+    // Cada jogador vê as próprias cartas
+    let (_, result) = get_json_as(&app, &uri, Some(&first.token)).await;
+    assert_eq!(hole_cards(&result["hand"], first), 2);
+    assert_eq!(hole_cards(&result["hand"], second), 0);
+    assert_eq!(result["hand"]["deck"], json!([]));
+
+    // O admin vê tudo; um token desconhecido é rejeitado
+    let (_, result) = get_json_as(&app, &uri, Some("admin")).await;
+    assert_eq!(hole_cards(&result["hand"], second), 2);
+    assert_eq!(result["hand"]["deck"].as_array().unwrap().len(), 52);
+    let (status, _) = get_json_as(&app, &uri, Some("outro")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // A prova de embaralhamento abre só as cartas que cada um vê
+    let verify = format!("/hands/{}/verify", hand_id);
+    let revealed = |result: &serde_json::Value| {
+        assert_eq!(result["verification"]["valid"], true);
+        assert!(result["proof"].get("server_seed").is_none());
+        result["proof"]["revealed"].as_array().unwrap().len()
+    };
+    let (_, result) = get_json(&app, &verify).await;
+    assert_eq!(revealed(&result), 0);
+    let (_, result) = get_json_as(&app, &verify, Some(&first.token)).await;
+    assert_eq!(revealed(&result), 2);
+    let (_, result) = get_json_as(&app, &verify, Some("admin")).await;
+    assert_eq!(revealed(&result), 52);
+
+    // Depois que o vencedor mostra as cartas elas entram no histórico público
+    send_json(
+        &app,
+        &format!("/room/{}/show", room_id),
+        json!({"player_id": second.id}),
+        Some(&second.token),
+    )
+    .await;
+    let (_, result) = get_json(&app, &uri).await;
+    assert_eq!(hole_cards(&result["hand"], second), 2);
+    assert_eq!(hole_cards(&result["hand"], first), 0);
+    let (_, result) = get_json(&app, &verify).await;
+    assert_eq!(revealed(&result), 2);
+    let (_, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    assert_eq!(hole_cards(&result["hands"][0], first), 0);
+    assert_eq!(result["hands"][0]["shown"], json!([second.id]));
+}
+
+#[tokio::test]
+async fn test_unknown_hand_returns_not_found() {
+    let app = create_test_app().await;
+    let (status, result) = get_json(&app, "/hands/inexistente").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(result["code"], "hand_not_found");
+}
+
+#[tokio::test]
+async fn test_pokerstars_export_download() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    play_hand(&app, &room_id, &players).await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/room/{}/hands/pokerstars", room_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    assert!(response.headers()["content-disposition"]
+        .to_str()
+        .unwrap()
+        .contains(&format!("poker-{}.txt", room_id)));
+
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(text.starts_with("PokerStars Hand #"));
+    assert!(text.contains(&format!("Table '{}' 2-max", room_id)));
+    assert!(text.contains("*** SUMMARY ***"));
+    assert!(!text.contains("Dealt to "));
+
+    // Com o token, o jogador é o herói e só as cartas dele saem em "Dealt to"
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/room/{}/hands/pokerstars", room_id))
+                .header("authorization", format!("Bearer {}", players[0].token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+    assert_eq!(text.matches("Dealt to ").count(), 1);
+    assert!(text.contains("Dealt to Alice ["));
+}
+
+#[tokio::test]
+async fn test_ohh_export_and_import() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    play_hand(&app, &room_id, &players).await;
+
+    let (_, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    let hand = &result["hands"][0];
+    let hand_id = hand["hand_id"].as_str().unwrap();
+
+    let (status, document) = get_json(&app, &format!("/hands/{}/ohh", hand_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(document["ohh"]["game_type"], "Holdem");
+    assert_eq!(document["ohh"]["players"].as_array().unwrap().len(), 2);
+
+    // Reimportar o documento gera uma nova mão com o mesmo board
+    let (status, _) = send_json(&app, "/hands/import", document.clone(), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let token = Some(players[0].token.as_str());
+    let (status, imported) = send_json(&app, "/hands/import", document, token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(imported["hand"]["hand_id"], hand["hand_id"]);
+    assert_eq!(imported["hand"]["board"], hand["board"]);
+
+    let imported_id = imported["hand"]["hand_id"].as_str().unwrap();
+    let (status, _) = get_json(&app, &format!("/hands/{}", imported_id)).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_ohh_import_rejects_invalid_document() {
+    let app = create_test_app().await;
+    let (_, players) = setup_room(&app).await;
+    let mut document: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/ohh_external.json")).unwrap();
+    document["ohh"]["rounds"][1]["cards"][0] = json!("Zz");
+
+    let token = Some(players[0].token.as_str());
+    let (status, result) = send_json(&app, "/hands/import", document, token).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(result["code"], "invalid_hand_history");
+}
+
+#[tokio::test]
+async fn test_ohh_import_is_limited_per_player() {
+    let app = create_test_app().await;
+    let (_, players) = setup_room(&app).await;
+    let document: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/ohh_external.json")).unwrap();
+
+    let (status, result) = send_json(
+        &app,
+        "/hands/import",
+        document.clone(),
+        Some("token-inventado"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(result["code"], "unauthorized");
+
+    let token = Some(players[0].token.as_str());
+    for _ in 0..200 {
+        let (status, _) = send_json(&app, "/hands/import", document.clone(), token).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, result) = send_json(&app, "/hands/import", document.clone(), token).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(result["code"], "import_limit_reached");
+
+    // O limite é de cada jogador
+    let token = Some(players[1].token.as_str());
+    let (status, _) = send_json(&app, "/hands/import", document, token).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_replay_recorded_hand() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+    play_hand(&app, &room_id, &players).await;
+
+    let (_, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    let hand = &result["hands"][0];
+    let hand_id = hand["hand_id"].as_str().unwrap();
+
+    let (status, result) = get_json(&app, &format!("/hands/{}/replay", hand_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    let replay = &result["replay"];
+    assert_eq!(replay["consistent"], true);
+    assert_eq!(replay["divergences"].as_array().unwrap().len(), 0);
+
+    let steps = replay["steps"].as_array().unwrap();
+    let recorded_actions = hand["actions"].as_array().unwrap().len();
+    assert_eq!(steps.len(), recorded_actions - 1); // 2 blinds no passo 0
+    assert_eq!(steps.last().unwrap()["game_state"]["state"], "Finished");
+}
+
+#[tokio::test]
+async fn test_imported_hand_cannot_be_replayed() {
+    let app = create_test_app().await;
+    let (_, players) = setup_room(&app).await;
+    let document: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/ohh_external.json")).unwrap();
+    let token = Some(players[0].token.as_str());
+    let (_, imported) = send_json(&app, "/hands/import", document, token).await;
+    let hand_id = imported["hand"]["hand_id"].as_str().unwrap();
+
+    let (status, result) = get_json(&app, &format!("/hands/{}/replay", hand_id)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(result["code"], "hand_not_replayable");
+}
+
+async fn create_scenario_room(
+    app: &Router,
+    options: serde_json::Value,
+) -> (String, Vec<TestPlayer>) {
+    let mut body = json!({"creator_name": "Alice"});
+    body.as_object_mut()
+        .unwrap()
+        .extend(options.as_object().unwrap().clone());
+    let created = post_json(app, "/room", body).await;
+    let room_id = created["room_id"].as_str().unwrap().to_string();
+    let joined = post_json(
+        app,
+        &format!("/room/{}/join", room_id),
+        json!({"player_name": "Bob"}),
+    )
+    .await;
+    let players = vec![
+        TestPlayer::from_response(&created),
+        TestPlayer::from_response(&joined),
+    ];
+    (room_id, players)
+}
+
+/// Estado do jogo que o jogador recebe ao assinar o stream de eventos
+async fn game_state_seen_by(app: &Router, room_id: &str, player: &TestPlayer) -> serde_json::Value {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/room/{}/events?player_id={}&player_token={}",
+                    room_id, player.id, player.token
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let event = next_event(&mut response.into_body().into_data_stream()).await;
+    let data = event
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .unwrap();
+    serde_json::from_str::<serde_json::Value>(data).unwrap()["data"]["game"].clone()
+}
+
+/// Hole cards de cada assento, na ordem dos assentos
+fn seat_hands(game_state: &serde_json::Value) -> Vec<(String, serde_json::Value)> {
+    game_state["players"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| (p["id"].as_str().unwrap().to_string(), p["hand"].clone()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_stacked_deck_scenario() {
+    let app = create_test_mode_app();
+    let stacked = ["Ah", "Kh", "Ad", "Kd", "As", "7c", "2d", "Ks", "Ac"];
+    let (room_id, players) = create_scenario_room(&app, json!({"stacked_deck": stacked})).await;
+
+    let (_, started) = send_json(
+        &app,
+        &format!("/room/{}/start", room_id),
+        json!({}),
+        Some(&players[0].token),
+    )
+    .await;
+    // As hole cards saem uma por jogador, na ordem dos assentos
+    let expected = [
+        json!([{"suit": "Hearts", "rank": "Ace"}, {"suit": "Diamonds", "rank": "Ace"}]),
+        json!([{"suit": "Hearts", "rank": "King"}, {"suit": "Diamonds", "rank": "King"}]),
+    ];
+    for (seat, (player_id, hand)) in seat_hands(&started["game_state"]).iter().enumerate() {
+        // Quem iniciou só vê as próprias cartas
+        if *player_id == players[0].id {
+            assert_eq!(*hand, expected[seat]);
+        } else {
+            assert_eq!(*hand, json!([]));
+        }
+    }
+    let seen_by_bob = game_state_seen_by(&app, &room_id, &players[1]).await;
+    for (seat, (player_id, hand)) in seat_hands(&seen_by_bob).iter().enumerate() {
+        if *player_id == players[1].id {
+            assert_eq!(*hand, expected[seat]);
+        } else {
+            assert_eq!(*hand, json!([]));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_seeded_rooms_deal_identical_hands() {
+    let app = create_test_mode_app();
+    let mut hands = Vec::new();
+    for _ in 0..2 {
+        let (room_id, players) = create_scenario_room(&app, json!({"seed": 2024})).await;
+        post_json(&app, &format!("/room/{}/start", room_id), json!({})).await;
+        let mut dealt = Vec::new();
+        for player in &players {
+            let state = game_state_seen_by(&app, &room_id, player).await;
+            let (_, hand) = seat_hands(&state)
+                .into_iter()
+                .find(|(id, _)| *id == player.id)
+                .unwrap();
+            assert_eq!(hand.as_array().unwrap().len(), 2);
+            dealt.push(hand);
+        }
+        // A ordem dos assentos pode variar entre as salas
+        dealt.sort_by_key(|hand| hand.to_string());
+        hands.push(dealt);
+    }
+    assert_eq!(hands[0], hands[1]);
+}
+
+#[tokio::test]
+async fn test_players_only_see_their_own_hole_cards() {
+    let app = create_test_app().await;
+    let (room_id, players) = setup_room(&app).await;
+
+    let (current, other) = start_game(&app, &room_id, &players).await;
+
+    for player in &players {
+        let state = game_state_seen_by(&app, &room_id, player).await;
+        for (player_id, hand) in seat_hands(&state) {
+            let cards = hand.as_array().unwrap().len();
+            assert_eq!(cards, if player_id == player.id { 2 } else { 0 });
+        }
+    }
+
+    let (_, result) = submit_action(&app, &room_id, current, &current.token, json!("Call")).await;
+    for (player_id, hand) in seat_hands(&result["game_state"]) {
+        assert_eq!(hand.as_array().unwrap().is_empty(), player_id == other.id);
+    }
+}
+
+#[tokio::test]
+async fn test_seed_requires_test_mode() {
+    let app = create_test_app().await;
+    let (status, result) = send_json(
+        &app,
+        "/room",
+        json!({"creator_name": "Alice", "seed": 1}),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(result["code"], "test_mode_disabled");
+
+    let app = create_test_mode_app();
+    let (status, result) = send_json(
+        &app,
+        "/room",
+        json!({"creator_name": "Alice", "stacked_deck": ["Ah", "Ah"]}),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(result["code"], "invalid_deck");
+}
+
+#[tokio::test]
+async fn test_fair_shuffle_commit_reveal_and_verify() {
+    let app = create_test_app().await;
+    let created = post_json(&app, "/room", json!({"creator_name": "Alice"})).await;
+    let room_id = created["room_id"].as_str().unwrap().to_string();
+    let commitment = created["next_commitment"].as_str().unwrap().to_string();
+    let joined = post_json(
+        &app,
+        &format!("/room/{}/join", room_id),
+        json!({"player_name": "Bob"}),
+    )
+    .await;
+    let players = [
+        TestPlayer::from_response(&created),
+        TestPlayer::from_response(&joined),
+    ];
+
+    // Client seed exige o token do próprio jogador
+    let uri = format!("/room/{}/client-seed", room_id);
+    let body = json!({"player_id": players[1].id, "client_seed": "sorte"});
+    let (status, _) = send_json(&app, &uri, body.clone(), Some(&players[0].token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, result) = send_json(&app, &uri, body, Some(&players[1].token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["next_commitment"], commitment.as_str());
+    assert_eq!(result["next_client_seeds"][&players[1].id], "sorte");
+
+    // Durante a mão só o compromisso é público
+    let started = post_json(&app, &format!("/room/{}/start", room_id), json!({})).await;
+    let fairness = &started["game_state"]["fairness"];
+    assert_eq!(fairness["commitment"], commitment.as_str());
+    assert!(fairness["server_seed"].is_null());
+    assert_ne!(fairness["next_commitment"], commitment.as_str());
+
+    let mut current = started["game_state"]["current_player"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    loop {
+        let player = players.iter().find(|p| p.id == current).unwrap();
+        let (status, result) =
+            submit_action(&app, &room_id, player, &player.token, json!("Call")).await;
+        assert_eq!(status, StatusCode::OK, "{}", result);
+        let game_state = &result["game_state"];
+        if game_state["state"] == "Finished" {
+            // A seed não é revelada nem depois da mão
+            assert!(game_state["fairness"].get("server_seed").is_none());
+            break;
+        }
+        current = game_state["current_player"].as_str().unwrap().to_string();
+    }
+
+    let (_, result) = get_json(&app, &format!("/room/{}/hands", room_id)).await;
+    let hand_id = result["hands"][0]["hand_id"].as_str().unwrap();
+    let (status, result) = get_json(&app, &format!("/hands/{}/verify", hand_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["proof"]["commitment"], commitment.as_str());
+    assert_eq!(result["proof"]["client_seeds"][&players[1].id], "sorte");
+    assert_eq!(result["proof"]["positions"].as_array().unwrap().len(), 52);
+    assert_eq!(result["verification"]["valid"], true);
+    // O board da mão está entre as cartas abertas
+    let revealed = result["proof"]["revealed"].as_array().unwrap();
+    assert!(revealed.len() >= 5);
+}
+
+#[tokio::test]
+async fn test_imported_hand_cannot_be_verified() {
+    let app = create_test_app().await;
+    let (_, players) = setup_room(&app).await;
+    let document: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/ohh_external.json")).unwrap();
+    let token = Some(players[0].token.as_str());
+    let (_, imported) = send_json(&app, "/hands/import", document, token).await;
+    let hand_id = imported["hand"]["hand_id"].as_str().unwrap();
+
+    let (status, result) = get_json(&app, &format!("/hands/{}/verify", hand_id)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(result["code"], "hand_not_verifiable");
+}
@@ -0,0 +1,71 @@
+//! Testes do motor do jogo usado como biblioteca, sem o servidor.
+
+use poker_api::evaluator;
+use poker_api::i18n::Locale;
+use poker_api::models::*;
+use poker_api::notation::parse_cards;
+use poker_api::shuffle::DeckSource;
+
+fn cards(notation: &str) -> Vec<Card> {
+    parse_cards(notation).unwrap()
+}
+
+fn players(count: usize) -> Vec<Player> {
+    (1..=count)
+        .map(|i| Player {
+            id: format!("bot{}", i),
+            name: format!("Bot {}", i),
+            chips: 1000,
+            hand: Vec::new(),
+            current_bet: 0,
+            is_folded: false,
+            is_all_in: false,
+        })
+        .collect()
+}
+
+#[test]
+fn test_bot_plays_a_hand_to_showdown() {
+    let mut game = Game::new(players(3));
+    game.deck_source = DeckSource::Seeded { seed: 11 };
+    game.start_round().unwrap();
+
+    while game.state != GameState::Finished {
+        let player = &game.players[game.current_player_index];
+        let action = if game.current_bet > player.current_bet {
+            PlayerAction::Call
+        } else {
+            PlayerAction::Check
+        };
+        let player_id = player.id.clone();
+        game.process_action(&player_id, action).unwrap();
+    }
+
+    let history = game.hand_history.clone().unwrap();
+    assert!(history.is_finished());
+    assert_eq!(history.board.cards().len(), 5);
+    assert_eq!(history.showdown.len(), 3);
+    let total: u32 = game.players.iter().map(|p| p.chips).sum();
+    assert_eq!(total, 3000);
+
+    let text = poker_api::pokerstars::export_hand(&history, "bots", Some("bot1"));
+    assert!(text.contains("*** SHOW DOWN ***"));
+}
+
+#[test]
+fn test_evaluator_without_a_game() {
+    let board = "Kd 7s 7c 2h 9d";
+    let kings_full = cards(&format!("Kh Kc {}", board));
+    let sevens = cards(&format!("Ah Qd {}", board));
+
+    assert_eq!(
+        evaluator::compare(&kings_full, &sevens),
+        std::cmp::Ordering::Greater
+    );
+    let evaluation = evaluator::evaluate(&kings_full);
+    assert_eq!(evaluation.rank, HandRank::FullHouse);
+    assert_eq!(
+        evaluator::describe(&evaluation).render(Locale::En),
+        "Full House, Kings full of Sevens"
+    );
+}